@@ -0,0 +1,164 @@
+//! Module containing custom allocator support
+//!
+//! A custom allocator can be registered on an [`Environment`](../environment/struct.Environment.html)
+//! using [`Environment::register_allocator()`](../environment/struct.Environment.html#method.register_allocator).
+//! Sessions created afterwards will use it for the memory this crate asks ONNX Runtime to allocate
+//! on its behalf (for example string input tensors and input/output names).
+//!
+//! An [`OrtAllocator`](struct.OrtAllocator.html) can also be passed to
+//! [`Session::run_with_allocator()`](../session/struct.Session.html#method.run_with_allocator)
+//! so that outputs are allocated from it.
+//!
+//! **NOTE**: The C API of the ONNX Runtime version this crate is built against (1.6) does not allow
+//! replacing the allocators used internally by the execution providers; kernels' intermediate
+//! buffers (and outputs with dynamic shapes) are still allocated by the runtime itself.
+
+use std::{ffi, fmt};
+
+use tracing::debug;
+
+use onnxruntime_sys as sys;
+
+use crate::{
+    error::{status_to_result, OrtError, Result},
+    g_ort,
+    memory::MemoryInfo,
+    AllocatorType, MemType,
+};
+
+/// Trait implemented by Rust allocators that can be handed to ONNX Runtime
+///
+/// Implementations must be thread safe: the runtime can call them from any of its threads.
+pub trait Allocator: Send + Sync {
+    /// Allocate `size` bytes, returning a null pointer on failure
+    fn alloc(&self, size: usize) -> *mut ffi::c_void;
+
+    /// Free memory previously returned by [`alloc()`](#tymethod.alloc)
+    fn free(&self, ptr: *mut ffi::c_void);
+
+    /// Description of the memory returned by the allocator, as reported to ONNX Runtime
+    ///
+    /// Defaults to CPU memory from a device (non-arena) allocator.
+    fn info(&self) -> (AllocatorType, MemType) {
+        (AllocatorType::Device, MemType::Default)
+    }
+}
+
+/// Allocator that ONNX Runtime can allocate tensors from
+///
+/// Either ONNX Runtime's default CPU allocator or a custom [`Allocator`](trait.Allocator.html).
+#[derive(Debug)]
+pub struct OrtAllocator {
+    ptr: *mut sys::OrtAllocator,
+    // Keeps the custom allocator (and thus `ptr`) alive
+    _custom: Option<CustomAllocator>,
+}
+
+// ONNX Runtime's default allocator is thread safe, and custom allocators are `Send + Sync`.
+unsafe impl Send for OrtAllocator {}
+unsafe impl Sync for OrtAllocator {}
+
+impl OrtAllocator {
+    /// ONNX Runtime's default CPU allocator
+    pub fn default_cpu() -> Result<OrtAllocator> {
+        let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
+        let status = unsafe { g_ort().GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr) };
+        status_to_result(status).map_err(OrtError::Allocator)?;
+        assert_ne!(allocator_ptr, std::ptr::null_mut());
+
+        Ok(OrtAllocator {
+            ptr: allocator_ptr,
+            _custom: None,
+        })
+    }
+
+    /// Wrap a custom [`Allocator`](trait.Allocator.html)
+    pub fn from_allocator<A>(allocator: A) -> Result<OrtAllocator>
+    where
+        A: Allocator + 'static,
+    {
+        let custom = CustomAllocator::new(Box::new(allocator))?;
+        Ok(OrtAllocator {
+            ptr: custom.ptr(),
+            _custom: Some(custom),
+        })
+    }
+
+    pub(crate) fn ptr(&self) -> *mut sys::OrtAllocator {
+        self.ptr
+    }
+}
+
+/// Bridge between an [`Allocator`](trait.Allocator.html) trait object and the C API's `OrtAllocator`
+///
+/// ONNX Runtime keeps pointers to the `OrtAllocator`: its state is boxed so that moving the
+/// `CustomAllocator` (e.g. in a growing `Vec`) doesn't invalidate them.
+pub(crate) struct CustomAllocator {
+    state: Box<CustomAllocatorState>,
+}
+
+/// The `OrtAllocator` must be the first field so that the pointer ONNX Runtime passes back to the
+/// callbacks can be cast to the whole struct.
+#[repr(C)]
+struct CustomAllocatorState {
+    ort_allocator: sys::OrtAllocator,
+    memory_info: MemoryInfo,
+    allocator: Box<dyn Allocator>,
+}
+
+// The memory info is never mutated after creation and the wrapped allocator is `Send + Sync`.
+unsafe impl Send for CustomAllocator {}
+unsafe impl Sync for CustomAllocator {}
+
+impl fmt::Debug for CustomAllocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomAllocator")
+            .field("memory_info", &self.state.memory_info)
+            .finish()
+    }
+}
+
+impl CustomAllocator {
+    pub(crate) fn new(allocator: Box<dyn Allocator>) -> Result<CustomAllocator> {
+        debug!("Creating new custom allocator.");
+        let (allocator_type, memory_type) = allocator.info();
+        let memory_info = MemoryInfo::new(allocator_type, memory_type)?;
+
+        Ok(CustomAllocator {
+            state: Box::new(CustomAllocatorState {
+                ort_allocator: sys::OrtAllocator {
+                    version: sys::ORT_API_VERSION,
+                    Alloc: Some(custom_alloc),
+                    Free: Some(custom_free),
+                    Info: Some(custom_info),
+                },
+                memory_info,
+                allocator,
+            }),
+        })
+    }
+
+    /// Pointer to pass to the C API wherever an `OrtAllocator` is expected
+    ///
+    /// The pointer is valid for as long as the `CustomAllocator` is alive.
+    pub(crate) fn ptr(&self) -> *mut sys::OrtAllocator {
+        &self.state.ort_allocator as *const sys::OrtAllocator as *mut sys::OrtAllocator
+    }
+}
+
+extern "C" fn custom_alloc(this: *mut sys::OrtAllocator, size: u64) -> *mut ffi::c_void {
+    let this = unsafe { &*(this as *const CustomAllocatorState) };
+    this.allocator.alloc(size as usize)
+}
+
+extern "C" fn custom_free(this: *mut sys::OrtAllocator, ptr: *mut ffi::c_void) {
+    let this = unsafe { &*(this as *const CustomAllocatorState) };
+    if !ptr.is_null() {
+        this.allocator.free(ptr);
+    }
+}
+
+extern "C" fn custom_info(this: *const sys::OrtAllocator) -> *const sys::OrtMemoryInfo {
+    let this = unsafe { &*(this as *const CustomAllocatorState) };
+    this.memory_info.ptr
+}
@@ -0,0 +1,301 @@
+//! Module loading deployment bundles: a model and the configuration of its preprocessing
+//!
+//! A bundle is a directory holding the model, `model.onnx`, and a `preprocess.json` sidecar
+//! describing how raw inputs are normalized, as often distributed with models of the
+//! [ONNX Model Zoo](https://github.com/onnx/models):
+//!
+//! ```json
+//! {
+//!     "dtype": "float",
+//!     "shape": [null, 3, 224, 224],
+//!     "layout": "nchw",
+//!     "scale": 0.00392156862745098,
+//!     "mean": [0.485, 0.456, 0.406],
+//!     "std": [0.229, 0.224, 0.225]
+//! }
+//! ```
+//!
+//! See [`PreprocessConfig`](struct.PreprocessConfig.html) for the meaning of each field.
+//!
+//! Enabled with the `bundle` feature.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ndarray::{Array, Axis, IxDyn};
+use serde::Deserialize;
+
+use crate::{
+    error::{BundleError, NormalizationError, OrtError, Result},
+    preprocessor::{PreprocessedSession, Preprocessor},
+    session::SessionBuilder,
+    tensor::TensorElementDataType,
+};
+
+/// File name of the model in a bundle directory
+pub const MODEL_FILE_NAME: &str = "model.onnx";
+/// File name of the preprocessing configuration in a bundle directory
+pub const PREPROCESS_FILE_NAME: &str = "preprocess.json";
+
+/// Configuration of the preprocessing of a model's input, read from `preprocess.json`
+///
+/// Each value `x` of channel `c` becomes `(x * scale - mean[c]) / std[c]`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PreprocessConfig {
+    /// Element type of the model's input, as named by ONNX; only `float` is supported
+    pub dtype: String,
+    /// Shape of the model's input, with `null` for dynamic dimensions (like the batch size)
+    pub shape: Vec<Option<usize>>,
+    /// Name of each dimension of the input, one letter per dimension, `c` marking the channels
+    /// (e.g. `nchw` or `nhwc`)
+    pub layout: String,
+    /// Factor applied to the raw values first, `1` if omitted
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Per channel mean subtracted from the scaled values
+    pub mean: Vec<f32>,
+    /// Per channel standard deviation dividing the centered values
+    pub std: Vec<f32>,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+impl PreprocessConfig {
+    /// Index of the channel dimension
+    fn channel_axis(&self) -> Result<usize> {
+        let layout = self.layout.to_ascii_lowercase();
+        let invalid = || OrtError::Bundle(BundleError::InvalidLayout(self.layout.clone()));
+        if layout.chars().count() != self.shape.len() || layout.matches('c').count() != 1 {
+            return Err(invalid());
+        }
+        layout.chars().position(|d| d == 'c').ok_or_else(invalid)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.dtype != "float" {
+            return Err(OrtError::Bundle(BundleError::UnsupportedDataType(
+                self.dtype.clone(),
+            )));
+        }
+        let channel_axis = self.channel_axis()?;
+        if self.std.len() != self.mean.len() {
+            return Err(OrtError::Normalization(NormalizationError::ChannelCount {
+                expected: self.mean.len(),
+                actual: self.std.len(),
+            }));
+        }
+        if let Some(channels) = self.shape[channel_axis] {
+            if channels != self.mean.len() {
+                return Err(OrtError::Normalization(NormalizationError::ChannelCount {
+                    expected: self.mean.len(),
+                    actual: channels,
+                }));
+            }
+        }
+        if let Some(channel) = self.std.iter().position(|&s| s == 0.0) {
+            return Err(OrtError::Normalization(
+                NormalizationError::ZeroStandardDeviation { channel },
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Preprocessor for PreprocessConfig {
+    type Input = f32;
+    type Output = f32;
+
+    fn preprocess(&self, inputs: Vec<Array<f32, IxDyn>>) -> Result<Vec<Array<f32, IxDyn>>> {
+        let channel_axis = self.channel_axis()?;
+        inputs
+            .into_iter()
+            .map(|mut input| {
+                let matches_shape = input.ndim() == self.shape.len()
+                    && input
+                        .shape()
+                        .iter()
+                        .zip(&self.shape)
+                        .all(|(&actual, expected)| expected.is_none_or(|d| d == actual));
+                if !matches_shape {
+                    return Err(OrtError::Bundle(BundleError::InputShape {
+                        expected: self.shape.clone(),
+                        actual: input.shape().to_vec(),
+                    }));
+                }
+                let channels = input.len_of(Axis(channel_axis));
+                if channels != self.mean.len() {
+                    return Err(OrtError::Normalization(NormalizationError::ChannelCount {
+                        expected: self.mean.len(),
+                        actual: channels,
+                    }));
+                }
+
+                for (c, mut channel) in input.axis_iter_mut(Axis(channel_axis)).enumerate() {
+                    let (mean, std) = (self.mean[c], self.std[c]);
+                    channel.mapv_inplace(|x| (x * self.scale - mean) / std);
+                }
+                Ok(input)
+            })
+            .collect()
+    }
+}
+
+/// A model and the configuration of its preprocessing, loaded from a bundle directory
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # use onnxruntime::{bundle::ModelBundle, environment::Environment, ndarray::Array};
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let environment = Environment::builder().build()?;
+/// let bundle = ModelBundle::from_dir("resnet50")?;
+/// let session = bundle.load(environment.new_session_builder()?.with_number_threads(1)?)?;
+/// // Raw pixel values, normalized by the session
+/// let pixels = Array::<f32, _>::zeros((1, 3, 224, 224));
+/// let outputs = session.run(vec![pixels])?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelBundle {
+    /// Path of the model file
+    pub model_path: PathBuf,
+    /// Preprocessing of the model's input
+    pub config: PreprocessConfig,
+}
+
+impl ModelBundle {
+    /// Read the bundle stored in the directory `path`
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the directory doesn't contain both files, or if the configuration
+    /// is invalid (unknown fields, unsupported `dtype`, a `layout` not matching the `shape`...).
+    pub fn from_dir<P>(path: P) -> Result<ModelBundle>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let model_path = path.join(MODEL_FILE_NAME);
+        if !model_path.exists() {
+            return Err(OrtError::FileDoesNotExists {
+                filename: model_path,
+            });
+        }
+
+        let config_path = path.join(PREPROCESS_FILE_NAME);
+        let config = fs::read(&config_path).map_err(|source| {
+            OrtError::Bundle(BundleError::ReadConfig {
+                path: config_path.clone(),
+                source,
+            })
+        })?;
+        let config: PreprocessConfig = serde_json::from_slice(&config)
+            .map_err(|error| OrtError::Bundle(BundleError::ParseConfig(error)))?;
+        config.validate()?;
+
+        Ok(ModelBundle { model_path, config })
+    }
+
+    /// Load the model with `builder` (which sets the session's options) and attach the
+    /// preprocessing to the session
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the model can't be loaded, or if it doesn't have exactly one
+    /// input, of the configured element type.
+    pub fn load<'a>(
+        self,
+        builder: SessionBuilder<'a>,
+    ) -> Result<PreprocessedSession<'a, PreprocessConfig>> {
+        let session = builder.with_model_from_file(self.model_path)?;
+        if session.inputs.len() != 1 {
+            return Err(OrtError::Bundle(BundleError::InputCount(
+                session.inputs.len(),
+            )));
+        }
+        if session.inputs[0].input_type != TensorElementDataType::Float {
+            return Err(OrtError::DataTypeMismatch {
+                expected: TensorElementDataType::Float,
+                actual: session.inputs[0].input_type,
+            });
+        }
+        Ok(session.with_preprocessor(self.config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::arr3;
+    use test_env_log::test;
+
+    fn config() -> PreprocessConfig {
+        serde_json::from_str(
+            r#"{
+                "dtype": "float",
+                "shape": [2, 1, null],
+                "layout": "CHW",
+                "scale": 0.5,
+                "mean": [1.0, 0.0],
+                "std": [1.0, 2.0]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn normalizes_channels() {
+        let config = config();
+        config.validate().unwrap();
+        let input = arr3(&[[[2.0_f32, 4.0]], [[2.0, 4.0]]]).into_dyn();
+        let output = config.preprocess(vec![input]).unwrap();
+        assert_eq!(output[0], arr3(&[[[0.0, 1.0]], [[0.5, 1.0]]]).into_dyn());
+    }
+
+    #[test]
+    fn rejects_invalid_configs() {
+        let invalid = PreprocessConfig {
+            layout: String::from("hw"),
+            ..config()
+        };
+        assert!(matches!(
+            invalid.validate(),
+            Err(OrtError::Bundle(BundleError::InvalidLayout(_)))
+        ));
+
+        let invalid = PreprocessConfig {
+            dtype: String::from("uint8"),
+            ..config()
+        };
+        assert!(matches!(
+            invalid.validate(),
+            Err(OrtError::Bundle(BundleError::UnsupportedDataType(_)))
+        ));
+
+        let invalid = PreprocessConfig {
+            shape: vec![Some(3), Some(1), None],
+            ..config()
+        };
+        assert!(matches!(
+            invalid.validate(),
+            Err(OrtError::Normalization(NormalizationError::ChannelCount {
+                expected: 2,
+                actual: 3
+            }))
+        ));
+    }
+
+    #[test]
+    fn rejects_inputs_of_other_shapes() {
+        let input = arr3(&[[[2.0_f32, 4.0], [2.0, 4.0]]]).into_dyn();
+        assert!(matches!(
+            config().preprocess(vec![input]),
+            Err(OrtError::Bundle(BundleError::InputShape { .. }))
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+//! Module decompressing models, see [`SessionBuilder::with_model_from_compressed_reader()`](../session/struct.SessionBuilder.html#method.with_model_from_compressed_reader)
+
+use crate::{OrtError, Result};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Decompress a model compressed with gzip or zstd, detected by their magic bytes
+///
+/// Other data (e.g. an uncompressed model) is returned as is.
+pub(crate) fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        decompress_gzip(&bytes)
+    } else if bytes.starts_with(ZSTD_MAGIC) {
+        decompress_zstd(&bytes)
+    } else {
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "gzip")]
+fn decompress_gzip(bytes: &[u8]) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut model_bytes = Vec::new();
+    flate2::read::GzDecoder::new(bytes)
+        .read_to_end(&mut model_bytes)
+        .map_err(OrtError::ModelRead)?;
+    Ok(model_bytes)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn decompress_gzip(_bytes: &[u8]) -> Result<Vec<u8>> {
+    Err(OrtError::CompressionNotEnabled("gzip"))
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(bytes: &[u8]) -> Result<Vec<u8>> {
+    zstd::stream::decode_all(bytes).map_err(OrtError::ModelRead)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_bytes: &[u8]) -> Result<Vec<u8>> {
+    Err(OrtError::CompressionNotEnabled("zstd"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn uncompressed_data_is_unchanged() {
+        let bytes = vec![0x08, 0x07, 0x12, 0x0e];
+        assert_eq!(decompress(bytes.clone()).unwrap(), bytes);
+        assert_eq!(decompress(Vec::new()).unwrap(), Vec::<u8>::new());
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn compression_not_enabled() {
+        assert!(matches!(
+            decompress(vec![0x28, 0xb5, 0x2f, 0xfd, 0x00]),
+            Err(OrtError::CompressionNotEnabled("zstd"))
+        ));
+    }
+}
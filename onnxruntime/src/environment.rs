@@ -11,11 +11,14 @@ use tracing::{debug, warn};
 use onnxruntime_sys as sys;
 
 use crate::{
-    error::{status_to_result, OrtError, Result},
+    allocator::{Allocator, CustomAllocator},
+    error::{call_ort, status_to_result, OrtError, Result},
     g_ort,
+    memory::MemoryInfo,
     onnxruntime::custom_logger,
     session::SessionBuilder,
-    LoggingLevel,
+    threading::ThreadingOptions,
+    AllocatorType, LoggingLevel, MemType,
 };
 
 lazy_static! {
@@ -23,13 +26,25 @@ lazy_static! {
         Arc::new(Mutex::new(EnvironmentSingleton {
             name: String::from("uninitialized"),
             env_ptr: AtomicPtr::new(std::ptr::null_mut()),
+            allocators: Vec::new(),
+            cpu_arena_limit: None,
+            global_thread_pool: false,
         }));
+    static ref G_GLOBAL_ENV: Mutex<Option<&'static Environment>> = Mutex::new(None);
 }
 
 #[derive(Debug)]
 struct EnvironmentSingleton {
     name: String,
     env_ptr: AtomicPtr<sys::OrtEnv>,
+    /// Custom allocators registered on the environment; the last one is the active one.
+    ///
+    /// Previously registered allocators are kept alive since sessions might still be using them.
+    allocators: Vec<CustomAllocator>,
+    /// Maximum size of the CPU memory arena registered on the environment, if any
+    cpu_arena_limit: Option<usize>,
+    /// Whether the environment was created with global thread pools
+    global_thread_pool: bool,
 }
 
 /// An [`Environment`](session/struct.Environment.html) is the main entry point of the ONNX Runtime.
@@ -44,6 +59,16 @@ struct EnvironmentSingleton {
 /// to name the environment, only the first name will be considered if many environments
 /// are created.
 ///
+/// An `Environment` is `Send` and `Sync`: ONNX Runtime environments are thread safe, and sessions
+/// can be created from multiple threads.
+///
+/// The recommended pattern is to build a single environment when the program starts and to share
+/// it (cloning an `Environment` is cheap), or to use the [global](#method.global) one. Building
+/// another environment while one is alive doesn't create a second ONNX Runtime environment: the
+/// existing one is returned, its configuration unchanged, and a warning is logged. The ONNX
+/// Runtime environment is released once the last `Environment` is dropped; building one afterwards
+/// creates a new environment with the new configuration.
+///
 /// # Example
 ///
 /// ```no_run
@@ -69,9 +94,34 @@ impl Environment {
         EnvBuilder {
             name: "default".into(),
             log_level: LoggingLevel::Warning,
+            global_thread_pool: None,
         }
     }
 
+    /// Shared environment, created with the default configuration on first use
+    ///
+    /// This spares simple programs from passing an `Environment` around: sessions built from it
+    /// can live for the whole program (`Session<'static>`). Use the [builder](#method.builder)
+    /// for a custom configuration.
+    ///
+    /// The global environment is never dropped. Like any other environment, it is the process'
+    /// single ONNX Runtime environment: if one was already created, it is reused with its
+    /// configuration.
+    pub fn global() -> Result<&'static Environment> {
+        let mut global = G_GLOBAL_ENV
+            .lock()
+            .expect("Failed to acquire lock: another thread panicked?");
+        if let Some(environment) = *global {
+            return Ok(environment);
+        }
+
+        let environment: &'static Environment = Box::leak(Box::new(
+            Environment::builder().with_name("global").build()?,
+        ));
+        *global = Some(environment);
+        Ok(environment)
+    }
+
     /// Return the name of the current environment
     pub fn name(&self) -> String {
         self.env.lock().unwrap().name.to_string()
@@ -81,8 +131,84 @@ impl Environment {
         *self.env.lock().unwrap().env_ptr.get_mut()
     }
 
+    /// Whether the sessions share the environment's [global thread pools](struct.EnvBuilder.html#method.with_global_thread_pool)
+    pub fn has_global_thread_pool(&self) -> bool {
+        self.env.lock().unwrap().global_thread_pool
+    }
+
+    /// Register a custom [`Allocator`](../allocator/trait.Allocator.html) on the environment
+    ///
+    /// Sessions created _after_ the registration will use it for the memory this crate requests
+    /// from ONNX Runtime (string input tensors, input and output names). The allocator is kept
+    /// alive for the lifetime of the environment.
+    ///
+    /// See the [`allocator`](../allocator/index.html) module for the limitations of custom allocators.
+    pub fn register_allocator<A>(&self, allocator: A) -> Result<()>
+    where
+        A: Allocator + 'static,
+    {
+        let custom_allocator = CustomAllocator::new(Box::new(allocator))?;
+        self.env.lock().unwrap().allocators.push(custom_allocator);
+        Ok(())
+    }
+
+    /// Pointer to the last registered custom allocator, if any
+    pub(crate) fn allocator_ptr(&self) -> Option<*mut sys::OrtAllocator> {
+        self.env
+            .lock()
+            .unwrap()
+            .allocators
+            .last()
+            .map(|allocator| allocator.ptr())
+    }
+
+    /// Register a CPU memory arena of at most `max_bytes` on the environment, for the sessions
+    /// with a [memory limit](../session/struct.SessionBuilder.html#method.with_memory_limit)
+    ///
+    /// ONNX Runtime only allows one such arena: registering it again with the same limit does
+    /// nothing, and with a different limit is an error.
+    pub(crate) fn register_cpu_arena(&self, max_bytes: usize) -> Result<()> {
+        let mut environment_guard = self.env.lock().unwrap();
+        match environment_guard.cpu_arena_limit {
+            Some(limit) if limit == max_bytes => return Ok(()),
+            Some(limit) => {
+                return Err(OrtError::MemoryLimitConflict {
+                    limit,
+                    requested: max_bytes,
+                })
+            }
+            None => {}
+        }
+
+        debug!(max_bytes, "Registering CPU memory arena.");
+        let mut arena_cfg: *mut sys::OrtArenaCfg = std::ptr::null_mut();
+        // -1 keeps ONNX Runtime's defaults for the arena extend strategy and chunk sizes
+        unsafe {
+            call_ort(|ort| ort.CreateArenaCfg.unwrap()(max_bytes as _, -1, -1, -1, &mut arena_cfg))
+        }
+        .map_err(OrtError::RegisterAllocator)?;
+        assert_ne!(arena_cfg, std::ptr::null_mut());
+
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default)?;
+        let env_ptr: *mut sys::OrtEnv = *environment_guard.env_ptr.get_mut();
+        let result = unsafe {
+            call_ort(|ort| {
+                ort.CreateAndRegisterAllocator.unwrap()(env_ptr, memory_info.ptr, arena_cfg)
+            })
+        };
+        unsafe { g_ort().ReleaseArenaCfg.unwrap()(arena_cfg) };
+        result.map_err(OrtError::RegisterAllocator)?;
+
+        environment_guard.cpu_arena_limit = Some(max_bytes);
+        Ok(())
+    }
+
     #[tracing::instrument]
-    fn new(name: String, log_level: LoggingLevel) -> Result<Environment> {
+    fn new(
+        name: String,
+        log_level: LoggingLevel,
+        global_thread_pool: Option<ThreadingOptions>,
+    ) -> Result<Environment> {
         // NOTE: Because 'G_ENV' is a lazy_static, locking it will, initially, create
         //      a new Arc<Mutex<EnvironmentSingleton>> with a strong count of 1.
         //      Cloning it to embed it inside the 'Environment' to return
@@ -100,18 +226,39 @@ impl Environment {
             // FIXME: What should go here?
             let logger_param: *mut std::ffi::c_void = std::ptr::null_mut();
 
-            let cname = CString::new(name.clone()).unwrap();
-
-            let create_env_with_custom_logger = g_ort().CreateEnvWithCustomLogger.unwrap();
-            let status = {
-                unsafe {
-                    create_env_with_custom_logger(
-                        logging_function,
-                        logger_param,
-                        log_level.into(),
-                        cname.as_ptr(),
-                        &mut env_ptr,
-                    )
+            let cname =
+                CString::new(name.clone()).map_err(|_| OrtError::EnvironmentName(name.clone()))?;
+
+            let status = match &global_thread_pool {
+                None => {
+                    let create_env_with_custom_logger = g_ort().CreateEnvWithCustomLogger.unwrap();
+                    unsafe {
+                        create_env_with_custom_logger(
+                            logging_function,
+                            logger_param,
+                            log_level.into(),
+                            cname.as_ptr(),
+                            &mut env_ptr,
+                        )
+                    }
+                }
+                Some(threading_options) => {
+                    let threading_options_ptr = threading_options.to_ort()?;
+                    let create_env = g_ort()
+                        .CreateEnvWithCustomLoggerAndGlobalThreadPools
+                        .unwrap();
+                    let status = unsafe {
+                        create_env(
+                            logging_function,
+                            logger_param,
+                            log_level.into(),
+                            cname.as_ptr(),
+                            threading_options_ptr,
+                            &mut env_ptr,
+                        )
+                    };
+                    unsafe { g_ort().ReleaseThreadingOptions.unwrap()(threading_options_ptr) };
+                    status
                 }
             };
 
@@ -124,6 +271,7 @@ impl Environment {
 
             *g_env_ptr = env_ptr;
             environment_guard.name = name;
+            environment_guard.global_thread_pool = global_thread_pool.is_some();
 
             // NOTE: Cloning the lazy_static 'G_ENV' will increase its strong count by one.
             //       If this 'Environment' is the only one in the process, the strong count
@@ -186,6 +334,9 @@ impl Drop for Environment {
 
             environment_guard.env_ptr = AtomicPtr::new(std::ptr::null_mut());
             environment_guard.name = String::from("uninitialized");
+            environment_guard.allocators.clear();
+            environment_guard.cpu_arena_limit = None;
+            environment_guard.global_thread_pool = false;
         }
     }
 }
@@ -201,6 +352,7 @@ impl Drop for Environment {
 pub struct EnvBuilder {
     name: String,
     log_level: LoggingLevel,
+    global_thread_pool: Option<ThreadingOptions>,
 }
 
 impl EnvBuilder {
@@ -229,9 +381,41 @@ impl EnvBuilder {
         self
     }
 
+    /// Share global thread pools, configured with `options`, between all the sessions of the
+    /// environment
+    ///
+    /// Sessions then don't create their own thread pools: this saves threads when many sessions
+    /// are loaded, and the [spin policy](../threading/struct.ThreadingOptions.html#method.with_spinning)
+    /// can only be set for the global thread pools. The sessions' own thread settings (such as
+    /// [`with_number_threads()`](../session/struct.SessionBuilder.html#method.with_number_threads))
+    /// are ignored.
+    ///
+    /// **NOTE**: Since ONNX can only define one environment per process,
+    /// creating multiple environments using multiple `EnvBuilder` will
+    /// end up re-using the same environment internally; a new one will _not_
+    /// be created. New parameters will be ignored.
+    pub fn with_global_thread_pool(mut self, options: ThreadingOptions) -> EnvBuilder {
+        self.global_thread_pool = Some(options);
+        self
+    }
+
     /// Commit the configuration to a new [`Environment`](environment/struct.Environment.html)
+    ///
+    /// If an environment is already alive in the process, it is returned instead and this
+    /// configuration is ignored (see [`Environment`](struct.Environment.html)).
+    ///
+    /// # Errors
+    ///
+    /// [`OrtError::EnvironmentName`](../error/enum.OrtError.html#variant.EnvironmentName) is
+    /// returned if the name contains a null byte, and
+    /// [`OrtError::Environment`](../error/enum.OrtError.html#variant.Environment) if ONNX Runtime
+    /// fails to create the environment.
     pub fn build(self) -> Result<Environment> {
-        Environment::new(self.name, self.log_level)
+        crate::check_ort_api()?;
+        if self.name.contains('\0') {
+            return Err(OrtError::EnvironmentName(self.name));
+        }
+        Environment::new(self.name, self.log_level, self.global_thread_pool)
     }
 }
 
@@ -294,6 +478,30 @@ mod tests {
         assert_eq!(G_ENV.env_ptr(), std::ptr::null_mut());
     }
 
+    #[test]
+    fn env_creation_errors() {
+        let _run_lock = CONCURRENT_TEST_RUN.single_test_run();
+
+        let env = Environment::builder()
+            .with_name("env_creation_errors")
+            .build()
+            .unwrap();
+        assert!(G_ENV.is_initialized());
+
+        // An invalid name is an error, not a panic, even when the environment already exists
+        assert!(matches!(
+            Environment::builder().with_name("env\0creation").build(),
+            Err(OrtError::EnvironmentName(name)) if name == "env\0creation"
+        ));
+        std::mem::drop(env);
+
+        assert!(matches!(
+            Environment::builder().with_name("env\0creation").build(),
+            Err(OrtError::EnvironmentName(_))
+        ));
+        assert!(!G_ENV.is_initialized());
+    }
+
     #[ignore]
     #[test]
     fn sequential_environment_creation() {
@@ -321,7 +529,7 @@ mod tests {
         let _concurrent_run_lock_guard = CONCURRENT_TEST_RUN.single_test_run();
 
         let initial_name = String::from("concurrent_environment_creation");
-        let main_env = Environment::new(initial_name.clone(), LoggingLevel::Warning).unwrap();
+        let main_env = Environment::new(initial_name.clone(), LoggingLevel::Warning, None).unwrap();
         let main_env_ptr = main_env.env_ptr() as u64;
 
         let children: Vec<_> = (0..10)
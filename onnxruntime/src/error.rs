@@ -1,12 +1,15 @@
 //! Module containing error definitions.
 
-use std::{io, path::PathBuf, string};
+use std::{io, path::PathBuf, string, time::Duration};
 
 use thiserror::Error;
 
 use onnxruntime_sys as sys;
 
-use crate::{char_p_to_string, g_ort};
+use crate::{
+    char_p_to_string, g_ort,
+    tensor::{ort_owned_tensor::TensorExtractError, TensorElementDataType, ValueType},
+};
 
 /// Type alias for the `Result`
 pub type Result<T> = std::result::Result<T, OrtError>;
@@ -23,12 +26,52 @@ pub enum OrtError {
     /// An error occurred when creating an ONNX environment
     #[error("Failed to create environment: {0}")]
     Environment(OrtApiError),
+    /// The name of an environment contains a null byte, so it can't be given to ONNX Runtime
+    #[error("Invalid environment name {0:?}: it contains a null byte")]
+    EnvironmentName(String),
     /// Error occurred when creating an ONNX session options
     #[error("Failed to create session options: {0}")]
     SessionOptions(OrtApiError),
+    /// Error occurred when registering an allocator on the environment
+    #[error("Failed to register allocator: {0}")]
+    RegisterAllocator(OrtApiError),
+    /// A memory limit was requested while the environment's CPU memory arena already has another
+    /// one
+    #[error(
+        "The environment's memory limit is already {limit} bytes, cannot set it to {requested}"
+    )]
+    MemoryLimitConflict {
+        /// The limit of the environment's CPU memory arena
+        limit: usize,
+        /// The requested limit
+        requested: usize,
+    },
+    /// Error occurred when loading a library of custom operators
+    #[error("Failed to register custom ops library {path:?}: {error}")]
+    CustomOpsLibrary {
+        /// Path of the library
+        path: PathBuf,
+        /// Error returned by ONNX Runtime
+        error: OrtApiError,
+    },
     /// Error occurred when creating an ONNX session
     #[error("Failed to create session: {0}")]
     Session(OrtApiError),
+    /// ONNX Runtime logged warnings while loading the model in
+    /// [strict mode](../session/struct.SessionBuilder.html#method.with_strict_load)
+    #[error("Warnings logged while loading the model: {}", .0.join("; "))]
+    StrictLoadWarning(Vec<String>),
+    /// Error occurred when listing the execution providers available in ONNX Runtime
+    #[error("Failed to get available execution providers: {0}")]
+    AvailableProviders(OrtApiError),
+    /// The inputs' dynamic leading dimensions cannot be fixed to a common batch size, as they are
+    /// not a single named dimension
+    #[error("Cannot fix the batch size: inputs have different or unnamed dynamic leading dimensions {dimensions:?}")]
+    InconsistentBatchDimension {
+        /// Name of each input with a dynamic leading dimension, and the name of that dimension
+        /// (empty when unnamed)
+        dimensions: Vec<(String, String)>,
+    },
     /// Error occurred when creating an ONNX allocator
     #[error("Failed to get allocator: {0}")]
     Allocator(OrtApiError),
@@ -62,6 +105,9 @@ pub enum OrtError {
     /// Error occurred when creating CPU memory information
     #[error("Failed to get dimensions: {0}")]
     CreateCpuMemoryInfo(OrtApiError),
+    /// Error occurred when reading the description of a memory
+    #[error("Failed to get memory info: {0}")]
+    GetMemoryInfo(OrtApiError),
     /// Error occurred when creating ONNX tensor
     #[error("Failed to create tensor: {0}")]
     CreateTensor(OrtApiError),
@@ -77,9 +123,52 @@ pub enum OrtError {
     /// Error occurred when getting tensor type and shape
     #[error("Failed to get tensor type and shape: {0}")]
     GetTensorTypeAndShape(OrtApiError),
+    /// Error occurred when getting the kind of an ONNX type information
+    #[error("Failed to get ONNX type from type info: {0}")]
+    GetOnnxTypeFromTypeInfo(OrtApiError),
+    /// Error occurred when getting the element type of a sequence or map type information
+    #[error("Failed to get sequence or map element type: {0}")]
+    GetElementType(OrtApiError),
+    /// Error occurred when getting the kind of an ONNX value
+    #[error("Failed to get value type: {0}")]
+    GetValueType(OrtApiError),
+    /// Error occurred when counting the elements of a sequence or map
+    #[error("Failed to get value count: {0}")]
+    GetValueCount(OrtApiError),
+    /// Error occurred when getting an element of a sequence or map
+    #[error("Failed to get value: {0}")]
+    GetValue(OrtApiError),
+    /// The model uses a kind of value that is not supported
+    #[error("Unsupported value type: {0:?}")]
+    UnsupportedValueType(ValueType),
+    /// Error occurred when getting the model's metadata
+    #[error("Failed to get model metadata: {0}")]
+    ModelMetadata(OrtApiError),
+    /// Error occurred when getting the start time of the session's profiling
+    #[error("Failed to get profiling start time: {0}")]
+    ProfilingStartTime(OrtApiError),
     /// Error occurred when ONNX inference operation was called
     #[error("Failed to run: {0}")]
     Run(OrtApiError),
+    /// Error occurred when creating or configuring the options of the global thread pools
+    #[error("Failed to set threading options: {0}")]
+    ThreadingOptions(OrtApiError),
+    /// Error occurred when creating or configuring the options of a run
+    #[error("Failed to set run options: {0}")]
+    RunOptions(OrtApiError),
+    /// The inference did not complete before its deadline, see
+    /// [`Session::run_with_timeout()`](../session/struct.Session.html#method.run_with_timeout)
+    #[error("The run did not complete within {0:?}")]
+    Timeout(Duration),
+    /// The inference was cancelled with a
+    /// [`CancellationToken`](../run_options/struct.CancellationToken.html)
+    #[error("The run was cancelled")]
+    Cancelled,
+    /// The inference ran out of memory (for example on the GPU)
+    ///
+    /// Running again with smaller inputs (e.g. a smaller batch) may succeed.
+    #[error("Out of memory while running: {0}")]
+    OutOfMemory(OrtApiError),
     /// Error occurred when extracting data from an ONNX tensor into an C array to be used as an `ndarray::ArrayView`
     #[error("Failed to get tensor data: {0}")]
     GetTensorMutableData(OrtApiError),
@@ -89,6 +178,9 @@ pub enum OrtError {
     /// Error occurred when converting data to a String
     #[error("Data was not UTF-8: {0}")]
     StringFromUtf8Error(#[from] string::FromUtf8Error),
+    /// Error occurred when borrowing data as a `str`
+    #[error("Data was not UTF-8: {0}")]
+    StringUtf8Error(std::str::Utf8Error),
 
     /// Error occurred when downloading a pre-trained ONNX model from the [ONNX Model Zoo](https://github.com/onnx/models)
     #[error("Failed to download ONNX model: {0}")]
@@ -97,6 +189,219 @@ pub enum OrtError {
     /// Dimensions of input data and ONNX model loaded from file do not match
     #[error("Dimensions do not match: {0:?}")]
     NonMatchingDimensions(NonMatchingDimensionsError),
+    /// The model has no input of the given name
+    #[error("Model has no input named {name:?}")]
+    UnknownInput {
+        /// Name of the input given to the inference call
+        name: String,
+    },
+    /// A required input was not given to the inference call
+    #[error("Missing required input {name:?}")]
+    MissingInput {
+        /// Name of the model's input
+        name: String,
+    },
+    /// More inputs were given to the inference call than the model has, optional ones included
+    #[error("Too many inputs: {given} given, the model has {model_input_count}")]
+    TooManyInputs {
+        /// Number of inputs given to the inference call
+        given: usize,
+        /// Number of inputs of the model, optional ones included
+        model_input_count: usize,
+    },
+    /// Token ids given to [`Session::run_ids()`](../session/struct.Session.html#method.run_ids)
+    /// don't have the length of the `input_ids`
+    #[error("{name} has {actual} ids, input_ids has {expected}")]
+    IdsLengthMismatch {
+        /// Name of the input with the wrong length
+        name: &'static str,
+        /// Length of the `input_ids`
+        expected: usize,
+        /// Length of the input
+        actual: usize,
+    },
+    /// The data type of a tensor doesn't match the requested one
+    #[error("Data type mismatch: expected {expected:?}, was {actual:?}")]
+    DataTypeMismatch {
+        /// The requested type
+        expected: TensorElementDataType,
+        /// The actual type of the tensor
+        actual: TensorElementDataType,
+    },
+    /// Casting an input to the element type expected by the model changed some of its values
+    #[error("Casting input {input:?} from {from:?} to {to:?} changed {changed} element(s)")]
+    LossyCast {
+        /// Name of the input
+        input: String,
+        /// The element type of the given array
+        from: TensorElementDataType,
+        /// The element type expected by the model
+        to: TensorElementDataType,
+        /// Number of elements whose value changed
+        changed: usize,
+    },
+    /// The kind of a value (tensor, sequence or map) doesn't match the requested one
+    #[error("Value type mismatch: expected {expected:?}, was {actual:?}")]
+    ValueTypeMismatch {
+        /// The requested kind of value
+        expected: ValueType,
+        /// The actual kind of the value
+        actual: ValueType,
+    },
+    /// Image tensor could not be normalized
+    #[error("Failed to normalize: {0}")]
+    Normalization(NormalizationError),
+    /// A model bundle could not be loaded
+    #[cfg(feature = "bundle")]
+    #[error("Invalid model bundle: {0}")]
+    Bundle(BundleError),
+    /// Tensors of this element type can't be converted to a serializable tensor
+    #[cfg(all(feature = "serde", feature = "complex"))]
+    #[error("Tensors of {0:?} are not serializable")]
+    NotSerializable(TensorElementDataType),
+    /// An index to one-hot encode is out of range
+    #[error("Cannot one-hot encode index {index} with {num_classes} classes")]
+    OneHotIndex {
+        /// The out of range index
+        index: usize,
+        /// Number of classes
+        num_classes: usize,
+    },
+    /// A class index to map to a label is out of range
+    #[error("Cannot map index {index} to one of {num_labels} labels")]
+    LabelIndex {
+        /// The out of range index
+        index: i64,
+        /// Number of labels
+        num_labels: usize,
+    },
+    /// Outputs of batched runs could not be concatenated (their shapes differ beyond the first
+    /// axis)
+    #[error("Failed to concatenate batch outputs: {0}")]
+    BatchConcatenation(ndarray::ShapeError),
+    /// The input of a batched run is 0-dimensional: it has no axis to split into batches
+    #[error("Cannot split a 0-dimensional input into batches")]
+    BatchScalarInput,
+    /// The batch size of a batched run exceeds the model's fixed batch dimension
+    #[error("Batch size {batch_size} exceeds the model's fixed batch size of {fixed_batch_size}")]
+    BatchSizeTooLarge {
+        /// Requested batch size
+        batch_size: usize,
+        /// The model's batch dimension
+        fixed_batch_size: usize,
+    },
+    /// An input cannot be synthesized from the model's information
+    #[error("Cannot synthesize input {name:?} of type {input_type:?}")]
+    SynthesizeInput {
+        /// Name of the input
+        name: String,
+        /// Type of the input's elements
+        input_type: TensorElementDataType,
+    },
+    /// The shape of a tensor doesn't match its number of elements
+    ///
+    /// This indicates a corrupt model or an ONNX Runtime bug.
+    #[error("Tensor shape {shape:?} doesn't match its {element_count} elements")]
+    ShapeMismatch {
+        /// Shape of the tensor
+        shape: Vec<usize>,
+        /// Number of elements actually in the tensor
+        element_count: usize,
+    },
+    /// The shape of an input array cannot be given to ONNX Runtime: a dimension doesn't fit in an
+    /// `i64`, or the shape doesn't describe the array's number of elements
+    #[error("Invalid tensor shape {shape:?} for {len} elements")]
+    InvalidTensorShape {
+        /// Shape of the array
+        shape: Vec<usize>,
+        /// Number of elements of the array
+        len: usize,
+    },
+    /// The length of flat data doesn't match the shape it is given
+    #[error("Shape {shape:?} doesn't match the {data_len} elements of the data")]
+    ShapeDataMismatch {
+        /// Requested shape
+        shape: Vec<usize>,
+        /// Number of elements of the data
+        data_len: usize,
+    },
+    /// A tensor expected to hold a single value has a different number of elements
+    #[error("Tensor of shape {shape:?} is not a scalar")]
+    NotScalar {
+        /// Shape of the tensor
+        shape: Vec<usize>,
+    },
+    /// ONNX Runtime returned a null pointer for the data of a non-empty tensor
+    #[error("Tensor data is a null pointer")]
+    NullTensorData,
+    /// The buffer an output is copied into doesn't have the output's number of elements
+    #[error("Output buffer size mismatch: output has {expected} elements, buffer has {actual}")]
+    OutputBufferSizeMismatch {
+        /// Number of elements of the output
+        expected: usize,
+        /// Length of the buffer
+        actual: usize,
+    },
+    /// A percentile outside of `[0, 100]` was requested
+    #[error("Percentile must be between 0 and 100, got {0}")]
+    InvalidPercentile(f64),
+    /// Raw bytes could not be reinterpreted as tensor elements (wrong length or alignment)
+    #[cfg(feature = "bytemuck")]
+    #[error("Failed to cast bytes into tensor elements: {0:?}")]
+    BytesCast(bytemuck::PodCastError),
+    /// A tensor could not be written in the `.npy` format
+    #[cfg(feature = "npy")]
+    #[error("Failed to write tensor to npy: {0}")]
+    NpyWrite(ndarray_npy::WriteNpyError),
+    /// Input tensor could not be built
+    #[error("Failed to build tensor: {0}")]
+    TensorBuilder(TensorBuilderError),
+    /// The model has no initializer with this name
+    #[error("No initializer named {0:?} in the model")]
+    InitializerNotFound(String),
+    /// An initializer was extracted as the wrong element type
+    #[error("Initializer {name:?} is of type {actual:?}, not {expected:?}")]
+    InitializerDataType {
+        /// Name of the initializer
+        name: String,
+        /// The requested element type
+        expected: TensorElementDataType,
+        /// The initializer's element type, `None` if it is not supported
+        actual: Option<TensorElementDataType>,
+    },
+    /// The data of the initializer is stored outside of the model file
+    #[error("Initializer {0:?} is stored as external data, which is not supported")]
+    ExternalInitializer(String),
+    /// Error occurred when reading a model from a stream
+    #[error("Failed to read model: {0}")]
+    ModelRead(io::Error),
+    /// The linked ONNX Runtime library doesn't provide the C API version this crate needs
+    ///
+    /// Which library is linked is decided at build time: see the `ORT_STRATEGY` and
+    /// `ORT_LIB_LOCATION` environment variables of `onnxruntime-sys`.
+    #[error("The linked ONNX Runtime library (version {version}) does not provide the C API version {api_version} this crate needs; use ONNX Runtime 1.6 or newer (see the ORT_STRATEGY and ORT_LIB_LOCATION build environment variables)")]
+    LibraryLoad {
+        /// Version of the linked library
+        version: String,
+        /// C API version requested by the crate
+        api_version: u32,
+    },
+    /// An execution provider could not be appended to the session options
+    ///
+    /// This usually means ONNX Runtime was not built with support for it.
+    #[error("Failed to append the {provider} execution provider (is ONNX Runtime built with it?): {error}")]
+    AppendExecutionProvider {
+        /// Name of the execution provider
+        provider: &'static str,
+        /// Error reported by ONNX Runtime
+        error: OrtApiError,
+    },
+    /// The feature is not available in the ONNX Runtime version this crate is built against
+    #[error("{0} is not supported by the ONNX Runtime version this crate is built against (1.6)")]
+    UnsupportedByOrtVersion(&'static str),
+    /// The model is compressed with a codec whose feature is not enabled
+    #[error("Model is compressed with {0}, enable the '{0}' feature to load it")]
+    CompressionNotEnabled(&'static str),
     /// File does not exists
     #[error("File {filename:?} does not exists")]
     FileDoesNotExists {
@@ -109,11 +414,44 @@ pub enum OrtError {
         /// Path with invalid UTF-8
         path: PathBuf,
     },
+    /// Error occurred when creating an IO binding or binding a value to it
+    #[error("Failed to bind value: {0}")]
+    IoBinding(OrtApiError),
+    /// Strings have no fixed size: string outputs cannot be bound to memory
+    #[error("String outputs cannot be bound to memory")]
+    StringOutputBinding,
+    /// Error occurred when creating a memory information
+    #[error("Failed to create memory info: {0}")]
+    CreateMemoryInfo(OrtApiError),
+    /// The CUDA runtime failed to count the devices, with this `cudaError_t`
+    #[cfg(feature = "cuda")]
+    #[error("Failed to count CUDA devices: CUDA error {0}")]
+    CudaDeviceCount(std::os::raw::c_int),
     /// Attempt to build a Rust `CString` from a null pointer
     #[error("Failed to build CString when original contains null: {0}")]
     CStringNulError(#[from] std::ffi::NulError),
 }
 
+impl From<TensorExtractError> for OrtError {
+    fn from(e: TensorExtractError) -> Self {
+        match e {
+            TensorExtractError::DataTypeMismatch { actual, requested } => {
+                OrtError::DataTypeMismatch {
+                    expected: requested,
+                    actual,
+                }
+            }
+            TensorExtractError::ValueTypeMismatch { actual, requested } => {
+                OrtError::ValueTypeMismatch {
+                    expected: requested,
+                    actual,
+                }
+            }
+            TensorExtractError::OrtError(e) => e,
+        }
+    }
+}
+
 /// Error used when dimensions of input (from model and from inference call)
 /// do not match (as they should).
 #[non_exhaustive]
@@ -141,6 +479,94 @@ pub enum NonMatchingDimensionsError {
     },
 }
 
+/// Error used when an image tensor cannot be normalized
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum NormalizationError {
+    /// The tensor's number of channels does not match the number of means and standard deviations
+    #[error("Expected {expected} channels, tensor has {actual}")]
+    ChannelCount {
+        /// Number of means and standard deviations given
+        expected: usize,
+        /// Number of channels of the tensor
+        actual: usize,
+    },
+    /// A standard deviation is zero, which would make the division undefined
+    #[error("Standard deviation of channel {channel} is zero")]
+    ZeroStandardDeviation {
+        /// Index of the channel
+        channel: usize,
+    },
+}
+
+/// Error used when a [`ModelBundle`](../bundle/struct.ModelBundle.html) cannot be loaded
+#[cfg(feature = "bundle")]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum BundleError {
+    /// The preprocessing configuration could not be read
+    #[error("Failed to read {path:?}: {source}")]
+    ReadConfig {
+        /// Path of the configuration
+        path: PathBuf,
+        /// Error reading the file
+        source: io::Error,
+    },
+    /// The preprocessing configuration is not valid JSON, or doesn't follow the schema
+    #[error("Failed to parse preprocessing configuration: {0}")]
+    ParseConfig(serde_json::Error),
+    /// The configured element type is not supported
+    #[error("Unsupported data type {0:?}, only \"float\" is")]
+    UnsupportedDataType(String),
+    /// The layout doesn't name every dimension of the shape, or doesn't have one channel dimension
+    #[error("Invalid layout {0:?}")]
+    InvalidLayout(String),
+    /// The model doesn't have exactly one input
+    #[error("Bundled models must have a single input, the model has {0}")]
+    InputCount(usize),
+    /// An input doesn't have the configured shape
+    #[error("Expected an input of shape {expected:?}, got {actual:?}")]
+    InputShape {
+        /// Configured shape, `None` for dynamic dimensions
+        expected: Vec<Option<usize>>,
+        /// Shape of the input
+        actual: Vec<usize>,
+    },
+}
+
+/// Error used when filling a [`TensorBuilder`](../tensor/tensor_builder/struct.TensorBuilder.html)
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum TensorBuilderError {
+    /// The data does not hold a whole number of rows
+    #[error("Expected a multiple of {expected} elements, got {actual}")]
+    RowLength {
+        /// Number of elements in a row
+        expected: usize,
+        /// Number of elements given
+        actual: usize,
+    },
+    /// The row is past the end of the tensor
+    #[error("Row {index} is out of bounds, tensor has {rows} rows")]
+    RowOutOfBounds {
+        /// Index of the row
+        index: usize,
+        /// Number of rows of the tensor
+        rows: usize,
+    },
+    /// Some rows were never written
+    #[error("{missing} of {rows} rows were not written")]
+    Incomplete {
+        /// Number of rows not written
+        missing: usize,
+        /// Number of rows of the tensor
+        rows: usize,
+    },
+    /// String tensors can't be written in place: ONNX Runtime manages the memory of their strings
+    #[error("String tensors cannot be built row by row")]
+    StringElements,
+}
+
 /// Error details when ONNX C API fail
 #[non_exhaustive]
 #[derive(Error, Debug)]
@@ -148,11 +574,102 @@ pub enum OrtApiError {
     /// Details as reported by the ONNX C API in case of error
     #[error("Error calling ONNX Runtime C function: {0}")]
     Msg(String),
+    /// Details as reported by the ONNX C API in case of error, when the C API reports a more
+    /// specific kind of error than [`OrtErrorCode::Fail`](enum.OrtErrorCode.html#variant.Fail)
+    #[error("Error calling ONNX Runtime C function ({code:?}): {message}")]
+    MsgWithCode {
+        /// Kind of error
+        code: OrtErrorCode,
+        /// Message describing the error
+        message: String,
+    },
     /// Details as reported by the ONNX C API in case of error cannot be converted to UTF-8
     #[error("Error calling ONNX Runtime C function and failed to convert error message to UTF-8")]
     IntoStringError(std::ffi::IntoStringError),
 }
 
+impl OrtApiError {
+    /// Kind of error reported by ONNX Runtime, to handle some errors programmatically
+    ///
+    /// ```
+    /// # use onnxruntime::{OrtApiError, OrtError, OrtErrorCode};
+    /// fn is_invalid_model(error: &OrtError) -> bool {
+    ///     match error {
+    ///         OrtError::Session(api_error) => matches!(
+    ///             api_error.code(),
+    ///             Some(OrtErrorCode::InvalidProtobuf) | Some(OrtErrorCode::InvalidGraph)
+    ///         ),
+    ///         _ => false,
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// Returns `None` if the error message could not be read, in which case the code isn't either.
+    pub fn code(&self) -> Option<OrtErrorCode> {
+        match self {
+            OrtApiError::Msg(_) => Some(OrtErrorCode::Fail),
+            OrtApiError::MsgWithCode { code, .. } => Some(*code),
+            OrtApiError::IntoStringError(_) => None,
+        }
+    }
+
+    /// Message reported by ONNX Runtime, if it could be read
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            OrtApiError::Msg(message) | OrtApiError::MsgWithCode { message, .. } => Some(message),
+            OrtApiError::IntoStringError(_) => None,
+        }
+    }
+}
+
+/// Kind of error reported by the ONNX C API (`OrtErrorCode`)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum OrtErrorCode {
+    /// No error
+    Ok,
+    /// Generic failure
+    Fail,
+    /// An argument is invalid, for example an input of the wrong type
+    InvalidArgument,
+    /// A file does not exist
+    NoSuchFile,
+    /// No model was loaded
+    NoModel,
+    /// Error in an execution engine
+    EngineError,
+    /// Exception thrown while running
+    RuntimeException,
+    /// The model could not be parsed
+    InvalidProtobuf,
+    /// A model was already loaded
+    ModelLoaded,
+    /// The feature is not implemented
+    NotImplemented,
+    /// The graph of the model is invalid
+    InvalidGraph,
+    /// Failure of an execution provider
+    EpFail,
+}
+
+impl From<sys::OrtErrorCode> for OrtErrorCode {
+    fn from(code: sys::OrtErrorCode) -> Self {
+        match code {
+            sys::OrtErrorCode::ORT_OK => OrtErrorCode::Ok,
+            sys::OrtErrorCode::ORT_FAIL => OrtErrorCode::Fail,
+            sys::OrtErrorCode::ORT_INVALID_ARGUMENT => OrtErrorCode::InvalidArgument,
+            sys::OrtErrorCode::ORT_NO_SUCHFILE => OrtErrorCode::NoSuchFile,
+            sys::OrtErrorCode::ORT_NO_MODEL => OrtErrorCode::NoModel,
+            sys::OrtErrorCode::ORT_ENGINE_ERROR => OrtErrorCode::EngineError,
+            sys::OrtErrorCode::ORT_RUNTIME_EXCEPTION => OrtErrorCode::RuntimeException,
+            sys::OrtErrorCode::ORT_INVALID_PROTOBUF => OrtErrorCode::InvalidProtobuf,
+            sys::OrtErrorCode::ORT_MODEL_LOADED => OrtErrorCode::ModelLoaded,
+            sys::OrtErrorCode::ORT_NOT_IMPLEMENTED => OrtErrorCode::NotImplemented,
+            sys::OrtErrorCode::ORT_INVALID_GRAPH => OrtErrorCode::InvalidGraph,
+            sys::OrtErrorCode::ORT_EP_FAIL => OrtErrorCode::EpFail,
+        }
+    }
+}
+
 /// Error from downloading pre-trained model from the [ONNX Model Zoo](https://github.com/onnx/models).
 #[non_exhaustive]
 #[derive(Error, Debug)]
@@ -189,9 +706,13 @@ impl From<OrtStatusWrapper> for std::result::Result<(), OrtApiError> {
         if status.0.is_null() {
             Ok(())
         } else {
+            let code = unsafe { g_ort().GetErrorCode.unwrap()(status.0) };
             let raw: *const i8 = unsafe { g_ort().GetErrorMessage.unwrap()(status.0) };
             match char_p_to_string(raw) {
-                Ok(msg) => Err(OrtApiError::Msg(msg)),
+                Ok(message) => match OrtErrorCode::from(code) {
+                    OrtErrorCode::Fail => Err(OrtApiError::Msg(message)),
+                    code => Err(OrtApiError::MsgWithCode { code, message }),
+                },
                 Err(err) => match err {
                     OrtError::StringConversion(OrtApiError::IntoStringError(e)) => {
                         Err(OrtApiError::IntoStringError(e))
@@ -217,3 +738,95 @@ where
 {
     status_to_result(f(g_ort()))
 }
+
+/// Convert the status returned by `Run`, singling out memory exhaustion
+///
+/// ONNX Runtime has no dedicated error code for it: allocation failures are reported with a
+/// generic code (`ORT_FAIL`, `ORT_EP_FAIL` or `ORT_RUNTIME_EXCEPTION`), so the message is inspected.
+pub(crate) fn run_status_to_result(status: *const sys::OrtStatus) -> Result<()> {
+    if status.is_null() {
+        return Ok(());
+    }
+
+    status_to_result(status).map_err(|e| {
+        if is_out_of_memory(&e) {
+            OrtError::OutOfMemory(e)
+        } else {
+            OrtError::Run(e)
+        }
+    })
+}
+
+fn is_out_of_memory(error: &OrtApiError) -> bool {
+    const OUT_OF_MEMORY_MESSAGES: &[&str] = &[
+        "out of memory",
+        "cuda_error_out_of_memory",
+        "cudaerrormemoryallocation",
+        "failed to allocate memory",
+        "bad_alloc",
+    ];
+
+    match (error.code(), error.message()) {
+        (
+            Some(OrtErrorCode::Fail | OrtErrorCode::EpFail | OrtErrorCode::RuntimeException),
+            Some(message),
+        ) => {
+            let msg = message.to_lowercase();
+            OUT_OF_MEMORY_MESSAGES
+                .iter()
+                .any(|pattern| msg.contains(pattern))
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+    use test_env_log::test;
+
+    fn run_result(code: sys::OrtErrorCode, msg: &str) -> Result<()> {
+        let msg = CString::new(msg).unwrap();
+        let status = unsafe { g_ort().CreateStatus.unwrap()(code, msg.as_ptr()) };
+        let result = run_status_to_result(status);
+        unsafe { g_ort().ReleaseStatus.unwrap()(status) };
+        result
+    }
+
+    #[test]
+    fn run_out_of_memory() {
+        let result = run_result(
+            sys::OrtErrorCode::ORT_FAIL,
+            "CUDA failure 2: out of memory ; GPU=0 ; hostname=gpu-node ; expr=cudaMalloc((void**)&p, size);",
+        );
+        match result {
+            Err(OrtError::OutOfMemory(OrtApiError::Msg(message))) => {
+                assert!(message.starts_with("CUDA failure 2: out of memory"))
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+
+        let result = run_result(
+            sys::OrtErrorCode::ORT_EP_FAIL,
+            "Failed to allocate memory for requested buffer of size 1073741824",
+        );
+        assert!(matches!(result, Err(OrtError::OutOfMemory(_))));
+    }
+
+    #[test]
+    fn run_other_failure() {
+        let result = run_result(
+            sys::OrtErrorCode::ORT_INVALID_ARGUMENT,
+            "Got invalid dimensions for input: x",
+        );
+        match result {
+            Err(OrtError::Run(error)) => {
+                assert_eq!(error.code(), Some(OrtErrorCode::InvalidArgument))
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+
+        assert!(run_status_to_result(std::ptr::null()).is_ok());
+    }
+}
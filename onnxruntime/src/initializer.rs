@@ -0,0 +1,181 @@
+//! Module reading the initializers (constant tensors, like weights) stored in a model
+//!
+//! ONNX Runtime does not expose the initializers of a loaded session, so they are decoded from the
+//! model's ONNX protobuf encoding instead. This is meant for inspecting models (for example to
+//! verify quantization scales), not for inference.
+
+use std::{fs, mem, path::Path};
+
+use ndarray::{Array, IxDyn};
+
+use crate::{
+    error::{OrtError, Result},
+    model_proto::{
+        read_initializers, TensorProto, TENSOR_DOUBLE_DATA, TENSOR_FLOAT_DATA, TENSOR_INT32_DATA,
+        TENSOR_INT64_DATA, TENSOR_UINT64_DATA,
+    },
+    tensor::{TensorDataToType, TensorElementDataType},
+};
+
+/// Trait implemented by the element types initializers can be extracted as
+pub trait InitializerElement: TensorDataToType + Copy {
+    /// Field of the ONNX `TensorProto` holding the elements when they are not stored as raw data
+    #[doc(hidden)]
+    const TYPED_DATA_FIELD: u32;
+
+    /// Decode an element from its little-endian bytes (`TensorProto.raw_data`)
+    #[doc(hidden)]
+    fn from_le_bytes(bytes: &[u8]) -> Self;
+
+    /// Decode an element from its wire representation in the typed data field
+    #[doc(hidden)]
+    fn from_wire(value: u64) -> Self;
+}
+
+macro_rules! impl_initializer_element {
+    ($type_:ty, $field:expr, |$value:ident| $from_wire:expr) => {
+        impl InitializerElement for $type_ {
+            const TYPED_DATA_FIELD: u32 = $field;
+
+            fn from_le_bytes(bytes: &[u8]) -> Self {
+                let mut le_bytes = [0_u8; mem::size_of::<$type_>()];
+                le_bytes.copy_from_slice(bytes);
+                <$type_>::from_le_bytes(le_bytes)
+            }
+
+            fn from_wire($value: u64) -> Self {
+                $from_wire
+            }
+        }
+    };
+}
+
+impl_initializer_element!(f32, TENSOR_FLOAT_DATA, |value| f32::from_bits(value as u32));
+impl_initializer_element!(f64, TENSOR_DOUBLE_DATA, |value| f64::from_bits(value));
+// Small integers are stored as (sign-extended) `int32_data` elements
+impl_initializer_element!(u8, TENSOR_INT32_DATA, |value| value as u8);
+impl_initializer_element!(i8, TENSOR_INT32_DATA, |value| value as i8);
+impl_initializer_element!(u16, TENSOR_INT32_DATA, |value| value as u16);
+impl_initializer_element!(i16, TENSOR_INT32_DATA, |value| value as i16);
+impl_initializer_element!(i32, TENSOR_INT32_DATA, |value| value as i32);
+impl_initializer_element!(i64, TENSOR_INT64_DATA, |value| value as i64);
+impl_initializer_element!(u32, TENSOR_UINT64_DATA, |value| value as u32);
+impl_initializer_element!(u64, TENSOR_UINT64_DATA, |value| value);
+
+/// The initializers of a model's main graph
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # use onnxruntime::initializer::Initializers;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let initializers = Initializers::from_file("model.onnx")?;
+/// for name in initializers.names() {
+///     println!("{}: {:?}", name, initializers.data_type(name));
+/// }
+/// let scale = initializers.extract::<f32>("scale")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Initializers {
+    initializers: Vec<TensorProto>,
+}
+
+impl Initializers {
+    /// Read the initializers of an ONNX model file
+    pub fn from_file<P>(model_filepath: P) -> Result<Initializers>
+    where
+        P: AsRef<Path>,
+    {
+        let model_filepath = model_filepath.as_ref();
+        if !model_filepath.exists() {
+            return Err(OrtError::FileDoesNotExists {
+                filename: model_filepath.to_path_buf(),
+            });
+        }
+        let model_bytes = fs::read(model_filepath).map_err(OrtError::ModelRead)?;
+        Initializers::from_memory(&model_bytes)
+    }
+
+    /// Read the initializers of an ONNX model held in memory
+    pub fn from_memory(model_bytes: &[u8]) -> Result<Initializers> {
+        let initializers = read_initializers(model_bytes).map_err(OrtError::ModelRead)?;
+        Ok(Initializers { initializers })
+    }
+
+    /// Names of the initializers, in the order the model stores them
+    pub fn names(&self) -> Vec<&str> {
+        self.initializers
+            .iter()
+            .map(|initializer| initializer.name.as_str())
+            .collect()
+    }
+
+    /// Element type of an initializer
+    ///
+    /// Returns `None` if there is no such initializer, or if its type is not supported.
+    pub fn data_type(&self, name: &str) -> Option<TensorElementDataType> {
+        self.find(name).and_then(|initializer| {
+            TensorElementDataType::from_proto_data_type(initializer.data_type)
+        })
+    }
+
+    /// Shape of an initializer, or `None` if there is no such initializer
+    pub fn shape(&self, name: &str) -> Option<&[i64]> {
+        self.find(name)
+            .map(|initializer| initializer.dims.as_slice())
+    }
+
+    /// Copy the elements of an initializer into an array
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if there is no such initializer, if `T` does not match its element
+    /// type, if its data is stored outside of the model file, or if the number of elements does
+    /// not match its shape.
+    pub fn extract<T>(&self, name: &str) -> Result<Array<T, IxDyn>>
+    where
+        T: InitializerElement,
+    {
+        let initializer = self
+            .find(name)
+            .ok_or_else(|| OrtError::InitializerNotFound(name.to_string()))?;
+
+        let data_type = TensorElementDataType::from_proto_data_type(initializer.data_type);
+        if data_type != Some(T::tensor_element_data_type()) {
+            return Err(OrtError::InitializerDataType {
+                name: name.to_string(),
+                expected: T::tensor_element_data_type(),
+                actual: data_type,
+            });
+        }
+        if initializer.external {
+            return Err(OrtError::ExternalInitializer(name.to_string()));
+        }
+
+        let elements: Vec<T> = match &initializer.raw_data {
+            Some(raw_data) => raw_data
+                .chunks_exact(mem::size_of::<T>())
+                .map(T::from_le_bytes)
+                .collect(),
+            None => initializer
+                .typed_data(T::TYPED_DATA_FIELD)
+                .iter()
+                .map(|value| T::from_wire(*value))
+                .collect(),
+        };
+
+        let shape: Vec<usize> = initializer.dims.iter().map(|dim| *dim as usize).collect();
+        let element_count = elements.len();
+        Array::from_shape_vec(IxDyn(&shape), elements).map_err(|_| OrtError::ShapeMismatch {
+            shape,
+            element_count,
+        })
+    }
+
+    fn find(&self, name: &str) -> Option<&TensorProto> {
+        self.initializers
+            .iter()
+            .find(|initializer| initializer.name == name)
+    }
+}
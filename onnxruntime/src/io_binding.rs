@@ -0,0 +1,171 @@
+//! Module binding the inputs and outputs of a session to memory ahead of a run
+//!
+//! With an [`IoBinding`](struct.IoBinding.html), outputs can be written directly to memory the
+//! caller allocated, for example a CUDA buffer feeding the next stage of a GPU pipeline, instead
+//! of being copied to CPU memory. Create one with
+//! [`Session::io_binding()`](../session/struct.Session.html#method.io_binding) and run it with
+//! [`Session::run_with_binding()`](../session/struct.Session.html#method.run_with_binding).
+
+use std::{convert::TryFrom, ffi::CString, fmt::Debug};
+
+use ndarray::Array;
+use tracing::debug;
+
+use onnxruntime_sys as sys;
+
+use crate::{
+    error::{call_ort, OrtError, Result},
+    g_ort,
+    memory::MemoryInfo,
+    tensor::{
+        input_tensor::AsOrtValue, OrtTensor, TensorElementDataType, TypeToTensorElementDataType,
+    },
+};
+
+/// Inputs and outputs bound to a session, to be run with
+/// [`Session::run_with_binding()`](../session/struct.Session.html#method.run_with_binding)
+///
+/// The binding keeps the input tensors alive; it can be run again, for example after updating
+/// the contents of the output buffers' consumers.
+#[derive(Debug)]
+pub struct IoBinding<'s> {
+    pub(crate) ptr: *mut sys::OrtIoBinding,
+    pub(crate) session_ptr: *mut sys::OrtSession,
+    memory_info: &'s MemoryInfo,
+    allocator_ptr: *mut sys::OrtAllocator,
+    inputs: Vec<Box<dyn AsOrtValue + 's>>,
+    outputs: Vec<*mut sys::OrtValue>,
+}
+
+impl<'s> IoBinding<'s> {
+    pub(crate) fn new(
+        session_ptr: *mut sys::OrtSession,
+        memory_info: &'s MemoryInfo,
+        allocator_ptr: *mut sys::OrtAllocator,
+    ) -> Result<IoBinding<'s>> {
+        let mut ptr: *mut sys::OrtIoBinding = std::ptr::null_mut();
+        unsafe { call_ort(|ort| ort.CreateIoBinding.unwrap()(session_ptr, &mut ptr)) }
+            .map_err(OrtError::IoBinding)?;
+        assert_ne!(ptr, std::ptr::null_mut());
+
+        Ok(IoBinding {
+            ptr,
+            session_ptr,
+            memory_info,
+            allocator_ptr,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+        })
+    }
+
+    /// Bind an array, in CPU memory, to the input `name`
+    pub fn bind_input<T, D>(&mut self, name: &str, array: Array<T, D>) -> Result<()>
+    where
+        T: TypeToTensorElementDataType + Debug + Clone + 's,
+        D: ndarray::Dimension + 's,
+    {
+        let tensor = OrtTensor::from_array(self.memory_info, self.allocator_ptr, array.into())?;
+        let name = CString::new(name)?;
+        unsafe {
+            call_ort(|ort| ort.BindInput.unwrap()(self.ptr, name.as_ptr(), tensor.ort_value()))
+        }
+        .map_err(OrtError::IoBinding)?;
+        self.inputs.push(Box::new(tensor));
+        Ok(())
+    }
+
+    /// Bind the output `name` to the memory at `ptr`, which the run then writes the output to
+    ///
+    /// `memory_info` describes where `ptr` lives, for example
+    /// `MemoryInfo::for_device("Cuda", 0)` for a buffer allocated with `cudaMalloc()` on the
+    /// first GPU. The output must have the given `shape` and `data_type`; strings are not
+    /// supported.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to at least `shape`'s number of elements of `data_type`, allocated on the
+    /// device `memory_info` describes. The memory must stay allocated for as long as the binding
+    /// is alive, ONNX Runtime writing to it during every run, and must not be accessed by anything
+    /// else during a run. The binding doesn't take ownership of it: the caller frees it, after the
+    /// binding is dropped.
+    ///
+    /// # Errors
+    ///
+    /// [`OrtError::InvalidTensorShape`](../error/enum.OrtError.html#variant.InvalidTensorShape) is
+    /// returned if the size of the output in bytes overflows, or if a dimension doesn't fit in an
+    /// `i64`.
+    pub unsafe fn bind_output_to_ptr(
+        &mut self,
+        name: &str,
+        memory_info: &MemoryInfo,
+        ptr: *mut std::ffi::c_void,
+        shape: &[usize],
+        data_type: TensorElementDataType,
+    ) -> Result<()> {
+        let element_size = element_size(data_type).ok_or(OrtError::StringOutputBinding)?;
+        let element_count = shape
+            .iter()
+            .try_fold(1_usize, |count, dim| count.checked_mul(*dim));
+        let fits_i64 = shape.iter().all(|dim| i64::try_from(*dim).is_ok());
+        let byte_len = element_count
+            .and_then(|count| count.checked_mul(element_size))
+            .filter(|_| fits_i64)
+            .ok_or_else(|| OrtError::InvalidTensorShape {
+                shape: shape.to_vec(),
+                len: element_count.unwrap_or(usize::MAX),
+            })?;
+        let shape: Vec<i64> = shape.iter().map(|d| *d as i64).collect();
+
+        let mut value_ptr: *mut sys::OrtValue = std::ptr::null_mut();
+        call_ort(|ort| {
+            ort.CreateTensorWithDataAsOrtValue.unwrap()(
+                memory_info.ptr,
+                ptr,
+                byte_len as u64,
+                shape.as_ptr(),
+                shape.len() as u64,
+                data_type.into(),
+                &mut value_ptr,
+            )
+        })
+        .map_err(OrtError::CreateTensorWithData)?;
+        // Released with the binding, even if binding it fails
+        self.outputs.push(value_ptr);
+
+        let name = CString::new(name)?;
+        call_ort(|ort| ort.BindOutput.unwrap()(self.ptr, name.as_ptr(), value_ptr))
+            .map_err(OrtError::IoBinding)
+    }
+}
+
+impl<'s> Drop for IoBinding<'s> {
+    #[tracing::instrument]
+    fn drop(&mut self) {
+        debug!("Dropping the IO binding.");
+        unsafe { g_ort().ReleaseIoBinding.unwrap()(self.ptr) };
+        for value in &self.outputs {
+            unsafe { g_ort().ReleaseValue.unwrap()(*value) };
+        }
+    }
+}
+
+/// Size in bytes of an element, `None` for strings which don't have a fixed size
+fn element_size(data_type: TensorElementDataType) -> Option<usize> {
+    match data_type {
+        TensorElementDataType::Uint8
+        | TensorElementDataType::Int8
+        | TensorElementDataType::Bool => Some(1),
+        TensorElementDataType::Uint16 | TensorElementDataType::Int16 => Some(2),
+        TensorElementDataType::Float
+        | TensorElementDataType::Int32
+        | TensorElementDataType::Uint32 => Some(4),
+        TensorElementDataType::Int64
+        | TensorElementDataType::Double
+        | TensorElementDataType::Uint64 => Some(8),
+        #[cfg(feature = "complex")]
+        TensorElementDataType::Complex64 => Some(8),
+        #[cfg(feature = "complex")]
+        TensorElementDataType::Complex128 => Some(16),
+        TensorElementDataType::String => None,
+    }
+}
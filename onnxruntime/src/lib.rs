@@ -47,7 +47,7 @@
 //! #     .with_name("test")
 //! #     .with_log_level(LoggingLevel::Verbose)
 //! #     .build()?;
-//! let mut session = environment
+//! let session = environment
 //!     .new_session_builder()?
 //!     .with_optimization_level(GraphOptimizationLevel::Basic)?
 //!     .with_number_threads(1)?
@@ -72,7 +72,7 @@ a model can be fetched directly from the [ONNX Model Zoo](https://github.com/onn
 #     .with_name("test")
 #     .with_log_level(LoggingLevel::Verbose)
 #     .build()?;
-let mut session = environment
+let session = environment
     .new_session_builder()?
     .with_optimization_level(GraphOptimizationLevel::Basic)?
     .with_number_threads(1)?
@@ -96,7 +96,7 @@ to download.
 //! #     .with_name("test")
 //! #     .with_log_level(LoggingLevel::Verbose)
 //! #     .build()?;
-//! # let mut session = environment
+//! # let session = environment
 //! #     .new_session_builder()?
 //! #     .with_optimization_level(GraphOptimizationLevel::Basic)?
 //! #     .with_number_threads(1)?
@@ -119,6 +119,7 @@ to download.
 //! example for more details.
 
 use std::{
+    collections::HashSet,
     ffi, ptr,
     sync::{atomic::AtomicPtr, Arc, Mutex},
 };
@@ -127,19 +128,34 @@ use lazy_static::lazy_static;
 
 use onnxruntime_sys as sys;
 
+pub mod allocator;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+mod compression;
 pub mod download;
 pub mod environment;
 pub mod error;
-mod memory;
+pub mod initializer;
+pub mod io_binding;
+pub mod memory;
+pub mod metadata;
+mod model_proto;
+pub mod preprocessor;
+pub mod retry;
+pub mod run_options;
 pub mod session;
 pub mod tensor;
+pub mod threading;
 
 // Re-export
-pub use error::{OrtApiError, OrtError, Result};
+pub use error::{OrtApiError, OrtError, OrtErrorCode, Result};
 use sys::OnnxEnumInt;
 
 // Re-export ndarray as it's part of the public API anyway
 pub use ndarray;
+// Re-export num-complex, whose types are the elements of complex tensors
+#[cfg(feature = "complex")]
+pub use num_complex;
 
 lazy_static! {
     // static ref G_ORT: Arc<Mutex<AtomicPtr<sys::OrtApi>>> =
@@ -168,6 +184,132 @@ fn g_ort() -> sys::OrtApi {
     unsafe { *api_ptr_mut }
 }
 
+/// Version of the ONNX Runtime library the program is linked against, e.g. `"1.6.0"`
+///
+/// This is the version of the library actually in use, which can differ from the version the
+/// `onnxruntime-sys` bindings were generated from (for example with `ORT_STRATEGY=system`).
+pub fn api_version() -> &'static str {
+    let base: *const sys::OrtApiBase = unsafe { sys::OrtGetApiBase() };
+    assert_ne!(base, ptr::null());
+    let version = unsafe { (*base).GetVersionString.unwrap()() };
+    assert_ne!(version, ptr::null());
+    // The string is static in the library
+    unsafe { ffi::CStr::from_ptr(version) }
+        .to_str()
+        .expect("ONNX Runtime version is not valid UTF-8")
+}
+
+/// Names of the execution providers available in the linked ONNX Runtime library, e.g.
+/// `"CUDAExecutionProvider"` or `"CPUExecutionProvider"`
+///
+/// This allows checking that a provider is supported before configuring a session with it, to fall
+/// back to another one instead of failing to build the session. The CPU provider is always
+/// available.
+pub fn available_providers() -> Result<Vec<String>> {
+    let mut providers_ptr: *mut *mut i8 = ptr::null_mut();
+    let mut providers_length: i32 = 0;
+    unsafe {
+        error::call_ort(|ort| {
+            ort.GetAvailableProviders.unwrap()(&mut providers_ptr, &mut providers_length)
+        })
+    }
+    .map_err(OrtError::AvailableProviders)?;
+    assert_ne!(providers_ptr, ptr::null_mut());
+
+    let providers = (0..providers_length as usize)
+        .map(|i| char_p_to_string(unsafe { *providers_ptr.add(i) }))
+        .collect::<Result<Vec<String>>>();
+
+    unsafe {
+        error::call_ort(|ort| {
+            ort.ReleaseAvailableProviders.unwrap()(providers_ptr, providers_length)
+        })
+    }
+    .map_err(OrtError::AvailableProviders)?;
+
+    providers
+}
+
+/// The operators used by an ONNX model, as `(domain, op_type)` pairs, without loading it
+///
+/// The model's protobuf encoding is decoded directly: no session is created, so a model can be
+/// checked against an allowlist of vetted operators before ONNX Runtime ever parses it. The
+/// operators of subgraphs (e.g. the branches of `If` and the bodies of `Loop`) and of the model's
+/// local functions are included. The default ONNX domain is reported as `""`.
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// let model_bytes = std::fs::read("model.onnx")?;
+/// let allowed = ["Conv", "Relu", "MaxPool", "Gemm"];
+/// for (domain, op_type) in onnxruntime::inspect_model_ops(&model_bytes)? {
+///     if !domain.is_empty() || !allowed.contains(&op_type.as_str()) {
+///         return Err(format!("Operator {}::{} is not allowed", domain, op_type).into());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// [`OrtError::ModelRead`](error/enum.OrtError.html#variant.ModelRead) is returned if the bytes
+/// are not a valid ONNX model encoding.
+pub fn inspect_model_ops(model_bytes: &[u8]) -> Result<HashSet<(String, String)>> {
+    model_proto::read_ops(model_bytes).map_err(OrtError::ModelRead)
+}
+
+/// Number of CUDA devices (GPUs) visible to the process
+///
+/// A session is placed on one of them with
+/// [`CudaProviderOptions::device_id`](session/struct.CudaProviderOptions.html#structfield.device_id),
+/// between `0` and this count (excluded). Returns `0` when there is no GPU.
+///
+/// **NOTE**: ONNX Runtime doesn't enumerate devices: they are counted with the CUDA runtime's
+/// `cudaGetDeviceCount()`, so `libcudart` must be available at link time (as it is wherever a
+/// CUDA build of ONNX Runtime runs).
+///
+/// Enabled with the `cuda` feature.
+#[cfg(feature = "cuda")]
+pub fn cuda_device_count() -> Result<usize> {
+    #[link(name = "cudart")]
+    extern "C" {
+        fn cudaGetDeviceCount(count: *mut std::os::raw::c_int) -> std::os::raw::c_int;
+    }
+    const CUDA_SUCCESS: std::os::raw::c_int = 0;
+    const CUDA_ERROR_NO_DEVICE: std::os::raw::c_int = 100;
+
+    let mut count = 0;
+    match unsafe { cudaGetDeviceCount(&mut count) } {
+        CUDA_SUCCESS => Ok(count as usize),
+        CUDA_ERROR_NO_DEVICE => Ok(0),
+        code => Err(OrtError::CudaDeviceCount(code)),
+    }
+}
+
+/// Make sure the linked ONNX Runtime library provides the C API version this crate is built for
+///
+/// The library is linked at build time, so a missing library prevents the program from starting at
+/// all; a library that is too old is only detected here, when its API is requested.
+fn check_ort_api() -> Result<()> {
+    let mut api_ref = G_ORT_API
+        .lock()
+        .expect("Failed to acquire lock: another thread panicked?");
+    let api_ptr: *mut sys::OrtApi = *api_ref.get_mut();
+    check_ort_api_ptr(api_ptr)
+}
+
+fn check_ort_api_ptr(api_ptr: *const sys::OrtApi) -> Result<()> {
+    if api_ptr.is_null() {
+        Err(OrtError::LibraryLoad {
+            version: api_version().to_string(),
+            api_version: sys::ORT_API_VERSION,
+        })
+    } else {
+        Ok(())
+    }
+}
+
 fn char_p_to_string(raw: *const i8) -> Result<String> {
     let c_string = unsafe { ffi::CStr::from_ptr(raw as *mut i8).to_owned() };
     match c_string.into_string() {
@@ -181,11 +323,28 @@ mod onnxruntime {
     //! Module containing a custom logger, used to catch the runtime's own logging and send it
     //! to Rust's tracing logging instead.
 
-    use std::{ffi, ffi::CStr, ptr};
+    use std::{cell::RefCell, ffi, ffi::CStr, ptr};
     use tracing::{debug, error, info, span, trace, warn, Level};
 
     use onnxruntime_sys as sys;
 
+    thread_local! {
+        /// Warnings logged on this thread while they are being captured
+        static CAPTURED_WARNINGS: RefCell<Option<Vec<String>>> = const { RefCell::new(None) };
+    }
+
+    /// Run `f`, collecting the warnings (and more severe messages) the runtime logs meanwhile on
+    /// the current thread
+    pub(crate) fn capture_warnings<F, R>(f: F) -> (R, Vec<String>)
+    where
+        F: FnOnce() -> R,
+    {
+        let previous = CAPTURED_WARNINGS.with(|captured| captured.replace(Some(Vec::new())));
+        let result = f();
+        let warnings = CAPTURED_WARNINGS.with(|captured| captured.replace(previous));
+        (result, warnings.unwrap_or_default())
+    }
+
     /// Runtime's logging sends the code location where the log happened, will be parsed to this struct.
     #[derive(Debug)]
     struct CodeLocation<'a> {
@@ -244,6 +403,19 @@ mod onnxruntime {
         assert_ne!(logid, ptr::null());
         let logid = unsafe { CStr::from_ptr(logid) };
 
+        let is_warning = !matches!(
+            severity,
+            sys::OrtLoggingLevel::ORT_LOGGING_LEVEL_VERBOSE
+                | sys::OrtLoggingLevel::ORT_LOGGING_LEVEL_INFO
+        );
+        if is_warning {
+            CAPTURED_WARNINGS.with(|captured| {
+                if let Some(warnings) = captured.borrow_mut().as_mut() {
+                    warnings.push(message.to_string_lossy().into_owned());
+                }
+            });
+        }
+
         // Parse the code location
         let code_location: CodeLocation = code_location.into();
 
@@ -269,7 +441,16 @@ mod onnxruntime {
 }
 
 /// Logging level of the ONNX Runtime C API
-#[derive(Debug)]
+///
+/// Messages of at least this severity are logged. The level can be set for the whole
+/// [environment](environment/struct.EnvBuilder.html#method.with_log_level), overridden for a
+/// [session](session/struct.SessionBuilder.html#method.with_log_level) and for a single
+/// [run](run_options/struct.RunOptions.html#method.set_log_level).
+///
+/// ONNX Runtime's messages are forwarded to [`tracing`](https://docs.rs/tracing): verbose messages
+/// are logged as `TRACE`, info as `DEBUG`, warnings as `INFO`, errors as `WARN` and fatal errors as
+/// `ERROR`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[cfg_attr(not(windows), repr(u32))]
 #[cfg_attr(windows, repr(i32))]
 pub enum LoggingLevel {
@@ -297,6 +478,14 @@ impl Into<sys::OrtLoggingLevel> for LoggingLevel {
     }
 }
 
+impl LoggingLevel {
+    /// Severity of the level, as expected by the session and run options
+    pub(crate) fn severity(self) -> std::os::raw::c_int {
+        let level: sys::OrtLoggingLevel = self.into();
+        level as std::os::raw::c_int
+    }
+}
+
 /// Optimization level performed by ONNX Runtime of the loaded graph
 ///
 /// See the [official documentation](https://github.com/microsoft/onnxruntime/blob/master/docs/ONNX_Runtime_Graph_Optimizations.md)
@@ -375,6 +564,27 @@ impl Into<sys::OrtMemType> for MemType {
     }
 }
 
+/// How a device memory arena grows when it runs out of memory
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArenaExtendStrategy {
+    /// Allocate chunks of growing, power of two sizes (the default)
+    #[default]
+    NextPowerOfTwo,
+    /// Allocate exactly the size requested: slower, but doesn't over-allocate
+    SameAsRequested,
+}
+
+impl ArenaExtendStrategy {
+    /// Value of the strategy in the execution providers' options
+    #[cfg(feature = "cuda")]
+    pub(crate) fn as_c_int(self) -> std::os::raw::c_int {
+        match self {
+            ArenaExtendStrategy::NextPowerOfTwo => 0,
+            ArenaExtendStrategy::SameAsRequested => 1,
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -385,4 +595,44 @@ mod test {
         let ptr = s.as_c_str().as_ptr();
         assert_eq!("foo", char_p_to_string(ptr).unwrap());
     }
+
+    #[test]
+    fn linked_library_version() {
+        let version = api_version();
+        assert!(!version.is_empty());
+        let numbers: Vec<u32> = version
+            .split('.')
+            .map(|number| number.parse().unwrap())
+            .collect();
+        assert_eq!(numbers.len(), 3);
+        assert!(numbers[0] >= 1);
+    }
+
+    #[test]
+    fn cpu_provider_is_available() {
+        let providers = available_providers().unwrap();
+        assert!(providers
+            .iter()
+            .any(|provider| provider == "CPUExecutionProvider"));
+    }
+
+    #[test]
+    fn linked_library_provides_api() {
+        assert!(check_ort_api().is_ok());
+    }
+
+    #[test]
+    fn missing_api_is_reported() {
+        // Simulate a library too old to provide the API version the crate needs
+        match check_ort_api_ptr(ptr::null()) {
+            Err(OrtError::LibraryLoad {
+                version,
+                api_version,
+            }) => {
+                assert!(!version.is_empty());
+                assert_eq!(api_version, sys::ORT_API_VERSION);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
 }
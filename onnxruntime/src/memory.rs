@@ -1,20 +1,36 @@
+//! Module describing the memory tensors are stored in
+
+use std::ffi::CString;
+
 use tracing::debug;
 
 use onnxruntime_sys as sys;
 
 use crate::{
-    error::{status_to_result, OrtError, Result},
+    char_p_to_string,
+    error::{call_ort, status_to_result, OrtError, Result},
     g_ort, AllocatorType, MemType,
 };
 
+/// Name ONNX Runtime gives to the CPU memory
+const CPU_MEMORY_NAME: &str = "Cpu";
+
+/// Description of the memory (device, allocator) holding tensors
+///
+/// Returned by [`DynOrtTensor::memory_info()`](../tensor/struct.DynOrtTensor.html#method.memory_info)
+/// to tell where an output lives, for example to copy it appropriately.
 #[derive(Debug)]
-pub(crate) struct MemoryInfo {
-    pub ptr: *mut sys::OrtMemoryInfo,
+pub struct MemoryInfo {
+    pub(crate) ptr: *mut sys::OrtMemoryInfo,
 }
 
+// The memory info is never mutated after creation.
+unsafe impl Send for MemoryInfo {}
+unsafe impl Sync for MemoryInfo {}
+
 impl MemoryInfo {
     #[tracing::instrument]
-    pub fn new(allocator: AllocatorType, memory_type: MemType) -> Result<Self> {
+    pub(crate) fn new(allocator: AllocatorType, memory_type: MemType) -> Result<Self> {
         debug!("Creating new memory info.");
         let mut memory_info_ptr: *mut sys::OrtMemoryInfo = std::ptr::null_mut();
         let status = unsafe {
@@ -31,6 +47,56 @@ impl MemoryInfo {
             ptr: memory_info_ptr,
         })
     }
+
+    /// Describe the memory of a device, for example to bind an output to a buffer allocated on it
+    /// (see [`IoBinding::bind_output_to_ptr()`](../io_binding/struct.IoBinding.html#method.bind_output_to_ptr))
+    ///
+    /// `name` is ONNX Runtime's name of the device memory: `Cpu`, `Cuda` or `CudaPinned` (page
+    /// locked CPU memory). `device_id` is the index of the device, for example the GPU's.
+    pub fn for_device(name: &str, device_id: i32) -> Result<MemoryInfo> {
+        let name = CString::new(name)?;
+        let mut memory_info_ptr: *mut sys::OrtMemoryInfo = std::ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.CreateMemoryInfo.unwrap()(
+                    name.as_ptr(),
+                    AllocatorType::Device.into(),
+                    device_id,
+                    MemType::Default.into(),
+                    &mut memory_info_ptr,
+                )
+            })
+        }
+        .map_err(OrtError::CreateMemoryInfo)?;
+        assert_ne!(memory_info_ptr, std::ptr::null_mut());
+
+        Ok(Self {
+            ptr: memory_info_ptr,
+        })
+    }
+
+    /// Name of the memory, as given by ONNX Runtime (e.g. `Cpu` or `Cuda`)
+    pub fn name(&self) -> Result<String> {
+        let mut name_ptr: *const i8 = std::ptr::null();
+        unsafe { call_ort(|ort| ort.MemoryInfoGetName.unwrap()(self.ptr, &mut name_ptr)) }
+            .map_err(OrtError::GetMemoryInfo)?;
+        assert_ne!(name_ptr, std::ptr::null());
+        // The name is owned by the memory info: it must not be freed
+        char_p_to_string(name_ptr)
+    }
+
+    /// Identifier of the device holding the memory (e.g. the GPU index)
+    pub fn device_id(&self) -> Result<i32> {
+        let mut device_id = 0;
+        unsafe { call_ort(|ort| ort.MemoryInfoGetId.unwrap()(self.ptr, &mut device_id)) }
+            .map_err(OrtError::GetMemoryInfo)?;
+        Ok(device_id)
+    }
+
+    /// Whether the memory is the CPU's, so that tensors can be read directly
+    pub fn is_cpu(&self) -> Result<bool> {
+        Ok(self.name()? == CPU_MEMORY_NAME)
+    }
 }
 
 impl Drop for MemoryInfo {
@@ -55,4 +121,23 @@ mod tests {
         let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
         std::mem::drop(memory_info);
     }
+
+    #[test]
+    fn cpu_memory_info() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        assert_eq!(memory_info.name().unwrap(), "Cpu");
+        assert_eq!(memory_info.device_id().unwrap(), 0);
+        assert!(memory_info.is_cpu().unwrap());
+    }
+
+    #[test]
+    fn device_memory_info() {
+        let memory_info = MemoryInfo::for_device("Cpu", 0).unwrap();
+        assert!(memory_info.is_cpu().unwrap());
+
+        let memory_info = MemoryInfo::for_device("Cuda", 1).unwrap();
+        assert_eq!(memory_info.name().unwrap(), "Cuda");
+        assert_eq!(memory_info.device_id().unwrap(), 1);
+        assert!(!memory_info.is_cpu().unwrap());
+    }
 }
@@ -0,0 +1,194 @@
+//! Module containing model metadata types
+
+use std::{collections::HashMap, ffi::CString};
+
+use tracing::debug;
+
+use onnxruntime_sys as sys;
+
+use crate::{
+    char_p_to_string,
+    error::{call_ort, OrtError, Result},
+    g_ort,
+};
+
+/// Metadata stored in a loaded model, as reported by ONNX Runtime
+///
+/// Obtained through [`Session::metadata()`](../session/struct.Session.html#method.metadata).
+///
+/// **NOTE**: ONNX Runtime does not expose the IR version nor the opset imports through its
+/// metadata API; see [`Session::ir_version()`](../session/struct.Session.html#method.ir_version)
+/// and [`Session::opset_version()`](../session/struct.Session.html#method.opset_version) instead.
+#[derive(Debug)]
+pub struct ModelMetadata {
+    metadata_ptr: *mut sys::OrtModelMetadata,
+    allocator_ptr: *mut sys::OrtAllocator,
+}
+
+// The metadata is only read once created; the default allocator is thread safe.
+unsafe impl Send for ModelMetadata {}
+unsafe impl Sync for ModelMetadata {}
+
+impl ModelMetadata {
+    pub(crate) fn new(session_ptr: *const sys::OrtSession) -> Result<ModelMetadata> {
+        let mut metadata_ptr: *mut sys::OrtModelMetadata = std::ptr::null_mut();
+        unsafe {
+            call_ort(|ort| ort.SessionGetModelMetadata.unwrap()(session_ptr, &mut metadata_ptr))
+        }
+        .map_err(OrtError::ModelMetadata)?;
+        assert_ne!(metadata_ptr, std::ptr::null_mut());
+
+        // The default allocator lives for the whole process, which frees the metadata from
+        // having to borrow the session.
+        let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
+        unsafe { call_ort(|ort| ort.GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr)) }
+            .map_err(OrtError::Allocator)?;
+        assert_ne!(allocator_ptr, std::ptr::null_mut());
+
+        Ok(ModelMetadata {
+            metadata_ptr,
+            allocator_ptr,
+        })
+    }
+
+    /// Name of the tool that produced the model
+    pub fn producer_name(&self) -> Result<String> {
+        let f = g_ort().ModelMetadataGetProducerName.unwrap();
+        self.extract_string(f)
+    }
+
+    /// Name of the model's graph
+    pub fn graph_name(&self) -> Result<String> {
+        let f = g_ort().ModelMetadataGetGraphName.unwrap();
+        self.extract_string(f)
+    }
+
+    /// Domain of the model
+    pub fn domain(&self) -> Result<String> {
+        let f = g_ort().ModelMetadataGetDomain.unwrap();
+        self.extract_string(f)
+    }
+
+    /// Human readable description of the model
+    pub fn description(&self) -> Result<String> {
+        let f = g_ort().ModelMetadataGetDescription.unwrap();
+        self.extract_string(f)
+    }
+
+    /// Version of the model itself (`ModelProto.model_version`)
+    pub fn version(&self) -> Result<i64> {
+        let mut version = 0;
+        unsafe {
+            call_ort(|ort| ort.ModelMetadataGetVersion.unwrap()(self.metadata_ptr, &mut version))
+        }
+        .map_err(OrtError::ModelMetadata)?;
+        Ok(version)
+    }
+
+    /// Look up a value of the model's custom metadata map
+    pub fn custom(&self, key: &str) -> Result<Option<String>> {
+        let key = CString::new(key)?;
+        let mut value_ptr: *mut i8 = std::ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.ModelMetadataLookupCustomMetadataMap.unwrap()(
+                    self.metadata_ptr,
+                    self.allocator_ptr,
+                    key.as_ptr(),
+                    &mut value_ptr,
+                )
+            })
+        }
+        .map_err(OrtError::ModelMetadata)?;
+
+        if value_ptr.is_null() {
+            Ok(None)
+        } else {
+            self.take_string(value_ptr).map(Some)
+        }
+    }
+
+    /// The model's whole custom metadata map
+    ///
+    /// Exported models often store configuration there (for example the mean and standard
+    /// deviation to normalize inputs with): this reads every entry in one call.
+    pub fn custom_map(&self) -> Result<HashMap<String, String>> {
+        let mut keys_ptr: *mut *mut i8 = std::ptr::null_mut();
+        let mut num_keys = 0_i64;
+        unsafe {
+            call_ort(|ort| {
+                ort.ModelMetadataGetCustomMetadataMapKeys.unwrap()(
+                    self.metadata_ptr,
+                    self.allocator_ptr,
+                    &mut keys_ptr,
+                    &mut num_keys,
+                )
+            })
+        }
+        .map_err(OrtError::ModelMetadata)?;
+        // No array is allocated when the map is empty
+        if keys_ptr.is_null() {
+            return Ok(HashMap::new());
+        }
+
+        // Every key is freed before the array holding them, even if one fails to be converted
+        let keys: Vec<Result<String>> =
+            unsafe { std::slice::from_raw_parts(keys_ptr, num_keys as usize) }
+                .iter()
+                .map(|&key_ptr| self.take_string(key_ptr))
+                .collect();
+        unsafe {
+            call_ort(|ort| {
+                ort.AllocatorFree.unwrap()(self.allocator_ptr, keys_ptr as *mut std::ffi::c_void)
+            })
+        }
+        .map_err(OrtError::Allocator)?;
+
+        let mut map = HashMap::with_capacity(keys.len());
+        for key in keys {
+            let key = key?;
+            if let Some(value) = self.custom(&key)? {
+                map.insert(key, value);
+            }
+        }
+        Ok(map)
+    }
+
+    fn extract_string(
+        &self,
+        f: unsafe extern "C" fn(
+            *const sys::OrtModelMetadata,
+            *mut sys::OrtAllocator,
+            *mut *mut i8,
+        ) -> *mut sys::OrtStatus,
+    ) -> Result<String> {
+        let mut value_ptr: *mut i8 = std::ptr::null_mut();
+        unsafe { call_ort(|_| f(self.metadata_ptr, self.allocator_ptr, &mut value_ptr)) }
+            .map_err(OrtError::ModelMetadata)?;
+        assert_ne!(value_ptr, std::ptr::null_mut());
+
+        self.take_string(value_ptr)
+    }
+
+    /// Copy a string allocated by ONNX Runtime, freeing the original
+    fn take_string(&self, value_ptr: *mut i8) -> Result<String> {
+        let value = char_p_to_string(value_ptr);
+        unsafe {
+            call_ort(|ort| {
+                ort.AllocatorFree.unwrap()(self.allocator_ptr, value_ptr as *mut std::ffi::c_void)
+            })
+        }
+        .map_err(OrtError::Allocator)?;
+        value
+    }
+}
+
+impl Drop for ModelMetadata {
+    #[tracing::instrument]
+    fn drop(&mut self) {
+        debug!("Dropping the model metadata.");
+        assert_ne!(self.metadata_ptr, std::ptr::null_mut());
+        unsafe { g_ort().ReleaseModelMetadata.unwrap()(self.metadata_ptr) };
+        self.metadata_ptr = std::ptr::null_mut();
+    }
+}
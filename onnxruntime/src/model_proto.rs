@@ -0,0 +1,808 @@
+//! Minimal reader for the protobuf encoding of ONNX models
+//!
+//! ONNX Runtime's C API does not expose everything stored in a model (for example the IR version
+//! or the opset imports). Instead of depending on a full protobuf implementation, this module
+//! decodes the few fields of the [ONNX schema](https://github.com/onnx/onnx/blob/master/onnx/onnx.proto)
+//! the crate needs directly from the
+//! [wire format](https://developers.google.com/protocol-buffers/docs/encoding).
+
+use std::{
+    collections::HashSet,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// `ModelProto.ir_version`
+const MODEL_IR_VERSION: u32 = 1;
+/// `ModelProto.graph`
+const MODEL_GRAPH: u32 = 7;
+/// `ModelProto.opset_import`
+const MODEL_OPSET_IMPORT: u32 = 8;
+/// `ModelProto.functions`
+const MODEL_FUNCTIONS: u32 = 25;
+/// `GraphProto.node`
+const GRAPH_NODE: u32 = 1;
+/// `GraphProto.initializer`
+const GRAPH_INITIALIZER: u32 = 5;
+/// `GraphProto.input`
+const GRAPH_INPUT: u32 = 11;
+/// `NodeProto.op_type`
+const NODE_OP_TYPE: u32 = 4;
+/// `NodeProto.attribute`
+const NODE_ATTRIBUTE: u32 = 5;
+/// `NodeProto.domain`
+const NODE_DOMAIN: u32 = 7;
+/// `AttributeProto.g`
+const ATTRIBUTE_G: u32 = 6;
+/// `AttributeProto.graphs`
+const ATTRIBUTE_GRAPHS: u32 = 11;
+/// `FunctionProto.node`
+const FUNCTION_NODE: u32 = 7;
+/// `ValueInfoProto.name`
+const VALUE_INFO_NAME: u32 = 1;
+/// `ValueInfoProto.type`
+const VALUE_INFO_TYPE: u32 = 2;
+/// `TypeProto.tensor_type`
+const TYPE_TENSOR_TYPE: u32 = 1;
+/// `TypeProto.Tensor.shape`
+const TENSOR_TYPE_SHAPE: u32 = 2;
+/// `TensorShapeProto.dim`
+const SHAPE_DIM: u32 = 1;
+/// `TensorShapeProto.Dimension.denotation`
+const DIMENSION_DENOTATION: u32 = 3;
+/// `TensorProto.dims`
+const TENSOR_DIMS: u32 = 1;
+/// `TensorProto.data_type`
+const TENSOR_DATA_TYPE: u32 = 2;
+/// `TensorProto.float_data`
+pub(crate) const TENSOR_FLOAT_DATA: u32 = 4;
+/// `TensorProto.int32_data`
+pub(crate) const TENSOR_INT32_DATA: u32 = 5;
+/// `TensorProto.int64_data`
+pub(crate) const TENSOR_INT64_DATA: u32 = 7;
+/// `TensorProto.name`
+const TENSOR_NAME: u32 = 8;
+/// `TensorProto.raw_data`
+const TENSOR_RAW_DATA: u32 = 9;
+/// `TensorProto.double_data`
+pub(crate) const TENSOR_DOUBLE_DATA: u32 = 10;
+/// `TensorProto.uint64_data`
+pub(crate) const TENSOR_UINT64_DATA: u32 = 11;
+/// `TensorProto.data_location`
+const TENSOR_DATA_LOCATION: u32 = 14;
+/// `TensorProto.DataLocation.EXTERNAL`
+const DATA_LOCATION_EXTERNAL: i64 = 1;
+/// `OperatorSetIdProto.domain`
+const OPSET_DOMAIN: u32 = 1;
+/// `OperatorSetIdProto.version`
+const OPSET_VERSION: u32 = 2;
+
+/// Deepest nesting of subgraphs (e.g. `If` branches within `Loop` bodies) visited when listing
+/// operators, so that crafted models can't exhaust the stack
+const MAX_SUBGRAPH_DEPTH: usize = 64;
+
+/// A field's value, as encoded on the wire
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WireValue<'a> {
+    /// Wire type 0
+    Varint(u64),
+    /// Wire type 1
+    Fixed64(u64),
+    /// Wire type 2
+    Bytes(&'a [u8]),
+    /// Wire type 5
+    Fixed32(u32),
+}
+
+impl<'a> WireValue<'a> {
+    pub(crate) fn as_i64(&self) -> Option<i64> {
+        match *self {
+            WireValue::Varint(v) | WireValue::Fixed64(v) => Some(v as i64),
+            WireValue::Fixed32(v) => Some(i64::from(v)),
+            WireValue::Bytes(_) => None,
+        }
+    }
+
+    pub(crate) fn as_bytes(&self) -> Option<&'a [u8]> {
+        match *self {
+            WireValue::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_string(&self) -> Option<String> {
+        self.as_bytes()
+            .map(|b| String::from_utf8_lossy(b).into_owned())
+    }
+}
+
+/// Iterator over the `(field number, value)` pairs of an encoded message
+#[derive(Debug)]
+pub(crate) struct Fields<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Fields<'a> {
+        Fields { buf }
+    }
+
+    fn read_varint(&mut self) -> io::Result<u64> {
+        let mut value = 0_u64;
+        for (i, byte) in self.buf.iter().enumerate().take(10) {
+            value |= u64::from(byte & 0x7f) << (7 * i);
+            if byte & 0x80 == 0 {
+                self.buf = &self.buf[i + 1..];
+                return Ok(value);
+            }
+        }
+        Err(invalid_data("truncated or invalid varint"))
+    }
+
+    fn read_bytes(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.buf.len() < len {
+            return Err(invalid_data("truncated field"));
+        }
+        let (bytes, rest) = self.buf.split_at(len);
+        self.buf = rest;
+        Ok(bytes)
+    }
+
+    fn read_field(&mut self) -> io::Result<(u32, WireValue<'a>)> {
+        let key = self.read_varint()?;
+        let field_number = (key >> 3) as u32;
+        let value = match key & 0x7 {
+            0 => WireValue::Varint(self.read_varint()?),
+            1 => {
+                let mut fixed = [0_u8; 8];
+                fixed.copy_from_slice(self.read_bytes(8)?);
+                WireValue::Fixed64(u64::from_le_bytes(fixed))
+            }
+            2 => {
+                let len = self.read_varint()? as usize;
+                WireValue::Bytes(self.read_bytes(len)?)
+            }
+            5 => {
+                let mut fixed = [0_u8; 4];
+                fixed.copy_from_slice(self.read_bytes(4)?);
+                WireValue::Fixed32(u32::from_le_bytes(fixed))
+            }
+            wire_type => {
+                return Err(invalid_data(&format!(
+                    "unsupported wire type {}",
+                    wire_type
+                )))
+            }
+        };
+        Ok((field_number, value))
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = io::Result<(u32, WireValue<'a>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            None
+        } else {
+            let field = self.read_field();
+            if field.is_err() {
+                // Stop iterating on malformed input
+                self.buf = &[];
+            }
+            Some(field)
+        }
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Version information stored at the top level of an ONNX model, and the size of its graph
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ModelVersions {
+    /// `ModelProto.ir_version`
+    pub ir_version: Option<i64>,
+    /// `ModelProto.opset_import` as `(domain, version)` pairs
+    pub opset_imports: Vec<(String, i64)>,
+    /// Number of elements of the main graph's repeated fields
+    pub graph_counts: Option<GraphCounts>,
+}
+
+/// Number of nodes and initializers of a `GraphProto` (excluding its subgraphs), and the
+/// denotations of its inputs' shapes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct GraphCounts {
+    /// `GraphProto.node`
+    pub node_count: usize,
+    /// `GraphProto.initializer`
+    pub initializer_count: usize,
+    /// Denotation of each dimension of the tensor inputs (`GraphProto.input`), by input name
+    pub input_denotations: Vec<(String, Vec<Option<String>>)>,
+}
+
+impl ModelVersions {
+    /// Decode the version information from an encoded model
+    ///
+    /// Only the top-level fields are visited: large fields (like the graph) are skipped by seeking
+    /// over them so that the whole model does not have to be read.
+    pub(crate) fn read<R>(mut reader: R) -> io::Result<ModelVersions>
+    where
+        R: Read + Seek,
+    {
+        let mut versions = ModelVersions::default();
+
+        while let Some(key) = read_varint_from(&mut reader)? {
+            let field_number = (key >> 3) as u32;
+            match key & 0x7 {
+                0 => {
+                    let value = read_varint_from(&mut reader)?
+                        .ok_or_else(|| invalid_data("truncated varint"))?;
+                    if field_number == MODEL_IR_VERSION {
+                        versions.ir_version = Some(value as i64);
+                    }
+                }
+                1 => {
+                    reader.seek(SeekFrom::Current(8))?;
+                }
+                2 => {
+                    let len = read_varint_from(&mut reader)?
+                        .ok_or_else(|| invalid_data("truncated length"))?;
+                    if field_number == MODEL_OPSET_IMPORT {
+                        let opset_import = read_bytes_from(&mut reader, len)?;
+                        versions
+                            .opset_imports
+                            .push(parse_opset_import(&opset_import)?);
+                    } else if field_number == MODEL_GRAPH {
+                        versions.graph_counts = Some(read_graph_counts(&mut reader, len)?);
+                    } else {
+                        reader.seek(SeekFrom::Current(len as i64))?;
+                    }
+                }
+                5 => {
+                    reader.seek(SeekFrom::Current(4))?;
+                }
+                wire_type => {
+                    return Err(invalid_data(&format!(
+                        "unsupported wire type {}",
+                        wire_type
+                    )))
+                }
+            }
+        }
+
+        Ok(versions)
+    }
+}
+
+fn parse_opset_import(buf: &[u8]) -> io::Result<(String, i64)> {
+    let mut domain = String::new();
+    let mut version = 0;
+    for field in Fields::new(buf) {
+        match field? {
+            (OPSET_DOMAIN, value) => domain = value.as_string().unwrap_or_default(),
+            (OPSET_VERSION, value) => version = value.as_i64().unwrap_or_default(),
+            _ => {}
+        }
+    }
+    Ok((domain, version))
+}
+
+/// Count the nodes and initializers of an encoded graph of `len` bytes, seeking over their
+/// content, and decode its inputs' denotations
+fn read_graph_counts<R>(reader: &mut R, len: u64) -> io::Result<GraphCounts>
+where
+    R: Read + Seek,
+{
+    let end = reader.stream_position()? + len;
+    let mut counts = GraphCounts::default();
+
+    while reader.stream_position()? < end {
+        let key = read_varint_from(reader)?.ok_or_else(|| invalid_data("truncated graph"))?;
+        match (key >> 3) as u32 {
+            GRAPH_NODE => counts.node_count += 1,
+            GRAPH_INITIALIZER => counts.initializer_count += 1,
+            GRAPH_INPUT if key & 0x7 == 2 => {
+                let len =
+                    read_varint_from(reader)?.ok_or_else(|| invalid_data("truncated length"))?;
+                let value_info = read_bytes_from(reader, len)?;
+                if let Some(input) = parse_input_denotations(&value_info)? {
+                    counts.input_denotations.push(input);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        let skip = match key & 0x7 {
+            0 => {
+                read_varint_from(reader)?.ok_or_else(|| invalid_data("truncated varint"))?;
+                0
+            }
+            1 => 8,
+            2 => read_varint_from(reader)?.ok_or_else(|| invalid_data("truncated length"))?,
+            5 => 4,
+            wire_type => {
+                return Err(invalid_data(&format!(
+                    "unsupported wire type {}",
+                    wire_type
+                )))
+            }
+        };
+        reader.seek(SeekFrom::Current(skip as i64))?;
+    }
+
+    Ok(counts)
+}
+
+/// Decode the name and dimension denotations of a tensor `ValueInfoProto`
+///
+/// Returns `None` for values which are not tensors or have no shape.
+fn parse_input_denotations(buf: &[u8]) -> io::Result<Option<(String, Vec<Option<String>>)>> {
+    let mut name = String::new();
+    let mut shape = None;
+    for field in Fields::new(buf) {
+        match field? {
+            (VALUE_INFO_NAME, value) => name = value.as_string().unwrap_or_default(),
+            (VALUE_INFO_TYPE, value) => {
+                let tensor_type =
+                    find_field(value.as_bytes().unwrap_or_default(), TYPE_TENSOR_TYPE)?;
+                shape = match tensor_type {
+                    Some(tensor_type) => find_field(tensor_type, TENSOR_TYPE_SHAPE)?,
+                    None => None,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    let shape = match shape {
+        Some(shape) => shape,
+        None => return Ok(None),
+    };
+    let mut denotations = Vec::new();
+    for field in Fields::new(shape) {
+        if let (SHAPE_DIM, value) = field? {
+            let dim = value.as_bytes().unwrap_or_default();
+            denotations.push(
+                find_field(dim, DIMENSION_DENOTATION)?
+                    .map(|denotation| String::from_utf8_lossy(denotation).into_owned()),
+            );
+        }
+    }
+    Ok(Some((name, denotations)))
+}
+
+/// An initializer of a graph (`GraphProto.initializer`), with its data still encoded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TensorProto {
+    /// `TensorProto.name`
+    pub name: String,
+    /// `TensorProto.data_type`, an ONNX `TensorProto.DataType`
+    pub data_type: i64,
+    /// `TensorProto.dims`
+    pub dims: Vec<i64>,
+    /// `TensorProto.raw_data`: the elements as little-endian bytes
+    pub raw_data: Option<Vec<u8>>,
+    /// Elements of the typed data fields (`float_data`, `int64_data`...) as their wire
+    /// representation (varints, or the bits of floating point values), by field number
+    pub typed_data: Vec<(u32, Vec<u64>)>,
+    /// Whether the data is stored outside of the model (`TensorProto.data_location`)
+    pub external: bool,
+}
+
+impl TensorProto {
+    /// Elements of a typed data field, empty if the field is absent
+    pub(crate) fn typed_data(&self, field_number: u32) -> &[u64] {
+        self.typed_data
+            .iter()
+            .find(|(number, _)| *number == field_number)
+            .map(|(_, values)| values.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Decode the initializers of the main graph of an encoded model
+pub(crate) fn read_initializers(model: &[u8]) -> io::Result<Vec<TensorProto>> {
+    let graph = match find_field(model, MODEL_GRAPH)? {
+        Some(graph) => graph,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut initializers = Vec::new();
+    for field in Fields::new(graph) {
+        if let (GRAPH_INITIALIZER, value) = field? {
+            initializers.push(parse_tensor(value.as_bytes().unwrap_or_default())?);
+        }
+    }
+    Ok(initializers)
+}
+
+/// Decode the `(domain, op_type)` of the nodes of an encoded model
+///
+/// The nodes of subgraphs (e.g. the branches of `If` and the bodies of `Loop`) and of the model's
+/// local functions are included. The default ONNX domain is reported as `""`, whether it is
+/// written as `""` or `"ai.onnx"`. Subgraphs nested deeper than `MAX_SUBGRAPH_DEPTH` are
+/// rejected as invalid data.
+pub(crate) fn read_ops(model: &[u8]) -> io::Result<HashSet<(String, String)>> {
+    let mut ops = HashSet::new();
+    for field in Fields::new(model) {
+        match field? {
+            (MODEL_GRAPH, value) => {
+                collect_graph_ops(value.as_bytes().unwrap_or_default(), 0, &mut ops)?
+            }
+            (MODEL_FUNCTIONS, value) => {
+                for field in Fields::new(value.as_bytes().unwrap_or_default()) {
+                    if let (FUNCTION_NODE, node) = field? {
+                        collect_node_ops(node.as_bytes().unwrap_or_default(), 0, &mut ops)?;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(ops)
+}
+
+/// Collect the operators of a graph nested in `depth` other graphs
+fn collect_graph_ops(
+    graph: &[u8],
+    depth: usize,
+    ops: &mut HashSet<(String, String)>,
+) -> io::Result<()> {
+    if depth > MAX_SUBGRAPH_DEPTH {
+        return Err(invalid_data("subgraphs nested too deeply"));
+    }
+    for field in Fields::new(graph) {
+        if let (GRAPH_NODE, node) = field? {
+            collect_node_ops(node.as_bytes().unwrap_or_default(), depth, ops)?;
+        }
+    }
+    Ok(())
+}
+
+fn collect_node_ops(
+    node: &[u8],
+    depth: usize,
+    ops: &mut HashSet<(String, String)>,
+) -> io::Result<()> {
+    let mut domain = String::new();
+    let mut op_type = String::new();
+    for field in Fields::new(node) {
+        match field? {
+            (NODE_OP_TYPE, value) => op_type = value.as_string().unwrap_or_default(),
+            (NODE_DOMAIN, value) => domain = value.as_string().unwrap_or_default(),
+            (NODE_ATTRIBUTE, value) => {
+                for field in Fields::new(value.as_bytes().unwrap_or_default()) {
+                    match field? {
+                        (ATTRIBUTE_G, graph) | (ATTRIBUTE_GRAPHS, graph) => {
+                            collect_graph_ops(graph.as_bytes().unwrap_or_default(), depth + 1, ops)?
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if domain == "ai.onnx" {
+        domain.clear();
+    }
+    ops.insert((domain, op_type));
+    Ok(())
+}
+
+fn parse_tensor(buf: &[u8]) -> io::Result<TensorProto> {
+    let mut tensor = TensorProto::default();
+    for field in Fields::new(buf) {
+        match field? {
+            (TENSOR_DIMS, value) => tensor
+                .dims
+                .extend(repeated_varints(value)?.into_iter().map(|dim| dim as i64)),
+            (TENSOR_DATA_TYPE, value) => tensor.data_type = value.as_i64().unwrap_or_default(),
+            (TENSOR_NAME, value) => tensor.name = value.as_string().unwrap_or_default(),
+            (TENSOR_RAW_DATA, value) => {
+                tensor.raw_data = Some(value.as_bytes().unwrap_or_default().to_vec())
+            }
+            (TENSOR_DATA_LOCATION, value) => {
+                tensor.external = value.as_i64() == Some(DATA_LOCATION_EXTERNAL)
+            }
+            (number, value)
+                if matches!(
+                    number,
+                    TENSOR_FLOAT_DATA
+                        | TENSOR_INT32_DATA
+                        | TENSOR_INT64_DATA
+                        | TENSOR_DOUBLE_DATA
+                        | TENSOR_UINT64_DATA
+                ) =>
+            {
+                let values = match number {
+                    TENSOR_FLOAT_DATA => repeated_fixed(value, 4)?,
+                    TENSOR_DOUBLE_DATA => repeated_fixed(value, 8)?,
+                    _ => repeated_varints(value)?,
+                };
+                match tensor.typed_data.iter_mut().find(|(n, _)| *n == number) {
+                    Some((_, existing)) => existing.extend(values),
+                    None => tensor.typed_data.push((number, values)),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(tensor)
+}
+
+/// Elements of a repeated varint field, either packed or as a single element
+fn repeated_varints(value: WireValue) -> io::Result<Vec<u64>> {
+    match value {
+        WireValue::Bytes(packed) => {
+            let mut fields = Fields::new(packed);
+            let mut values = Vec::new();
+            while !fields.buf.is_empty() {
+                values.push(fields.read_varint()?);
+            }
+            Ok(values)
+        }
+        WireValue::Varint(v) => Ok(vec![v]),
+        _ => Err(invalid_data("expected a varint")),
+    }
+}
+
+/// Elements of a repeated fixed-size (`size` bytes) field, either packed or as a single element
+fn repeated_fixed(value: WireValue, size: usize) -> io::Result<Vec<u64>> {
+    match value {
+        WireValue::Bytes(packed) => {
+            if packed.len() % size != 0 {
+                return Err(invalid_data("truncated packed field"));
+            }
+            Ok(packed
+                .chunks_exact(size)
+                .map(|chunk| {
+                    let mut fixed = [0_u8; 8];
+                    fixed[..size].copy_from_slice(chunk);
+                    u64::from_le_bytes(fixed)
+                })
+                .collect())
+        }
+        WireValue::Fixed32(v) if size == 4 => Ok(vec![u64::from(v)]),
+        WireValue::Fixed64(v) if size == 8 => Ok(vec![v]),
+        _ => Err(invalid_data("expected a fixed-size value")),
+    }
+}
+
+/// Content of the last occurrence of a length-delimited field of an encoded message
+fn find_field(buf: &[u8], field_number: u32) -> io::Result<Option<&[u8]>> {
+    let mut found = None;
+    for field in Fields::new(buf) {
+        let (number, value) = field?;
+        if number == field_number {
+            found = value.as_bytes().or(found);
+        }
+    }
+    Ok(found)
+}
+
+/// Read a length-delimited field of `len` bytes from a stream
+///
+/// The buffer grows as the data is read rather than being allocated upfront: the length comes
+/// from the model and may be far larger than the stream.
+fn read_bytes_from<R: Read>(reader: &mut R, len: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len).read_to_end(&mut bytes)?;
+    if bytes.len() as u64 != len {
+        return Err(invalid_data("truncated field"));
+    }
+    Ok(bytes)
+}
+
+/// Read a varint from a stream, returning `None` on a clean end of stream
+fn read_varint_from<R: Read>(reader: &mut R) -> io::Result<Option<u64>> {
+    let mut value = 0_u64;
+    for i in 0..10 {
+        let mut byte = [0_u8; 1];
+        if reader.read(&mut byte)? == 0 {
+            return if i == 0 {
+                Ok(None)
+            } else {
+                Err(invalid_data("truncated varint"))
+            };
+        }
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(invalid_data("invalid varint"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn fields_decoding() {
+        // field 1: varint 150, field 2: string "testing"
+        let buf = [
+            0x08, 0x96, 0x01, 0x12, 0x07, b't', b'e', b's', b't', b'i', b'n', b'g',
+        ];
+        let fields = Fields::new(&buf).collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(
+            fields,
+            vec![
+                (1, WireValue::Varint(150)),
+                (2, WireValue::Bytes(b"testing"))
+            ]
+        );
+    }
+
+    #[test]
+    fn fields_truncated() {
+        let buf = [0x12, 0x07, b't', b'e'];
+        assert!(Fields::new(&buf).next().unwrap().is_err());
+    }
+
+    #[test]
+    fn model_versions() {
+        let buf = [
+            // ir_version: 7
+            0x08, 0x07, // producer_name: "x" (skipped)
+            0x12, 0x01, b'x', // opset_import { domain: "", version: 12 }
+            0x42, 0x04, 0x0a, 0x00, 0x10, 0x0c,
+        ];
+        let versions = ModelVersions::read(Cursor::new(&buf[..])).unwrap();
+        assert_eq!(versions.ir_version, Some(7));
+        assert_eq!(versions.opset_imports, vec![(String::new(), 12)]);
+        assert_eq!(versions.graph_counts, None);
+    }
+
+    #[test]
+    fn model_versions_truncated() {
+        // opset_import claiming u64::MAX bytes, followed by 2 bytes
+        let buf = [
+            0x42, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x0a, 0x00,
+        ];
+        let error = ModelVersions::read(Cursor::new(&buf[..])).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn graph_counts_truncated() {
+        // graph { input claiming u64::MAX bytes }
+        let buf = [
+            0x3a, 0x0b, 0x5a, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01,
+        ];
+        let error = ModelVersions::read(Cursor::new(&buf[..])).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn initializers() {
+        let buf = [
+            // graph { initializer { dims: [2], data_type: FLOAT, float_data: [1.0, 2.0],
+            // name: "w" }, initializer { dims: [1], data_type: INT64, name: "b",
+            // raw_data: [-1] } }
+            0x3a, 0x29, //
+            0x2a, 0x12, 0x0a, 0x01, 0x02, 0x10, 0x01, 0x22, 0x08, 0x00, 0x00, 0x80, 0x3f, 0x00,
+            0x00, 0x00, 0x40, 0x42, 0x01, b'w', //
+            0x2a, 0x13, 0x08, 0x01, 0x10, 0x07, 0x42, 0x01, b'b', 0x4a, 0x08, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0x70, 0x00,
+        ];
+        let initializers = read_initializers(&buf).unwrap();
+        assert_eq!(initializers.len(), 2);
+
+        let w = &initializers[0];
+        assert_eq!(w.name, "w");
+        assert_eq!(w.data_type, 1);
+        assert_eq!(w.dims, vec![2]);
+        assert_eq!(w.raw_data, None);
+        assert_eq!(
+            w.typed_data(TENSOR_FLOAT_DATA),
+            &[u64::from(1.0_f32.to_bits()), u64::from(2.0_f32.to_bits())]
+        );
+        assert!(w.typed_data(TENSOR_INT64_DATA).is_empty());
+
+        let b = &initializers[1];
+        assert_eq!(b.name, "b");
+        assert_eq!(b.data_type, 7);
+        assert_eq!(b.dims, vec![1]);
+        assert_eq!(b.raw_data, Some((-1_i64).to_le_bytes().to_vec()));
+        assert!(!b.external);
+
+        // Models without a graph have no initializers
+        assert!(read_initializers(&[0x08, 0x07]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn graph_counts() {
+        let buf = [
+            // graph { node {}, node { name: "n" }, name: "g", initializer {} }
+            0x3a, 0x0c, 0x0a, 0x00, 0x0a, 0x03, 0x1a, 0x01, b'n', 0x12, 0x01, b'g', 0x2a, 0x00,
+            // ir_version: 7
+            0x08, 0x07,
+        ];
+        let versions = ModelVersions::read(Cursor::new(&buf[..])).unwrap();
+        assert_eq!(versions.ir_version, Some(7));
+        assert_eq!(
+            versions.graph_counts,
+            Some(GraphCounts {
+                node_count: 2,
+                initializer_count: 1,
+                input_denotations: vec![],
+            })
+        );
+    }
+
+    #[test]
+    fn ops() {
+        let buf = [
+            // graph { node { op_type: "Relu" }, node { op_type: "If", attribute {
+            //   g { node { op_type: "Gelu", domain: "com.microsoft" } } } } }
+            0x3a, 0x29, 0x0a, 0x06, 0x22, 0x04, b'R', b'e', b'l', b'u', 0x0a, 0x1f, 0x22, 0x02,
+            b'I', b'f', 0x2a, 0x19, 0x32, 0x17, 0x0a, 0x15, 0x22, 0x04, b'G', b'e', b'l', b'u',
+            0x3a, 0x0d, b'c', b'o', b'm', b'.', b'm', b'i', b'c', b'r', b'o', b's', b'o', b'f',
+            b't', //
+            // functions { node { op_type: "Relu", domain: "ai.onnx" } }
+            0xca, 0x01, 0x11, 0x3a, 0x0f, 0x22, 0x04, b'R', b'e', b'l', b'u', 0x3a, 0x07, b'a',
+            b'i', b'.', b'o', b'n', b'n', b'x',
+        ];
+        let ops = read_ops(&buf).unwrap();
+        let expected: HashSet<(String, String)> =
+            [("", "Relu"), ("", "If"), ("com.microsoft", "Gelu")]
+                .iter()
+                .map(|&(domain, op)| (domain.to_string(), op.to_string()))
+                .collect();
+        assert_eq!(ops, expected);
+
+        assert!(read_ops(&[0x08, 0x07]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn ops_nested_too_deeply() {
+        /// Encode `bytes` as the length-delimited field `number`
+        fn field(number: u8, bytes: &[u8]) -> Vec<u8> {
+            let mut encoded = vec![number << 3 | 2];
+            let mut len = bytes.len();
+            while len >= 0x80 {
+                encoded.push((len as u8 & 0x7f) | 0x80);
+                len >>= 7;
+            }
+            encoded.push(len as u8);
+            encoded.extend_from_slice(bytes);
+            encoded
+        }
+
+        // graph { node { op_type: "If", attribute { g { node { op_type: "If", ... } } } } }
+        let nested = |depth: usize| {
+            let mut graph = Vec::new();
+            for _ in 0..depth {
+                let mut node = field(4, b"If");
+                node.extend(field(5, &field(6, &graph)));
+                graph = field(1, &node);
+            }
+            field(7, &graph)
+        };
+
+        assert_eq!(read_ops(&nested(MAX_SUBGRAPH_DEPTH)).unwrap().len(), 1);
+        let error = read_ops(&nested(MAX_SUBGRAPH_DEPTH + 2)).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn input_denotations() {
+        let buf = [
+            // name: "x", type { tensor_type { elem_type: 1, shape {
+            //   dim { dim_param: "n", denotation: "DATA_BATCH" }, dim { dim_value: 3 } } } }
+            0x0a, 0x01, b'x', 0x12, 0x1b, 0x0a, 0x19, 0x08, 0x01, 0x12, 0x15, 0x0a, 0x0f, 0x12,
+            0x01, b'n', 0x1a, 0x0a, b'D', b'A', b'T', b'A', b'_', b'B', b'A', b'T', b'C', b'H',
+            0x0a, 0x02, 0x08, 0x03,
+        ];
+        assert_eq!(
+            parse_input_denotations(&buf).unwrap(),
+            Some((
+                String::from("x"),
+                vec![Some(String::from("DATA_BATCH")), None]
+            ))
+        );
+    }
+}
@@ -0,0 +1,119 @@
+//! Module transforming raw inputs before they are run through a session
+//!
+//! Normalization, scaling or layout changes are often the same for every call to
+//! [`Session::run()`](../session/struct.Session.html#method.run). Implement
+//! [`Preprocessor`](trait.Preprocessor.html) once and attach it with
+//! [`Session::with_preprocessor()`](../session/struct.Session.html#method.with_preprocessor): the
+//! returned [`PreprocessedSession`](struct.PreprocessedSession.html) applies it to the inputs of
+//! every run.
+
+use std::{fmt::Debug, ops::Deref};
+
+use ndarray::{Array, Dimension, IxDyn};
+
+use crate::{
+    error::Result,
+    session::Session,
+    tensor::{DynOrtTensor, TypeToTensorElementDataType},
+};
+
+/// Transformation applied to the inputs of a session before they are converted to tensors
+///
+/// ```
+/// # use onnxruntime::{preprocessor::Preprocessor, Result};
+/// # use ndarray::{Array, IxDyn};
+/// /// Scale 8 bits pixel values to `[0, 1]`
+/// #[derive(Debug)]
+/// struct Scale;
+///
+/// impl Preprocessor for Scale {
+///     type Input = u8;
+///     type Output = f32;
+///
+///     fn preprocess(
+///         &self,
+///         inputs: Vec<Array<u8, IxDyn>>,
+///     ) -> Result<Vec<Array<f32, IxDyn>>> {
+///         Ok(inputs
+///             .into_iter()
+///             .map(|input| input.mapv(|pixel| f32::from(pixel) / 255.0))
+///             .collect())
+///     }
+/// }
+/// ```
+pub trait Preprocessor {
+    /// Element type of the raw inputs
+    type Input;
+    /// Element type of the inputs the model expects
+    type Output: TypeToTensorElementDataType + Debug + Clone;
+
+    /// Transform the raw inputs, given in the order of the model's inputs
+    ///
+    /// The returned vector must hold one array per model input, in the same order.
+    fn preprocess(
+        &self,
+        inputs: Vec<Array<Self::Input, IxDyn>>,
+    ) -> Result<Vec<Array<Self::Output, IxDyn>>>;
+}
+
+/// A [`Session`](../session/struct.Session.html) applying a
+/// [`Preprocessor`](trait.Preprocessor.html) to its inputs
+///
+/// Created with [`Session::with_preprocessor()`](../session/struct.Session.html#method.with_preprocessor).
+/// It dereferences to the underlying session, so its metadata and other run variants (which take
+/// inputs as-is) remain available.
+#[derive(Debug, Clone)]
+pub struct PreprocessedSession<'a, P> {
+    session: Session<'a>,
+    preprocessor: P,
+}
+
+impl<'a, P> PreprocessedSession<'a, P>
+where
+    P: Preprocessor,
+{
+    pub(crate) fn new(session: Session<'a>, preprocessor: P) -> PreprocessedSession<'a, P> {
+        PreprocessedSession {
+            session,
+            preprocessor,
+        }
+    }
+
+    /// Preprocess the raw input data, then run it through the ONNX graph
+    ///
+    /// See [`Session::run()`](../session/struct.Session.html#method.run).
+    pub fn run<'s, 't, 'm, D>(
+        &'s self,
+        input_arrays: Vec<Array<P::Input, D>>,
+    ) -> Result<Vec<DynOrtTensor<'m, IxDyn>>>
+    where
+        D: Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let input_arrays = input_arrays
+            .into_iter()
+            .map(|array| array.into_dyn())
+            .collect();
+        let input_arrays = self.preprocessor.preprocess(input_arrays)?;
+        self.session.run(input_arrays)
+    }
+
+    /// The preprocessor applied to the inputs
+    pub fn preprocessor(&self) -> &P {
+        &self.preprocessor
+    }
+
+    /// Detach the preprocessor, giving back the session
+    pub fn into_inner(self) -> Session<'a> {
+        self.session
+    }
+}
+
+impl<'a, P> Deref for PreprocessedSession<'a, P> {
+    type Target = Session<'a>;
+
+    fn deref(&self) -> &Session<'a> {
+        &self.session
+    }
+}
@@ -0,0 +1,134 @@
+//! Module retrying inference runs which failed transiently
+//!
+//! Execution providers running on a GPU can fail under load (`ORT_EP_FAIL`) and succeed when run
+//! again. A [`RetryPolicy`](struct.RetryPolicy.html) tells
+//! [`Session::run_with_retry()`](../session/struct.Session.html#method.run_with_retry) how many
+//! times to try, how long to wait between attempts and which errors are worth retrying.
+
+use std::{thread, time::Duration};
+
+use tracing::warn;
+
+use crate::{error::Result, OrtError, OrtErrorCode};
+
+/// How to retry runs which failed transiently
+///
+/// The default policy makes up to 3 attempts, waiting 100ms then 200ms, and only retries
+/// [`OrtErrorCode::EpFail`](../error/enum.OrtErrorCode.html#variant.EpFail).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, the first one included
+    ///
+    /// `0` behaves as `1`: the run is always attempted once.
+    pub max_attempts: usize,
+    /// Delay before the second attempt, doubled before each of the following ones
+    pub backoff: Duration,
+    /// Error codes of the runs to retry
+    ///
+    /// Only run failures ([`OrtError::Run`](../error/enum.OrtError.html#variant.Run) and
+    /// [`OrtError::OutOfMemory`](../error/enum.OrtError.html#variant.OutOfMemory)) are retried:
+    /// other errors, like invalid inputs, fail the same way on every attempt.
+    pub retry_on: Vec<OrtErrorCode>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+            retry_on: vec![OrtErrorCode::EpFail],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Whether a failed attempt should be retried, attempts left permitting
+    pub fn should_retry(&self, error: &OrtError) -> bool {
+        match error {
+            OrtError::Run(error) | OrtError::OutOfMemory(error) => error
+                .code()
+                .is_some_and(|code| self.retry_on.contains(&code)),
+            _ => false,
+        }
+    }
+
+    /// Call `attempt` until it succeeds, fails with an error which should not be retried, or the
+    /// maximum number of attempts is reached
+    ///
+    /// The error of the last attempt is returned.
+    pub fn run<T, F>(&self, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Result<T>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempts = 1;
+        loop {
+            match attempt() {
+                Err(error) if attempts < self.max_attempts && self.should_retry(&error) => {
+                    warn!(attempts, ?backoff, "Retrying failed run: {}", error);
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OrtApiError;
+    use test_env_log::test;
+
+    fn run_failure(code: OrtErrorCode) -> OrtError {
+        OrtError::Run(OrtApiError::MsgWithCode {
+            code,
+            message: String::from("injected failure"),
+        })
+    }
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            backoff: Duration::from_millis(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    #[test]
+    fn succeeds_on_second_attempt() {
+        let mut attempts = 0;
+        let result = policy().run(|| {
+            attempts += 1;
+            if attempts == 1 {
+                Err(run_failure(OrtErrorCode::EpFail))
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut attempts = 0;
+        let result: Result<()> = policy().run(|| {
+            attempts += 1;
+            Err(run_failure(OrtErrorCode::EpFail))
+        });
+        assert!(matches!(result, Err(OrtError::Run(_))));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn does_not_retry_other_errors() {
+        let mut attempts = 0;
+        let result: Result<()> = policy().run(|| {
+            attempts += 1;
+            Err(run_failure(OrtErrorCode::InvalidArgument))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+}
@@ -0,0 +1,199 @@
+//! Module containing the options of individual inference runs
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use tracing::{debug, error};
+
+use onnxruntime_sys as sys;
+
+use crate::{
+    error::{call_ort, OrtError, Result},
+    g_ort, LoggingLevel,
+};
+
+/// Options of a single inference run
+///
+/// Passed to [`Session::run_with_options()`](../session/struct.Session.html#method.run_with_options).
+/// The same options can be reused for many runs.
+#[derive(Debug)]
+pub struct RunOptions {
+    pub(crate) ptr: *mut sys::OrtRunOptions,
+}
+
+// The options are only mutated through `&mut self`, and ONNX Runtime only reads them during runs.
+unsafe impl Send for RunOptions {}
+unsafe impl Sync for RunOptions {}
+
+impl RunOptions {
+    /// Create the default run options
+    pub fn new() -> Result<RunOptions> {
+        let mut ptr: *mut sys::OrtRunOptions = std::ptr::null_mut();
+        unsafe { call_ort(|ort| ort.CreateRunOptions.unwrap()(&mut ptr)) }
+            .map_err(OrtError::RunOptions)?;
+        assert_ne!(ptr, std::ptr::null_mut());
+        Ok(RunOptions { ptr })
+    }
+
+    /// Set the minimum severity of the messages logged during the run
+    ///
+    /// This overrides the level of the [environment](../environment/struct.EnvBuilder.html#method.with_log_level)
+    /// and of the [session](../session/struct.SessionBuilder.html#method.with_log_level) for this
+    /// run only.
+    pub fn set_log_level(&mut self, log_level: LoggingLevel) -> Result<()> {
+        unsafe {
+            call_ort(|ort| {
+                ort.RunOptionsSetRunLogSeverityLevel.unwrap()(self.ptr, log_level.severity())
+            })
+        }
+        .map_err(OrtError::RunOptions)
+    }
+
+    /// Ask ONNX Runtime to stop the runs using these options
+    ///
+    /// This can be called from another thread while a run is in progress: it takes `&self` so that
+    /// the options can be shared (e.g. in an `Arc`). Terminating is cooperative: ONNX Runtime only
+    /// checks the flag between nodes, so a long running node is not interrupted. The terminated
+    /// run fails with [`OrtError::Run`](../error/enum.OrtError.html#variant.Run), as will any
+    /// later run with these options until [`unset_terminate()`](#method.unset_terminate) is called.
+    pub fn set_terminate(&self) -> Result<()> {
+        unsafe { call_ort(|ort| ort.RunOptionsSetTerminate.unwrap()(self.ptr)) }
+            .map_err(OrtError::RunOptions)
+    }
+
+    /// Clear the flag set by [`set_terminate()`](#method.set_terminate), so that the options can
+    /// be used for new runs
+    pub fn unset_terminate(&self) -> Result<()> {
+        unsafe { call_ort(|ort| ort.RunOptionsUnsetTerminate.unwrap()(self.ptr)) }
+            .map_err(OrtError::RunOptions)
+    }
+}
+
+impl Drop for RunOptions {
+    #[tracing::instrument]
+    fn drop(&mut self) {
+        debug!("Dropping the run options.");
+        assert_ne!(self.ptr, std::ptr::null_mut());
+        unsafe { g_ort().ReleaseRunOptions.unwrap()(self.ptr) };
+        self.ptr = std::ptr::null_mut();
+    }
+}
+
+/// A handle to cancel runs, from any thread
+///
+/// Pass it to [`Session::run_with_cancellation()`](../session/struct.Session.html#method.run_with_cancellation)
+/// or [`Session::run_async()`](../session/struct.Session.html#method.run_async):
+/// calling [`cancel()`](#method.cancel) (for example when a server shuts down) terminates the
+/// runs in progress and makes them, and any later run with the token, fail with
+/// [`OrtError::Cancelled`](../error/enum.OrtError.html#variant.Cancelled). Clones share the same
+/// state.
+///
+/// Like [`RunOptions::set_terminate()`](struct.RunOptions.html#method.set_terminate), which it
+/// relies on, cancellation is cooperative: a run stops once the node running when it is cancelled
+/// completes.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationState>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationState {
+    cancelled: AtomicBool,
+    /// Options of the runs in progress
+    runs: Mutex<Vec<Arc<RunOptions>>>,
+}
+
+impl CancellationToken {
+    /// Create a token which is not cancelled
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
+
+    /// Cancel the runs using this token, in progress and future
+    pub fn cancel(&self) {
+        debug!("Cancelling runs.");
+        let runs = self.inner.runs.lock().unwrap();
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        for run_options in runs.iter() {
+            if let Err(e) = run_options.set_terminate() {
+                error!("Failed to terminate a run: {}", e);
+            }
+        }
+    }
+
+    /// Whether [`cancel()`](#method.cancel) was called
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Register the options of a run, to be terminated on cancellation
+    ///
+    /// Returns an error if the token is already cancelled.
+    pub(crate) fn register(&self, run_options: &Arc<RunOptions>) -> Result<()> {
+        let mut runs = self.inner.runs.lock().unwrap();
+        if self.is_cancelled() {
+            return Err(OrtError::Cancelled);
+        }
+        runs.push(Arc::clone(run_options));
+        Ok(())
+    }
+
+    /// Unregister the options of a completed run
+    pub(crate) fn unregister(&self, run_options: &Arc<RunOptions>) {
+        self.inner
+            .runs
+            .lock()
+            .unwrap()
+            .retain(|registered| !Arc::ptr_eq(registered, run_options));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn run_options_log_levels() {
+        let mut run_options = RunOptions::new().unwrap();
+        for log_level in &[
+            LoggingLevel::Verbose,
+            LoggingLevel::Info,
+            LoggingLevel::Warning,
+            LoggingLevel::Error,
+            LoggingLevel::Fatal,
+        ] {
+            run_options.set_log_level(*log_level).unwrap();
+
+            let mut severity = -1;
+            unsafe {
+                call_ort(|ort| {
+                    ort.RunOptionsGetRunLogSeverityLevel.unwrap()(run_options.ptr, &mut severity)
+                })
+            }
+            .unwrap();
+            assert_eq!(severity, log_level.severity());
+        }
+    }
+
+    #[test]
+    fn cancellation_token() {
+        let token = CancellationToken::new();
+        let run_options = Arc::new(RunOptions::new().unwrap());
+        token.register(&run_options).unwrap();
+        assert!(!token.is_cancelled());
+
+        let clone = token.clone();
+        std::thread::spawn(move || clone.cancel()).join().unwrap();
+        assert!(token.is_cancelled());
+        token.unregister(&run_options);
+        assert!(token.inner.runs.lock().unwrap().is_empty());
+
+        assert!(matches!(
+            token.register(&run_options),
+            Err(OrtError::Cancelled)
+        ));
+    }
+}
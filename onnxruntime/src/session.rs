@@ -1,6 +1,20 @@
 //! Module containing session types
 
-use std::{convert::TryInto as _, ffi::CString, fmt::Debug, path::Path};
+use std::{
+    collections::HashMap,
+    convert::TryInto as _,
+    ffi::CString,
+    fmt::Debug,
+    fs::{self, File},
+    io::{BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
 
 #[cfg(not(target_family = "windows"))]
 use std::os::unix::ffi::OsStrExt;
@@ -9,25 +23,59 @@ use std::os::windows::ffi::OsStrExt;
 
 #[cfg(feature = "model-fetching")]
 use std::env;
+#[cfg(feature = "tokio")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
-use ndarray::Array;
-use tracing::{debug, error};
+use ndarray::{Array, ArrayBase, ArrayView, Axis, CowArray, Slice};
+use tracing::{debug, error, warn};
 
 use onnxruntime_sys as sys;
 
 use crate::{
-    char_p_to_string,
+    allocator::OrtAllocator,
+    char_p_to_string, compression,
     environment::Environment,
-    error::{call_ort, status_to_result, NonMatchingDimensionsError, OrtError, Result},
+    error::{
+        call_ort, run_status_to_result, status_to_result, NonMatchingDimensionsError, OrtApiError,
+        OrtError, Result,
+    },
     g_ort,
+    io_binding::IoBinding,
     memory::MemoryInfo,
-    tensor::{DynOrtTensor, OrtTensor, TensorElementDataType, TypeToTensorElementDataType},
-    AllocatorType, GraphOptimizationLevel, MemType,
+    metadata::ModelMetadata,
+    model_proto::ModelVersions,
+    onnxruntime::capture_warnings,
+    preprocessor::{PreprocessedSession, Preprocessor},
+    retry::RetryPolicy,
+    run_options::{CancellationToken, RunOptions},
+    tensor::{
+        input_tensor::{cast_array, AsOrtValue},
+        DynOrtTensor, InputTensor, OrtTensor, TensorElementDataType, TypeToTensorElementDataType,
+        ValueType,
+    },
+    AllocatorType, GraphOptimizationLevel, LoggingLevel, MemType,
 };
 
+#[cfg(feature = "serde")]
+use crate::tensor::serde_tensor::SerializableTensor;
+#[cfg(feature = "cuda")]
+use crate::ArenaExtendStrategy;
 #[cfg(feature = "model-fetching")]
 use crate::{download::AvailableOnnxModel, error::OrtDownloadError};
 
+// Only exported by ONNX Runtime builds with ACL support
+#[cfg(all(feature = "acl", target_arch = "aarch64"))]
+extern "C" {
+    fn OrtSessionOptionsAppendExecutionProvider_ACL(
+        options: *mut sys::OrtSessionOptions,
+        use_arena: std::os::raw::c_int,
+    ) -> sys::OrtStatusPtr;
+}
+
 /// Type used to create a session using the _builder pattern_
 ///
 /// A `SessionBuilder` is created by calling the
@@ -49,7 +97,7 @@ use crate::{download::AvailableOnnxModel, error::OrtDownloadError};
 ///     .with_name("test")
 ///     .with_log_level(LoggingLevel::Verbose)
 ///     .build()?;
-/// let mut session = environment
+/// let session = environment
 ///     .new_session_builder()?
 ///     .with_optimization_level(GraphOptimizationLevel::Basic)?
 ///     .with_number_threads(1)?
@@ -64,8 +112,20 @@ pub struct SessionBuilder<'a> {
 
     allocator: AllocatorType,
     memory_type: MemType,
+    strict_load: bool,
+    fixed_batch: Option<usize>,
+    custom_ops_libraries: Vec<CustomOpsLibrary>,
+    initializers: Vec<Box<dyn AsOrtValue>>,
+    optimization_cache: Option<PathBuf>,
+    auto_cast: bool,
+    lossy_cast_policy: LossyCastPolicy,
 }
 
+// The session options are owned by the builder, which only mutates them through `self`: it can be
+// moved to another thread. It is not `Sync` since ONNX Runtime doesn't guarantee concurrent reads
+// and writes of session options are safe (and sharing a builder is of no use anyway).
+unsafe impl<'a> Send for SessionBuilder<'a> {}
+
 impl<'a> Drop for SessionBuilder<'a> {
     #[tracing::instrument]
     fn drop(&mut self) {
@@ -84,12 +144,26 @@ impl<'a> SessionBuilder<'a> {
         assert_eq!(status, std::ptr::null_mut());
         assert_ne!(session_options_ptr, std::ptr::null_mut());
 
-        Ok(SessionBuilder {
+        let builder = SessionBuilder {
             env,
             session_options_ptr,
             allocator: AllocatorType::Arena,
             memory_type: MemType::Default,
-        })
+            strict_load: false,
+            fixed_batch: None,
+            custom_ops_libraries: Vec::new(),
+            initializers: Vec::new(),
+            optimization_cache: None,
+            auto_cast: false,
+            lossy_cast_policy: LossyCastPolicy::default(),
+        };
+        if env.has_global_thread_pool() {
+            unsafe {
+                call_ort(|ort| ort.DisablePerSessionThreads.unwrap()(builder.session_options_ptr))
+            }
+            .map_err(OrtError::SessionOptions)?;
+        }
+        Ok(builder)
     }
 
     /// Configure the session to use a number of threads
@@ -120,6 +194,202 @@ impl<'a> SessionBuilder<'a> {
         Ok(self)
     }
 
+    /// Set the minimum severity of the messages logged by the session
+    ///
+    /// This overrides the level of the [environment](../environment/struct.EnvBuilder.html#method.with_log_level)
+    /// for this session only.
+    pub fn with_log_level(self, log_level: LoggingLevel) -> Result<SessionBuilder<'a>> {
+        unsafe {
+            call_ort(|ort| {
+                ort.SetSessionLogSeverityLevel.unwrap()(
+                    self.session_options_ptr,
+                    log_level.severity(),
+                )
+            })
+        }
+        .map_err(OrtError::SessionOptions)?;
+        Ok(self)
+    }
+
+    /// Disable specific graph optimizers, by name (e.g. `"ConstantFolding"`)
+    ///
+    /// This is a targeted alternative to lowering the
+    /// [optimization level](#method.with_optimization_level) when a single optimizer causes issues.
+    ///
+    /// **NOTE**: This sets the `optimization.disable_specified_optimizers` session configuration
+    /// entry; ONNX Runtime versions which don't know that entry ignore it.
+    pub fn with_disabled_optimizers(self, optimizers: &[&str]) -> Result<SessionBuilder<'a>> {
+        self.add_config_entry(DISABLE_SPECIFIED_OPTIMIZERS_KEY, &optimizers.join(","))?;
+        Ok(self)
+    }
+
+    /// Make the session's computations deterministic
+    ///
+    /// **Unsupported with ONNX Runtime 1.6**, the version this crate is built against:
+    /// `SetDeterministicCompute` was added to ONNX Runtime later. Only disabling it (the default)
+    /// is accepted.
+    ///
+    /// # Errors
+    ///
+    /// Enabling it returns
+    /// [`OrtError::UnsupportedByOrtVersion`](../error/enum.OrtError.html#variant.UnsupportedByOrtVersion).
+    pub fn with_deterministic_compute(self, deterministic: bool) -> Result<SessionBuilder<'a>> {
+        if deterministic {
+            return Err(OrtError::UnsupportedByOrtVersion("Deterministic compute"));
+        }
+        Ok(self)
+    }
+
+    /// Append the CPU execution provider, with its own memory arena or not
+    ///
+    /// ONNX Runtime always falls back to the CPU execution provider for the nodes other providers
+    /// can't run: appending it is only needed to configure it. Execution providers are tried in
+    /// the order they are appended, so it should be appended after the others.
+    ///
+    /// `use_arena` is specific to the provider's allocations, unlike the session's
+    /// [allocator](#method.with_allocator) used for inputs and outputs.
+    pub fn with_cpu(self, use_arena: bool) -> Result<SessionBuilder<'a>> {
+        extern "C" {
+            fn OrtSessionOptionsAppendExecutionProvider_CPU(
+                options: *mut sys::OrtSessionOptions,
+                use_arena: std::os::raw::c_int,
+            ) -> sys::OrtStatusPtr;
+        }
+
+        debug!(use_arena, "Appending CPU execution provider.");
+        let status = unsafe {
+            OrtSessionOptionsAppendExecutionProvider_CPU(
+                self.session_options_ptr,
+                use_arena as std::os::raw::c_int,
+            )
+        };
+        status_to_result(status).map_err(|error| OrtError::AppendExecutionProvider {
+            provider: "CPU",
+            error,
+        })?;
+        Ok(self)
+    }
+
+    /// Append the OpenVINO execution provider, for Intel CPUs, GPUs and VPUs
+    ///
+    /// ONNX Runtime must have been built with OpenVINO support, otherwise
+    /// [`OrtError::AppendExecutionProvider`](../error/enum.OrtError.html#variant.AppendExecutionProvider)
+    /// is returned.
+    ///
+    /// **NOTE**: Blob caching ([`OpenVinoOptions::cache_dir`](struct.OpenVinoOptions.html#structfield.cache_dir))
+    /// was added to ONNX Runtime after the version this crate is built against (1.6): setting it
+    /// returns [`OrtError::UnsupportedByOrtVersion`](../error/enum.OrtError.html#variant.UnsupportedByOrtVersion).
+    #[cfg(feature = "openvino")]
+    pub fn with_openvino(self, options: OpenVinoOptions) -> Result<SessionBuilder<'a>> {
+        if options.cache_dir.is_some() {
+            return Err(OrtError::UnsupportedByOrtVersion("OpenVINO blob caching"));
+        }
+
+        // ONNX Runtime 1.6 encodes the precision in the device type (e.g. `GPU_FP16`)
+        let device_type = match &options.precision {
+            Some(precision) => format!("{}_{}", options.device_type, precision),
+            None => options.device_type.clone(),
+        };
+        let device_type = CString::new(device_type)?;
+        let device_id = CString::new("")?;
+        let provider_options = sys::OrtOpenVINOProviderOptions {
+            device_type: device_type.as_ptr(),
+            enable_vpu_fast_compile: 0,
+            device_id: device_id.as_ptr(),
+            num_of_threads: options.num_threads.unwrap_or(0) as u64,
+        };
+
+        unsafe {
+            call_ort(|ort| {
+                ort.SessionOptionsAppendExecutionProvider_OpenVINO.unwrap()(
+                    self.session_options_ptr,
+                    &provider_options,
+                )
+            })
+        }
+        .map_err(|error| OrtError::AppendExecutionProvider {
+            provider: "OpenVINO",
+            error,
+        })?;
+        Ok(self)
+    }
+
+    /// Append the CUDA execution provider, running inference on an NVIDIA GPU
+    ///
+    /// ONNX Runtime must have been built with CUDA support (see the `ORT_USE_CUDA` build
+    /// environment variable of `onnxruntime-sys`), otherwise
+    /// [`OrtError::AppendExecutionProvider`](../error/enum.OrtError.html#variant.AppendExecutionProvider)
+    /// is returned.
+    #[cfg(feature = "cuda")]
+    pub fn with_cuda(self, options: CudaProviderOptions) -> Result<SessionBuilder<'a>> {
+        let provider_options = sys::OrtCUDAProviderOptions {
+            device_id: options.device_id,
+            cudnn_conv_algo_search: sys::OrtCudnnConvAlgoSearch::EXHAUSTIVE,
+            cuda_mem_limit: options.gpu_mem_limit.unwrap_or(usize::MAX) as _,
+            arena_extend_strategy: options.arena_extend_strategy.as_c_int(),
+            do_copy_in_default_stream: options.copy_in_default_stream as _,
+        };
+
+        unsafe {
+            call_ort(|ort| {
+                ort.SessionOptionsAppendExecutionProvider_CUDA.unwrap()(
+                    self.session_options_ptr,
+                    &provider_options,
+                )
+            })
+        }
+        .map_err(|error| OrtError::AppendExecutionProvider {
+            provider: "CUDA",
+            error,
+        })?;
+        Ok(self)
+    }
+
+    /// Append the ACL (Arm Compute Library) execution provider, accelerating inference on Arm CPUs,
+    /// with its own memory arena or not
+    ///
+    /// ONNX Runtime must have been built with ACL support (`--use_acl`): the provider is appended
+    /// through `OrtSessionOptionsAppendExecutionProvider_ACL`, which other builds don't export.
+    /// The symbol is only referenced with the `acl` feature, so that linking against other builds
+    /// keeps working without it.
+    #[cfg(all(feature = "acl", target_arch = "aarch64"))]
+    pub fn with_acl(self, use_arena: bool) -> Result<SessionBuilder<'a>> {
+        debug!(use_arena, "Appending ACL execution provider.");
+        let status = unsafe {
+            OrtSessionOptionsAppendExecutionProvider_ACL(
+                self.session_options_ptr,
+                use_arena as std::os::raw::c_int,
+            )
+        };
+        status_to_result(status).map_err(|error| OrtError::AppendExecutionProvider {
+            provider: "ACL",
+            error,
+        })?;
+        Ok(self)
+    }
+
+    /// Cap the memory the session allocates on the CPU to `max_bytes`
+    ///
+    /// Runs needing more memory fail with
+    /// [`OrtError::OutOfMemory`](../error/enum.OrtError.html#variant.OutOfMemory) instead of
+    /// growing the process, which helps bounding the footprint of a multi-tenant server.
+    ///
+    /// **NOTE**: The version of ONNX Runtime this crate is built against (1.6) cannot limit the
+    /// memory of a single session. The limit is instead enforced by a CPU memory arena registered
+    /// on the environment, which the session then uses: it is shared by all the sessions built with
+    /// a memory limit, and they must all use the same one (another limit returns
+    /// [`OrtError::MemoryLimitConflict`](../error/enum.OrtError.html#variant.MemoryLimitConflict)).
+    /// The limit is best effort: memory ONNX Runtime allocates outside of the arena (for example
+    /// for the model's weights, or by other execution providers) is not accounted for. GPU memory
+    /// is limited separately, with
+    /// [`CudaProviderOptions::gpu_mem_limit`](struct.CudaProviderOptions.html#structfield.gpu_mem_limit)
+    /// for CUDA; other execution providers have no limit.
+    pub fn with_memory_limit(self, max_bytes: usize) -> Result<SessionBuilder<'a>> {
+        self.env.register_cpu_arena(max_bytes)?;
+        self.add_config_entry(USE_ENV_ALLOCATORS_KEY, "1")?;
+        Ok(self)
+    }
+
     /// Set the session's allocator
     ///
     /// Defaults to [`AllocatorType::Arena`](../enum.AllocatorType.html#variant.Arena)
@@ -136,6 +406,59 @@ impl<'a> SessionBuilder<'a> {
         Ok(self)
     }
 
+    /// Fail to load models for which ONNX Runtime logs warnings
+    ///
+    /// ONNX Runtime often logs a warning and carries on when a model has issues, which can go
+    /// unnoticed. In strict mode, any warning (or error) logged while the session is created makes
+    /// loading fail with [`OrtError::StrictLoadWarning`](../error/enum.OrtError.html#variant.StrictLoadWarning),
+    /// which is useful to validate exported models (for example in CI).
+    ///
+    /// Warnings are only seen if they are logged: the [environment's](../environment/struct.EnvBuilder.html#method.with_log_level)
+    /// (or the [session's](#method.with_log_level)) log level must be `Warning` or more verbose.
+    /// Only the messages logged by the thread loading the model are captured.
+    pub fn with_strict_load(mut self) -> Result<SessionBuilder<'a>> {
+        self.strict_load = true;
+        Ok(self)
+    }
+
+    /// Cast inputs to the element type expected by the model
+    ///
+    /// When enabled, a numeric input whose element type doesn't match the model's (for example an
+    /// `f64` array given to a `float` input) is cast to the expected type before running, and a
+    /// warning is logged. This smooths over models exported with a different precision than the
+    /// data fed to them. Casts which change values (rounding `f64` to `f32`, saturating
+    /// out of range integers, ...) are then handled according to the
+    /// [lossy cast policy](#method.with_lossy_cast_policy).
+    ///
+    /// Strings are never cast. Disabled by default: mismatched inputs are given to ONNX Runtime
+    /// as they are, which rejects them.
+    pub fn with_auto_cast(mut self, auto_cast: bool) -> Result<SessionBuilder<'a>> {
+        self.auto_cast = auto_cast;
+        Ok(self)
+    }
+
+    /// Set what to do when [auto-cast](#method.with_auto_cast) changes the value of some input
+    /// elements
+    ///
+    /// Defaults to [`LossyCastPolicy::Warn`](enum.LossyCastPolicy.html#variant.Warn).
+    pub fn with_lossy_cast_policy(mut self, policy: LossyCastPolicy) -> Result<SessionBuilder<'a>> {
+        self.lossy_cast_policy = policy;
+        Ok(self)
+    }
+
+    /// Fix the batch size: the dynamic leading dimension of the model's inputs is overridden to
+    /// `batch_size`, as if the model had been exported with a fixed batch
+    ///
+    /// This lets ONNX Runtime optimize the model for that size. The inputs' leading dimensions
+    /// are found by loading the model a first time without the override. Inputs with a fixed
+    /// leading dimension are left as they are; all the others must share the same named dimension,
+    /// or loading the model fails with
+    /// [`OrtError::InconsistentBatchDimension`](../error/enum.OrtError.html#variant.InconsistentBatchDimension).
+    pub fn with_fixed_batch(mut self, batch_size: usize) -> Result<SessionBuilder<'a>> {
+        self.fixed_batch = Some(batch_size);
+        Ok(self)
+    }
+
     /// Download an ONNX pre-trained model from the [ONNX Model Zoo](https://github.com/onnx/models) and commit the session
     #[cfg(feature = "model-fetching")]
     pub fn with_model_downloaded<M>(self, model: M) -> Result<Session<'a>>
@@ -155,13 +478,141 @@ impl<'a> SessionBuilder<'a> {
     // TODO: Add all functions changing the options.
     //       See all OrtApi methods taking a `options: *mut OrtSessionOptions`.
 
+    /// Save the model, once optimized, to a file
+    ///
+    /// The model is saved in the [ORT format](https://www.onnxruntime.ai/docs/how-to/mobile/model-conversion.html)
+    /// if `path` has the `.ort` extension, and in the ONNX format otherwise.
+    pub fn with_optimized_model_path<P>(self, path: P) -> Result<SessionBuilder<'a>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path_to_ort_chars(path.as_ref());
+        unsafe {
+            call_ort(|ort| {
+                ort.SetOptimizedModelFilePath.unwrap()(self.session_options_ptr, path.as_ptr())
+            })
+        }
+        .map_err(OrtError::SessionOptions)?;
+        Ok(self)
+    }
+
+    /// Profile the session, writing its profile to a JSON file named after `profile_file_prefix`
+    ///
+    /// ONNX Runtime appends the date and time to the prefix; the file is complete once the
+    /// session is dropped. Its timestamps are relative to
+    /// [`Session::profiling_start_time_ns()`](struct.Session.html#method.profiling_start_time_ns).
+    pub fn with_profiling<P>(self, profile_file_prefix: P) -> Result<SessionBuilder<'a>>
+    where
+        P: AsRef<Path>,
+    {
+        let prefix = path_to_ort_chars(profile_file_prefix.as_ref());
+        unsafe {
+            call_ort(|ort| ort.EnableProfiling.unwrap()(self.session_options_ptr, prefix.as_ptr()))
+        }
+        .map_err(OrtError::SessionOptions)?;
+        Ok(self)
+    }
+
+    /// Cache the optimized model in the file `path`, to skip graph optimizations on the next loads
+    ///
+    /// The first time a model is loaded with [`with_model_from_file()`](#method.with_model_from_file),
+    /// it is optimized as usual and the result is saved to `path` (see
+    /// [`with_optimized_model_path()`](#method.with_optimized_model_path)), along with a hash of
+    /// the model in `path` suffixed with `.hash`. The following loads find the cache up to date and
+    /// load it instead, with optimizations disabled. Once the source model changes, its hash
+    /// doesn't match anymore and the cache is rebuilt.
+    ///
+    /// Optimizations depend on the session options (optimization level, execution providers...):
+    /// a cache must only be reused with the same options. Failing to write the hash only logs a
+    /// warning, the cache is then rebuilt on the next load.
+    pub fn with_optimization_cache<P>(mut self, path: P) -> Result<SessionBuilder<'a>>
+    where
+        P: AsRef<Path>,
+    {
+        self.optimization_cache = Some(path.as_ref().to_path_buf());
+        Ok(self)
+    }
+
+    /// Register the custom operators of a shared library, so that models using them can be loaded
+    ///
+    /// The library must export a `RegisterCustomOps` function, as described in ONNX Runtime's
+    /// [documentation](https://www.onnxruntime.ai/docs/how-to/add-custom-op.html). It stays loaded
+    /// until the session is dropped.
+    pub fn with_custom_ops_library<P>(mut self, path: P) -> Result<SessionBuilder<'a>>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(OrtError::FileDoesNotExists {
+                filename: path.to_path_buf(),
+            });
+        }
+        let library_path = path.to_str().ok_or_else(|| OrtError::NonUtf8Path {
+            path: path.to_path_buf(),
+        })?;
+        let library_path = CString::new(library_path)?;
+
+        let mut handle: *mut std::os::raw::c_void = std::ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.RegisterCustomOpsLibrary.unwrap()(
+                    self.session_options_ptr,
+                    library_path.as_ptr(),
+                    &mut handle,
+                )
+            })
+        }
+        .map_err(|error| OrtError::CustomOpsLibrary {
+            path: path.to_path_buf(),
+            error,
+        })?;
+        assert_ne!(handle, std::ptr::null_mut());
+
+        self.custom_ops_libraries.push(CustomOpsLibrary { handle });
+        Ok(self)
+    }
+
+    /// Override the initializer `name` of the model (for example a weight matrix) with `tensor`
+    ///
+    /// The tensor must have the shape and element type of the initializer it replaces. It is kept
+    /// alive, and read in place, for as long as the session.
+    pub fn with_initializer<T, D>(
+        mut self,
+        name: &str,
+        tensor: OrtTensor<'static, T, D>,
+    ) -> Result<SessionBuilder<'a>>
+    where
+        T: TypeToTensorElementDataType + Debug + Clone + 'static,
+        D: ndarray::Dimension + 'static,
+    {
+        let name = CString::new(name)?;
+        let tensor: Box<dyn AsOrtValue> = Box::new(tensor);
+        unsafe {
+            call_ort(|ort| {
+                ort.AddInitializer.unwrap()(
+                    self.session_options_ptr,
+                    name.as_ptr(),
+                    tensor.ort_value(),
+                )
+            })
+        }
+        .map_err(OrtError::SessionOptions)?;
+        self.initializers.push(tensor);
+        Ok(self)
+    }
+
     /// Load an ONNX graph from a file and commit the session
-    pub fn with_model_from_file<P>(self, model_filepath_ref: P) -> Result<Session<'a>>
+    ///
+    /// Models in the [ORT format](https://www.onnxruntime.ai/docs/how-to/mobile/model-conversion.html)
+    /// (`.ort` files, as used by the minimal builds of ONNX Runtime for mobile and embedded devices)
+    /// are detected and loaded as well. Their [IR](struct.Session.html#method.ir_version) and
+    /// [opset](struct.Session.html#method.opset_version) versions are not available.
+    pub fn with_model_from_file<P>(mut self, model_filepath_ref: P) -> Result<Session<'a>>
     where
-        P: AsRef<Path> + 'a,
+        P: AsRef<Path>,
     {
         let model_filepath = model_filepath_ref.as_ref();
-        let mut session_ptr: *mut sys::OrtSession = std::ptr::null_mut();
 
         if !model_filepath.exists() {
             return Err(OrtError::FileDoesNotExists {
@@ -169,64 +620,75 @@ impl<'a> SessionBuilder<'a> {
             });
         }
 
-        // Build an OsString than a vector of bytes to pass to C
-        let model_path = std::ffi::OsString::from(model_filepath);
-        #[cfg(target_family = "windows")]
-        let model_path: Vec<u16> = model_path
-            .encode_wide()
-            .chain(std::iter::once(0)) // Make sure we have a null terminated string
-            .collect();
-        #[cfg(not(target_family = "windows"))]
-        let model_path: Vec<std::os::raw::c_char> = model_path
-            .as_bytes()
-            .iter()
-            .chain(std::iter::once(&b'\0')) // Make sure we have a null terminated string
-            .map(|b| *b as std::os::raw::c_char)
-            .collect();
+        if let Some(cache_path) = self.optimization_cache.take() {
+            return self.with_optimization_cache_from_file(model_filepath, cache_path);
+        }
+
+        let ort_format = is_ort_format_file(model_filepath);
+        if ort_format {
+            self.add_config_entry(LOAD_MODEL_FORMAT_KEY, "ORT")?;
+        }
+
+        let model_path = path_to_ort_chars(model_filepath);
 
         let env_ptr: *const sys::OrtEnv = self.env.env_ptr();
 
-        let status = unsafe {
+        let session_ptr = self.create_session(|session_ptr| unsafe {
             g_ort().CreateSession.unwrap()(
                 env_ptr,
                 model_path.as_ptr(),
                 self.session_options_ptr,
-                &mut session_ptr,
+                session_ptr,
             )
+        })?;
+
+        // Versions are read from the ONNX protobuf, which ORT format models don't contain
+        let versions = if ort_format {
+            ModelVersions::default()
+        } else {
+            File::open(model_filepath)
+                .and_then(|file| ModelVersions::read(BufReader::new(file)))
+                .unwrap_or_default()
         };
-        status_to_result(status).map_err(OrtError::Session)?;
-        assert_eq!(status, std::ptr::null_mut());
-        assert_ne!(session_ptr, std::ptr::null_mut());
-
-        let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
-        let status = unsafe { g_ort().GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr) };
-        status_to_result(status).map_err(OrtError::Allocator)?;
-        assert_eq!(status, std::ptr::null_mut());
-        assert_ne!(allocator_ptr, std::ptr::null_mut());
 
-        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default)?;
+        self.commit(session_ptr, versions)
+    }
 
-        // Extract input and output properties
-        let num_input_nodes = dangerous::extract_inputs_count(session_ptr)?;
-        let num_output_nodes = dangerous::extract_outputs_count(session_ptr)?;
-        let inputs = (0..num_input_nodes)
-            .map(|i| dangerous::extract_input(session_ptr, allocator_ptr, i))
-            .collect::<Result<Vec<Input>>>()?;
-        let outputs = (0..num_output_nodes)
-            .map(|i| dangerous::extract_output(session_ptr, allocator_ptr, i))
-            .collect::<Result<Vec<Output>>>()?;
+    fn with_optimization_cache_from_file(
+        self,
+        model_filepath: &Path,
+        cache_path: PathBuf,
+    ) -> Result<Session<'a>> {
+        let model_hash = fs::read(model_filepath)
+            .map(|model_bytes| format!("{:016x}", fnv1a_hash(&model_bytes)))
+            .map_err(OrtError::ModelRead)?;
+        let mut hash_path = cache_path.clone().into_os_string();
+        hash_path.push(".hash");
+        let hash_path = PathBuf::from(hash_path);
+
+        let cached_hash = fs::read_to_string(&hash_path).ok();
+        if cache_path.exists() && cached_hash.as_deref() == Some(model_hash.as_str()) {
+            debug!(?cache_path, "Loading optimized model from cache.");
+            return self
+                .with_optimization_level(GraphOptimizationLevel::DisableAll)?
+                .with_model_from_file(cache_path);
+        }
 
-        Ok(Session {
-            env: self.env,
-            session_ptr,
-            allocator_ptr,
-            memory_info,
-            inputs,
-            outputs,
-        })
+        debug!(?cache_path, "Saving optimized model to cache.");
+        let session = self
+            .with_optimized_model_path(&cache_path)?
+            .with_model_from_file(model_filepath)?;
+        if let Err(error) = fs::write(&hash_path, model_hash) {
+            warn!(?hash_path, %error, "Failed to write the hash of the optimization cache.");
+        }
+        Ok(session)
     }
 
     /// Load an ONNX graph from memory and commit the session
+    ///
+    /// As with [`with_model_from_file()`](#method.with_model_from_file), models in the ORT format
+    /// are detected and loaded as well. ONNX Runtime copies the bytes: they don't need to outlive
+    /// the session.
     pub fn with_model_from_memory<B>(self, model_bytes: B) -> Result<Session<'a>>
     where
         B: AsRef<[u8]>,
@@ -234,34 +696,177 @@ impl<'a> SessionBuilder<'a> {
         self.with_model_from_memory_monomorphized(model_bytes.as_ref())
     }
 
-    fn with_model_from_memory_monomorphized(self, model_bytes: &[u8]) -> Result<Session<'a>> {
-        let mut session_ptr: *mut sys::OrtSession = std::ptr::null_mut();
+    /// Load an ONNX graph from a reader and commit the session
+    ///
+    /// The whole stream is read into memory first: ONNX Runtime needs the model as a contiguous
+    /// buffer.
+    pub fn with_model_from_reader<R>(self, mut reader: R) -> Result<Session<'a>>
+    where
+        R: Read,
+    {
+        let mut model_bytes = Vec::new();
+        reader
+            .read_to_end(&mut model_bytes)
+            .map_err(OrtError::ModelRead)?;
+        self.with_model_from_memory_monomorphized(&model_bytes)
+    }
 
-        let env_ptr: *const sys::OrtEnv = self.env.env_ptr();
+    /// Load a compressed ONNX graph from a file and commit the session
+    ///
+    /// See [`with_model_from_compressed_reader()`](#method.with_model_from_compressed_reader).
+    pub fn with_model_from_compressed_file<P>(self, model_filepath: P) -> Result<Session<'a>>
+    where
+        P: AsRef<Path>,
+    {
+        let model_filepath = model_filepath.as_ref();
+        if !model_filepath.exists() {
+            return Err(OrtError::FileDoesNotExists {
+                filename: model_filepath.to_path_buf(),
+            });
+        }
+        let file = File::open(model_filepath).map_err(OrtError::ModelRead)?;
+        self.with_model_from_compressed_reader(BufReader::new(file))
+    }
 
-        let status = unsafe {
-            let model_data = model_bytes.as_ptr() as *const std::ffi::c_void;
-            let model_data_length = model_bytes.len() as u64;
-            g_ort().CreateSessionFromArray.unwrap()(
-                env_ptr,
-                model_data,
-                model_data_length,
-                self.session_options_ptr,
-                &mut session_ptr,
-            )
+    /// Load a compressed ONNX graph from a reader and commit the session
+    ///
+    /// Models compressed with gzip (with the `gzip` feature) or zstd (with the `zstd` feature) are
+    /// detected by their magic bytes and decompressed in memory before being loaded, sparing a
+    /// separate decompression step when models are stored or shipped compressed. Uncompressed
+    /// models are loaded as they are.
+    ///
+    /// # Errors
+    ///
+    /// [`OrtError::CompressionNotEnabled`](../error/enum.OrtError.html#variant.CompressionNotEnabled)
+    /// is returned if the model is compressed with a codec whose feature is not enabled, and
+    /// [`OrtError::ModelRead`](../error/enum.OrtError.html#variant.ModelRead) if the model can't be
+    /// read or decompressed.
+    pub fn with_model_from_compressed_reader<R>(self, mut reader: R) -> Result<Session<'a>>
+    where
+        R: Read,
+    {
+        let mut compressed_bytes = Vec::new();
+        reader
+            .read_to_end(&mut compressed_bytes)
+            .map_err(OrtError::ModelRead)?;
+        let model_bytes = compression::decompress(compressed_bytes)?;
+        self.with_model_from_memory_monomorphized(&model_bytes)
+    }
+
+    /// Create the `OrtSession` with `create`, capturing the warnings of the load in strict mode
+    fn create_session<F>(&self, create: F) -> Result<*mut sys::OrtSession>
+    where
+        F: Fn(*mut *mut sys::OrtSession) -> *mut sys::OrtStatus,
+    {
+        if let Some(batch_size) = self.fixed_batch {
+            self.override_batch_dimension(&create, batch_size)?;
+        }
+
+        let mut session_ptr: *mut sys::OrtSession = std::ptr::null_mut();
+        let (status, warnings) = if self.strict_load {
+            capture_warnings(|| create(&mut session_ptr))
+        } else {
+            (create(&mut session_ptr), Vec::new())
         };
         status_to_result(status).map_err(OrtError::Session)?;
         assert_eq!(status, std::ptr::null_mut());
         assert_ne!(session_ptr, std::ptr::null_mut());
 
+        if !warnings.is_empty() {
+            unsafe { g_ort().ReleaseSession.unwrap()(session_ptr) };
+            return Err(OrtError::StrictLoadWarning(warnings));
+        }
+        Ok(session_ptr)
+    }
+
+    /// Override the inputs' dynamic leading dimension, found by creating a first session with
+    /// `create`
+    fn override_batch_dimension<F>(&self, create: &F, batch_size: usize) -> Result<()>
+    where
+        F: Fn(*mut *mut sys::OrtSession) -> *mut sys::OrtStatus,
+    {
+        let mut session_ptr: *mut sys::OrtSession = std::ptr::null_mut();
+        status_to_result(create(&mut session_ptr)).map_err(OrtError::Session)?;
+        assert_ne!(session_ptr, std::ptr::null_mut());
+
         let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
-        let status = unsafe { g_ort().GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr) };
-        status_to_result(status).map_err(OrtError::Allocator)?;
-        assert_eq!(status, std::ptr::null_mut());
+        let dimensions = unsafe {
+            call_ort(|ort| ort.GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr))
+        }
+        .map_err(OrtError::Allocator)
+        .and_then(|_| dangerous::extract_dynamic_leading_dimensions(session_ptr, allocator_ptr));
+        unsafe { g_ort().ReleaseSession.unwrap()(session_ptr) };
+        let dimensions = dimensions?;
+
+        let name = match dimensions.first() {
+            Some((_, name)) => name.clone(),
+            // No dynamic batch to fix
+            None => return Ok(()),
+        };
+        if name.is_empty() || dimensions.iter().any(|(_, other)| *other != name) {
+            return Err(OrtError::InconsistentBatchDimension { dimensions });
+        }
+
+        debug!(%name, batch_size, "Fixing the batch dimension.");
+        let name = CString::new(name)?;
+        unsafe {
+            call_ort(|ort| {
+                ort.AddFreeDimensionOverrideByName.unwrap()(
+                    self.session_options_ptr,
+                    name.as_ptr(),
+                    batch_size as i64,
+                )
+            })
+        }
+        .map_err(OrtError::SessionOptions)
+    }
+
+    fn add_config_entry(&self, key: &str, value: &str) -> Result<()> {
+        let key = CString::new(key)?;
+        let value = CString::new(value)?;
+        unsafe {
+            call_ort(|ort| {
+                ort.AddSessionConfigEntry.unwrap()(
+                    self.session_options_ptr,
+                    key.as_ptr(),
+                    value.as_ptr(),
+                )
+            })
+        }
+        .map_err(OrtError::SessionOptions)
+    }
+
+    /// Finish building the [`Session`](struct.Session.html) around a freshly created `OrtSession`
+    fn commit(
+        mut self,
+        session_ptr: *mut sys::OrtSession,
+        versions: ModelVersions,
+    ) -> Result<Session<'a>> {
+        // Use the environment's custom allocator if one was registered
+        let allocator_ptr = match self.env.allocator_ptr() {
+            Some(allocator_ptr) => allocator_ptr,
+            None => {
+                let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
+                let status =
+                    unsafe { g_ort().GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr) };
+                status_to_result(status).map_err(OrtError::Allocator)?;
+                assert_eq!(status, std::ptr::null_mut());
+                allocator_ptr
+            }
+        };
         assert_ne!(allocator_ptr, std::ptr::null_mut());
 
         let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default)?;
 
+        let inner = Arc::new(SessionPointerHolder {
+            session_ptr,
+            allocator_ptr,
+            memory_info,
+            _env: self.env.clone(),
+            _custom_ops_libraries: std::mem::take(&mut self.custom_ops_libraries),
+            _initializers: std::mem::take(&mut self.initializers),
+        });
+
         // Extract input and output properties
         let num_input_nodes = dangerous::extract_inputs_count(session_ptr)?;
         let num_output_nodes = dangerous::extract_outputs_count(session_ptr)?;
@@ -271,36 +876,387 @@ impl<'a> SessionBuilder<'a> {
         let outputs = (0..num_output_nodes)
             .map(|i| dangerous::extract_output(session_ptr, allocator_ptr, i))
             .collect::<Result<Vec<Output>>>()?;
+        let num_optional_inputs = dangerous::extract_overridable_initializers_count(session_ptr)?;
+        let optional_inputs = (0..num_optional_inputs)
+            .map(|i| dangerous::extract_overridable_initializer(session_ptr, allocator_ptr, i))
+            .collect::<Result<Vec<Input>>>()?;
 
         Ok(Session {
             env: self.env,
-            session_ptr,
-            allocator_ptr,
-            memory_info,
+            inner,
+            versions,
             inputs,
+            optional_inputs,
             outputs,
+            auto_cast: if self.auto_cast {
+                Some(self.lossy_cast_policy)
+            } else {
+                None
+            },
         })
     }
-}
 
-/// Type storing the session information, built from an [`Environment`](environment/struct.Environment.html)
-#[derive(Debug)]
-pub struct Session<'a> {
-    env: &'a Environment,
-    session_ptr: *mut sys::OrtSession,
-    allocator_ptr: *mut sys::OrtAllocator,
-    memory_info: MemoryInfo,
+    fn with_model_from_memory_monomorphized(self, model_bytes: &[u8]) -> Result<Session<'a>> {
+        let ort_format = is_ort_format(model_bytes);
+        if ort_format {
+            self.add_config_entry(LOAD_MODEL_FORMAT_KEY, "ORT")?;
+        }
+
+        let env_ptr: *const sys::OrtEnv = self.env.env_ptr();
+
+        let session_ptr = self.create_session(|session_ptr| unsafe {
+            let model_data = model_bytes.as_ptr() as *const std::ffi::c_void;
+            let model_data_length = model_bytes.len() as u64;
+            g_ort().CreateSessionFromArray.unwrap()(
+                env_ptr,
+                model_data,
+                model_data_length,
+                self.session_options_ptr,
+                session_ptr,
+            )
+        })?;
+
+        let versions = if ort_format {
+            ModelVersions::default()
+        } else {
+            ModelVersions::read(Cursor::new(model_bytes)).unwrap_or_default()
+        };
+
+        self.commit(session_ptr, versions)
+    }
+}
+
+/// Session configuration key selecting the format of the model to load (`ONNX` or `ORT`)
+const LOAD_MODEL_FORMAT_KEY: &str = "session.load_model_format";
+
+/// Session configuration key making the session use the allocators registered on the environment
+const USE_ENV_ALLOCATORS_KEY: &str = "session.use_env_allocators";
+
+/// Session configuration key listing the optimizers to disable (comma separated)
+const DISABLE_SPECIFIED_OPTIMIZERS_KEY: &str = "optimization.disable_specified_optimizers";
+
+/// Operator type named by an ONNX Runtime error about a missing kernel or operator schema
+fn failing_op_type(message: &str) -> Option<String> {
+    const PATTERNS: &[(&str, char)] = &[
+        ("Failed to find kernel for ", '('),
+        ("Could not find an implementation for ", '('),
+        ("Op type not registered '", '\''),
+    ];
+    PATTERNS.iter().find_map(|(prefix, end)| {
+        let start = message.find(prefix)? + prefix.len();
+        let op_type = &message[start..];
+        let op_type = &op_type[..op_type.find(*end)?];
+        // Operator types are identifiers: anything else is part of another kind of message
+        Some(op_type.to_string())
+            .filter(|op_type| !op_type.is_empty() && !op_type.contains(char::is_whitespace))
+    })
+}
+
+/// Whether the bytes hold a model in the ORT format: a flatbuffer with the `ORTM` file identifier
+fn is_ort_format(model_bytes: &[u8]) -> bool {
+    model_bytes.get(4..8) == Some(&b"ORTM"[..])
+}
+
+fn is_ort_format_file(path: &Path) -> bool {
+    let mut header = [0_u8; 8];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|_| is_ort_format(&header))
+        .unwrap_or(false)
+}
+
+/// 64 bits FNV-1a hash, stable across platforms and Rust versions (unlike `DefaultHasher`)
+fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Handle of a library registered with
+/// [`with_custom_ops_library()`](struct.SessionBuilder.html#method.with_custom_ops_library)
+///
+/// ONNX Runtime loads the library but leaves unloading it to the caller, once the sessions using
+/// its operators are released.
+#[derive(Debug)]
+struct CustomOpsLibrary {
+    handle: *mut std::os::raw::c_void,
+}
+
+impl Drop for CustomOpsLibrary {
+    #[tracing::instrument]
+    fn drop(&mut self) {
+        debug!("Unloading the custom ops library.");
+        unsafe { unload_library(self.handle) };
+        self.handle = std::ptr::null_mut();
+    }
+}
+
+#[cfg(target_family = "windows")]
+unsafe fn unload_library(handle: *mut std::os::raw::c_void) {
+    extern "system" {
+        fn FreeLibrary(module: *mut std::os::raw::c_void) -> i32;
+    }
+    FreeLibrary(handle);
+}
+
+#[cfg(not(target_family = "windows"))]
+unsafe fn unload_library(handle: *mut std::os::raw::c_void) {
+    extern "C" {
+        fn dlclose(handle: *mut std::os::raw::c_void) -> std::os::raw::c_int;
+    }
+    dlclose(handle);
+}
+
+/// Build a null terminated path, in the encoding the C API expects
+#[cfg(target_family = "windows")]
+fn path_to_ort_chars(path: &Path) -> Vec<u16> {
+    path.as_os_str()
+        .encode_wide()
+        .chain(std::iter::once(0)) // Make sure we have a null terminated string
+        .collect()
+}
+
+/// Build a null terminated path, in the encoding the C API expects
+#[cfg(not(target_family = "windows"))]
+fn path_to_ort_chars(path: &Path) -> Vec<std::os::raw::c_char> {
+    path.as_os_str()
+        .as_bytes()
+        .iter()
+        .chain(std::iter::once(&b'\0')) // Make sure we have a null terminated string
+        .map(|b| *b as std::os::raw::c_char)
+        .collect()
+}
+
+/// Options of the OpenVINO execution provider
+///
+/// See [`SessionBuilder::with_openvino()`](struct.SessionBuilder.html#method.with_openvino).
+#[cfg(feature = "openvino")]
+#[derive(Debug, Clone, Default)]
+pub struct OpenVinoOptions {
+    /// Device to run on: `"CPU"`, `"GPU"`, `"MYRIAD"`, `"HDDL"`, etc.
+    ///
+    /// Empty selects the device ONNX Runtime was built for.
+    pub device_type: String,
+    /// Precision to run with on the device, e.g. `"FP32"` or `"FP16"`
+    pub precision: Option<String>,
+    /// Number of threads used by OpenVINO; `None` lets OpenVINO decide
+    pub num_threads: Option<usize>,
+    /// Directory where compiled blobs are cached
+    pub cache_dir: Option<PathBuf>,
+}
+
+/// Options of the CUDA execution provider
+///
+/// See [`SessionBuilder::with_cuda()`](struct.SessionBuilder.html#method.with_cuda).
+#[cfg(feature = "cuda")]
+#[derive(Debug, Clone)]
+pub struct CudaProviderOptions {
+    /// Index of the GPU to run on, from `0` to
+    /// [`cuda_device_count()`](../fn.cuda_device_count.html) (excluded)
+    ///
+    /// Devices are numbered by the CUDA runtime, restricted and ordered by the
+    /// `CUDA_VISIBLE_DEVICES` environment variable if it is set. A session runs on a single
+    /// device: to use several GPUs, build a session per device. Its memory on the device is
+    /// described by `MemoryInfo::for_device("Cuda", device_id)`.
+    pub device_id: i32,
+    /// Maximum size, in bytes, of the device memory arena; `None` doesn't limit it
+    pub gpu_mem_limit: Option<usize>,
+    /// How the device memory arena grows
+    pub arena_extend_strategy: ArenaExtendStrategy,
+    /// Copy the inputs and outputs in the default CUDA stream rather than in the compute stream
+    pub copy_in_default_stream: bool,
+}
+
+#[cfg(feature = "cuda")]
+impl Default for CudaProviderOptions {
+    fn default() -> Self {
+        CudaProviderOptions {
+            device_id: 0,
+            gpu_mem_limit: None,
+            arena_extend_strategy: ArenaExtendStrategy::default(),
+            copy_in_default_stream: true,
+        }
+    }
+}
+
+/// What to do when [auto-cast](struct.SessionBuilder.html#method.with_auto_cast) changes the
+/// value of some input elements
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LossyCastPolicy {
+    /// Log a warning and run the model with the cast values
+    #[default]
+    Warn,
+    /// Fail with [`OrtError::LossyCast`](../error/enum.OrtError.html#variant.LossyCast)
+    Error,
+}
+
+/// Type storing the session information, built from an [`Environment`](environment/struct.Environment.html)
+///
+/// Cloning a `Session` is cheap: clones share the same underlying ONNX Runtime session, which is
+/// released once the last clone is dropped.
+///
+/// Each session also keeps its environment alive: the ONNX Runtime environment is only released
+/// once every session created from it has been dropped, regardless of the order in which the
+/// [`Environment`](environment/struct.Environment.html) handles and sessions are dropped.
+///
+/// # Thread safety
+///
+/// ONNX Runtime guarantees that running inference on a session is thread safe: `Run()` can be
+/// called concurrently from multiple threads on the same session. A `Session` is thus `Send` and
+/// `Sync`, and [`run()`](#method.run) only takes `&self`; there is no need to wrap it in a
+/// `Mutex` to share it between threads.
+#[derive(Debug, Clone)]
+pub struct Session<'a> {
+    env: &'a Environment,
+    inner: Arc<SessionPointerHolder>,
+    versions: ModelVersions,
     /// Information about the ONNX's inputs as stored in loaded file
     pub inputs: Vec<Input>,
+    /// Information about the ONNX's optional inputs as stored in loaded file
+    ///
+    /// These are initializers which the model also declares as inputs: they have a default value
+    /// but can be overridden using [`run_with_inputs()`](#method.run_with_inputs).
+    pub optional_inputs: Vec<Input>,
     /// Information about the ONNX's outputs as stored in loaded file
     pub outputs: Vec<Output>,
+    auto_cast: Option<LossyCastPolicy>,
+}
+
+/// Statistics about a single inference run
+///
+/// Returned by [`Session::run_with_stats()`](struct.Session.html#method.run_with_stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunStats {
+    /// Wall-clock time spent in ONNX Runtime's `Run()`
+    ///
+    /// Converting the inputs to and the outputs from ONNX Runtime's representation is excluded.
+    pub duration: Duration,
+}
+
+/// Whether a model can be loaded by a minimal build of ONNX Runtime
+///
+/// Returned by [`Session::check_minimal_compatibility()`](struct.Session.html#method.check_minimal_compatibility).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinimalCompatibility {
+    /// The model loads the way a minimal build loads it
+    Compatible,
+    /// The model fails to load the way a minimal build loads it
+    Incompatible {
+        /// Operator type ONNX Runtime failed on, when its error names one
+        op_type: Option<String>,
+        /// ONNX Runtime's error
+        message: String,
+    },
+}
+
+impl MinimalCompatibility {
+    /// Whether the model loads the way a minimal build loads it
+    pub fn is_compatible(&self) -> bool {
+        matches!(self, MinimalCompatibility::Compatible)
+    }
+
+    fn from_error(error: OrtError) -> Result<MinimalCompatibility> {
+        match error {
+            OrtError::Session(OrtApiError::Msg(message))
+            | OrtError::Session(OrtApiError::MsgWithCode { message, .. }) => {
+                Ok(MinimalCompatibility::Incompatible {
+                    op_type: failing_op_type(&message),
+                    message,
+                })
+            }
+            error => Err(error),
+        }
+    }
+}
+
+/// Summary of the size of a loaded model
+///
+/// Returned by [`Session::model_summary()`](struct.Session.html#method.model_summary).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ModelSummary {
+    /// Number of inputs the model requires
+    pub input_count: usize,
+    /// Number of optional inputs (initializers which can be overridden)
+    pub optional_input_count: usize,
+    /// Number of outputs
+    pub output_count: usize,
+    /// Number of nodes of the main graph, excluding the nodes of subgraphs (e.g. of `If` or
+    /// `Loop` nodes)
+    ///
+    /// `None` if the graph could not be read from the model (for example for models in the ORT
+    /// format).
+    pub node_count: Option<usize>,
+    /// Number of initializers (constant tensors, like weights) of the main graph
+    ///
+    /// `None` if the graph could not be read from the model.
+    pub initializer_count: Option<usize>,
+}
+
+/// Iterator running a session in a loop, feeding back its outputs as its next inputs
+///
+/// This is the usual pattern of recurrent and autoregressive models, whose state (RNN state, KV
+/// cache, generated tokens...) is an output of a step and an input of the next one.
+///
+/// Created with [`Session::run_iter()`](struct.Session.html#method.run_iter), from the inputs of
+/// the first step and an `update` closure. Each iteration runs the session and yields its outputs;
+/// `update` is given these outputs and returns the inputs of the next step, or `None` to stop the
+/// loop. The loop also stops after the first error.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # use onnxruntime::{environment::Environment, ndarray::array};
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// # let environment = Environment::builder().build()?;
+/// # let session = environment.new_session_builder()?.with_model_from_file("counter.onnx")?;
+/// let steps = session.run_iter(vec![array![0_i64].into_dyn()], |outputs| {
+///     let next = outputs[0].try_extract::<i64>()?.view().to_owned();
+///     // Stop once the counter reaches 10
+///     Ok(if next[0] < 10 { Some(vec![next]) } else { None })
+/// });
+/// for outputs in steps {
+///     let outputs = outputs?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct InferenceLoop<'s, 'a, TIn, D, F>
+where
+    TIn: TypeToTensorElementDataType + Debug + Clone,
+    D: ndarray::Dimension,
+{
+    session: &'s Session<'a>,
+    inputs: Option<Vec<Array<TIn, D>>>,
+    update: F,
+}
+
+impl<'s, 'a, TIn, D, F> Iterator for InferenceLoop<'s, 'a, TIn, D, F>
+where
+    TIn: TypeToTensorElementDataType + Debug + Clone,
+    D: ndarray::Dimension,
+    F: FnMut(&[DynOrtTensor<'_, ndarray::IxDyn>]) -> Result<Option<Vec<Array<TIn, D>>>>,
+{
+    type Item = Result<Vec<DynOrtTensor<'s, ndarray::IxDyn>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let inputs = self.inputs.take()?;
+        let step = self.session.run(inputs).and_then(|outputs| {
+            self.inputs = (self.update)(&outputs)?;
+            Ok(outputs)
+        });
+        Some(step)
+    }
 }
 
 /// Information about an ONNX's input as stored in loaded file
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Input {
     /// Name of the input layer
     pub name: String,
+    /// Kind of value of the input layer
+    pub value_type: ValueType,
     /// Type of the input layer's elements
     pub input_type: TensorElementDataType,
     /// Shape of the input layer
@@ -309,61 +1265,1008 @@ pub struct Input {
     pub dimensions: Vec<Option<u32>>,
 }
 
-/// Information about an ONNX's output as stored in loaded file
-#[derive(Debug)]
-pub struct Output {
-    /// Name of the output layer
-    pub name: String,
-    /// Type of the output layer's elements
-    pub output_type: TensorElementDataType,
-    /// Shape of the output layer
+/// Information about an ONNX's output as stored in loaded file
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// Name of the output layer
+    pub name: String,
+    /// Kind of value of the output layer (a tensor, or a sequence or map of tensors)
+    pub value_type: ValueType,
+    /// Type of the output layer's elements
+    ///
+    /// For sequences and maps, this is the element type of the tensors they contain (the values'
+    /// for maps).
+    pub output_type: TensorElementDataType,
+    /// Shape of the output layer
+    ///
+    /// Empty for sequences and maps.
+    ///
+    /// C API uses a i64 for the dimensions. We use an unsigned of the same range of the positive values.
+    pub dimensions: Vec<Option<u32>>,
+}
+
+impl Input {
+    /// Return an iterator over the shape elements of the input layer
+    ///
+    /// Note: The member [`Input::dimensions`](struct.Input.html#structfield.dimensions)
+    /// stores `u32` (since ONNX uses `i64` but which cannot be negative) so the
+    /// iterator converts to `usize`.
+    pub fn dimensions(&self) -> impl Iterator<Item = Option<usize>> + '_ {
+        self.dimensions.iter().map(|d| d.map(|d2| d2 as usize))
+    }
+}
+
+impl Output {
+    /// Return an iterator over the shape elements of the output layer
+    ///
+    /// Note: The member [`Output::dimensions`](struct.Output.html#structfield.dimensions)
+    /// stores `u32` (since ONNX uses `i64` but which cannot be negative) so the
+    /// iterator converts to `usize`.
+    pub fn dimensions(&self) -> impl Iterator<Item = Option<usize>> + '_ {
+        self.dimensions.iter().map(|d| d.map(|d2| d2 as usize))
+    }
+}
+
+/// Holds on to the `OrtSession` until the last [`Session`](struct.Session.html) sharing it is dropped
+///
+/// It also holds a handle to the environment, so that the `OrtEnv` is only released after the
+/// session, whatever the order in which the user's environment handles and sessions are dropped.
+#[derive(Debug)]
+struct SessionPointerHolder {
+    session_ptr: *mut sys::OrtSession,
+    allocator_ptr: *mut sys::OrtAllocator,
+    memory_info: MemoryInfo,
+    // Dropped after the session is released in `drop()`
+    _env: Environment,
+    _custom_ops_libraries: Vec<CustomOpsLibrary>,
+    _initializers: Vec<Box<dyn AsOrtValue>>,
+}
+
+// The pointers are never mutated after the session is created, and ONNX Runtime allows using a
+// session (and its allocator and memory info) from multiple threads concurrently.
+unsafe impl Send for SessionPointerHolder {}
+unsafe impl Sync for SessionPointerHolder {}
+
+impl Drop for SessionPointerHolder {
+    #[tracing::instrument]
+    fn drop(&mut self) {
+        debug!("Dropping the session.");
+        unsafe { g_ort().ReleaseSession.unwrap()(self.session_ptr) };
+        // FIXME: There is no C function to release the allocator?
+
+        self.session_ptr = std::ptr::null_mut();
+        self.allocator_ptr = std::ptr::null_mut();
+    }
+}
+
+impl<'a> Session<'a> {
+    /// Check, at development time, that a model loads the way a minimal build of ONNX Runtime
+    /// (e.g. a mobile build) loads it
+    ///
+    /// Minimal builds only load models in the [ORT format](https://www.onnxruntime.ai/docs/how-to/mobile/model-conversion.html),
+    /// and can't optimize graphs. An ONNX model is thus first converted to the ORT format (with
+    /// basic optimizations, as for minimal builds), and the result is then loaded back as the ORT
+    /// format with optimizations disabled. Models already in the ORT format are loaded directly.
+    /// A failure to convert or load the model is reported as
+    /// [`MinimalCompatibility::Incompatible`](enum.MinimalCompatibility.html#variant.Incompatible),
+    /// naming the failing operator when ONNX Runtime's error does.
+    ///
+    /// **NOTE**: This runs in the full build linked to this crate, which has the kernels of all
+    /// operators: operators excluded from a reduced operator build can't be detected this way.
+    /// Compare [`inspect_model_ops()`](../fn.inspect_model_ops.html) with the operators of the
+    /// build's configuration for this. The `session.use_ort_model_bytes_directly` setting is
+    /// not supported by the ONNX Runtime version this crate targets either.
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use onnxruntime::{environment::Environment, session::{MinimalCompatibility, Session}};
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let environment = Environment::builder().build()?;
+    /// let model_bytes = std::fs::read("model.onnx")?;
+    /// match Session::check_minimal_compatibility(&environment, &model_bytes)? {
+    ///     MinimalCompatibility::Compatible => {}
+    ///     MinimalCompatibility::Incompatible { op_type, message } => {
+    ///         eprintln!("Unsupported operator {:?}: {}", op_type, message)
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_minimal_compatibility(
+        environment: &Environment,
+        model_bytes: &[u8],
+    ) -> Result<MinimalCompatibility> {
+        let ort_bytes = if is_ort_format(model_bytes) {
+            model_bytes.to_vec()
+        } else {
+            static CONVERSIONS: AtomicUsize = AtomicUsize::new(0);
+            let ort_model = std::env::temp_dir().join(format!(
+                "onnxruntime-rs-minimal-{}-{}.ort",
+                std::process::id(),
+                CONVERSIONS.fetch_add(1, Ordering::Relaxed)
+            ));
+            debug!(?ort_model, "Converting the model to the ORT format.");
+            let converted = environment
+                .new_session_builder()?
+                .with_optimization_level(GraphOptimizationLevel::Basic)?
+                .with_optimized_model_path(&ort_model)?
+                .with_model_from_memory(model_bytes);
+            let ort_bytes =
+                converted.and_then(|_| fs::read(&ort_model).map_err(OrtError::ModelRead));
+            let _ = fs::remove_file(&ort_model);
+            match ort_bytes {
+                Ok(ort_bytes) => ort_bytes,
+                Err(error) => return MinimalCompatibility::from_error(error),
+            }
+        };
+
+        let loaded = environment
+            .new_session_builder()?
+            .with_optimization_level(GraphOptimizationLevel::DisableAll)?
+            .with_model_from_memory(ort_bytes);
+        match loaded {
+            Ok(_) => Ok(MinimalCompatibility::Compatible),
+            Err(error) => MinimalCompatibility::from_error(error),
+        }
+    }
+
+    /// Metadata of the loaded model, as reported by ONNX Runtime
+    pub fn metadata(&self) -> Result<ModelMetadata> {
+        ModelMetadata::new(self.inner.session_ptr)
+    }
+
+    /// ONNX IR version the model was saved with
+    ///
+    /// Returns `None` if it could not be read from the model.
+    pub fn ir_version(&self) -> Option<i64> {
+        self.versions.ir_version
+    }
+
+    /// Time at which the session's profiling started, in nanoseconds since the epoch of the system's
+    /// high resolution clock (the Unix epoch on Linux)
+    ///
+    /// The timestamps of the profile written with
+    /// [`SessionBuilder::with_profiling()`](struct.SessionBuilder.html#method.with_profiling) are
+    /// offsets, in microseconds, from this time: adding them aligns the profiled operators with
+    /// other traces of the application. Returns `0` when profiling is not enabled.
+    pub fn profiling_start_time_ns(&self) -> Result<u64> {
+        let mut start_time_ns = 0_u64;
+        unsafe {
+            call_ort(|ort| {
+                ort.SessionGetProfilingStartTimeNs.unwrap()(
+                    self.inner.session_ptr,
+                    &mut start_time_ns,
+                )
+            })
+        }
+        .map_err(OrtError::ProfilingStartTime)?;
+        Ok(start_time_ns)
+    }
+
+    /// Summary of the size of the model: its number of inputs, outputs, nodes...
+    ///
+    /// The nodes are counted as stored in the model, before ONNX Runtime's graph optimizations.
+    pub fn model_summary(&self) -> ModelSummary {
+        let graph_counts = self.versions.graph_counts.as_ref();
+        ModelSummary {
+            input_count: self.inputs.len(),
+            optional_input_count: self.optional_inputs.len(),
+            output_count: self.outputs.len(),
+            node_count: graph_counts.map(|counts| counts.node_count),
+            initializer_count: graph_counts.map(|counts| counts.initializer_count),
+        }
+    }
+
+    /// [Denotation](https://github.com/onnx/onnx/blob/master/docs/DimensionDenotation.md) of each
+    /// dimension of an input's shape (e.g. `DATA_BATCH` or `DATA_CHANNEL`), `None` where the
+    /// model doesn't denote the dimension
+    ///
+    /// This tells for example where the channels of an image input are, without hardcoding the
+    /// model's layout.
+    ///
+    /// ONNX Runtime doesn't expose denotations: they are read from the model itself. Returns `None`
+    /// if the model has no such input, or if they could not be read (for example for models in the
+    /// ORT format).
+    pub fn input_denotations(&self, input_name: &str) -> Option<&[Option<String>]> {
+        self.versions
+            .graph_counts
+            .as_ref()?
+            .input_denotations
+            .iter()
+            .find(|(name, _)| name == input_name)
+            .map(|(_, denotations)| denotations.as_slice())
+    }
+
+    /// Shapes of the outputs, as declared by the model, with `None` for dynamic dimensions
+    ///
+    /// Useful to size buffers before running. Sequences and maps have an empty shape.
+    pub fn output_shapes(&self) -> Vec<Vec<Option<i64>>> {
+        self.outputs
+            .iter()
+            .map(|output| {
+                output
+                    .dimensions
+                    .iter()
+                    .map(|dim| dim.map(i64::from))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Version of the operator set imported by the model for a given domain
+    ///
+    /// The default ONNX domain can be given either as `""` or as `"ai.onnx"`.
+    /// Returns `None` if the model does not import that domain.
+    pub fn opset_version(&self, domain: &str) -> Option<i64> {
+        let is_default_domain = |domain: &str| domain.is_empty() || domain == "ai.onnx";
+        self.versions
+            .opset_imports
+            .iter()
+            .find(|(opset_domain, _)| {
+                opset_domain == domain
+                    || (is_default_domain(opset_domain) && is_default_domain(domain))
+            })
+            .map(|(_, version)| *version)
+    }
+
+    /// Attach a [`Preprocessor`](../preprocessor/trait.Preprocessor.html), applied to the inputs
+    /// of every [`run()`](../preprocessor/struct.PreprocessedSession.html#method.run)
+    ///
+    /// The returned [`PreprocessedSession`](../preprocessor/struct.PreprocessedSession.html)
+    /// dereferences to this session.
+    pub fn with_preprocessor<P>(self, preprocessor: P) -> PreprocessedSession<'a, P>
+    where
+        P: Preprocessor,
+    {
+        PreprocessedSession::new(self, preprocessor)
+    }
+
+    /// Run the input data through the ONNX graph, performing inference.
+    ///
+    /// Note that ONNX models can have multiple inputs; a `Vec<_>` is thus
+    /// used for the input data here.
+    pub fn run<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<Array<TIn, D>>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        self.validate_input_shapes(&input_arrays)?;
+        let input_names = self
+            .inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect();
+        self.run_with_output_allocator(input_names, cow_arrays(input_arrays), None, None, None)
+    }
+
+    /// Run owned or borrowed input data through the ONNX graph, performing inference.
+    ///
+    /// This is [`run()`](#method.run) for inputs which are not owned arrays, for example views of
+    /// a larger buffer: an [`ndarray::ArrayView`](https://docs.rs/ndarray/latest/ndarray/type.ArrayView.html)
+    /// converts into a `CowArray` with `into()`. Views in standard (C) layout are read in place
+    /// by ONNX Runtime, without copying; views in any other layout (e.g. transposed) are copied
+    /// first.
+    ///
+    /// ```no_run
+    /// # use std::error::Error;
+    /// # use onnxruntime::{environment::Environment, ndarray::{s, Array}};
+    /// # fn main() -> Result<(), Box<dyn Error>> {
+    /// # let environment = Environment::builder().build()?;
+    /// # let session = environment.new_session_builder()?.with_model_from_file("model.onnx")?;
+    /// let batch = Array::<f32, _>::zeros((8, 3, 224, 224));
+    /// // Run the first image of the batch without copying it
+    /// let image = batch.slice(s![0..1, .., .., ..]);
+    /// let outputs = session.run_cow(vec![image.into()])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_cow<'s, 't, 'i, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<CowArray<'i, TIn, D>>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        self.validate_input_shapes(&input_arrays)?;
+        let input_names = self
+            .inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect();
+        self.run_with_output_allocator(input_names, input_arrays, None, None, None)
+    }
+
+    /// Run the input data through the ONNX graph with the given options (for example a
+    /// [log level](../run_options/struct.RunOptions.html#method.set_log_level) for this run only).
+    pub fn run_with_options<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<Array<TIn, D>>,
+        run_options: &RunOptions,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        self.validate_input_shapes(&input_arrays)?;
+        let input_names = self
+            .inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect();
+        self.run_with_output_allocator(
+            input_names,
+            cow_arrays(input_arrays),
+            None,
+            Some(run_options),
+            None,
+        )
+    }
+
+    /// Run the input data through the ONNX graph, unless `token` is cancelled
+    ///
+    /// [Cancelling](../run_options/struct.CancellationToken.html#method.cancel) the token from
+    /// another thread terminates the run, which then fails with
+    /// [`OrtError::Cancelled`](../error/enum.OrtError.html#variant.Cancelled). A run started with
+    /// an already cancelled token fails right away.
+    ///
+    /// **NOTE**: Terminating is cooperative: ONNX Runtime only checks for it between nodes, so
+    /// the call may return some time after the cancellation. A run which completes despite being
+    /// cancelled returns its outputs.
+    pub fn run_with_cancellation<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<Array<TIn, D>>,
+        token: &CancellationToken,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let run_options = Arc::new(RunOptions::new()?);
+        self.run_cancellable(input_arrays, &run_options, token)
+    }
+
+    /// Run with `run_options`, terminated when `token` is cancelled
+    fn run_cancellable<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<Array<TIn, D>>,
+        run_options: &Arc<RunOptions>,
+        token: &CancellationToken,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        token.register(run_options)?;
+        let outputs = self.run_with_options(input_arrays, run_options);
+        token.unregister(run_options);
+
+        match outputs {
+            Err(_) if token.is_cancelled() => Err(OrtError::Cancelled),
+            outputs => outputs,
+        }
+    }
+
+    /// Run the input data through the ONNX graph, giving up after `timeout`
+    ///
+    /// The run is performed on the calling thread while a watchdog thread waits for the deadline.
+    /// If it passes, the watchdog [terminates](../run_options/struct.RunOptions.html#method.set_terminate)
+    /// the run and [`OrtError::Timeout`](../error/enum.OrtError.html#variant.Timeout) is returned.
+    /// This protects a server from pathological inputs.
+    ///
+    /// **NOTE**: Terminating is cooperative: ONNX Runtime only checks for it between nodes, so
+    /// the call may return some time after the deadline (after the node running when it passed
+    /// completes). A run which completes despite the deadline passing returns its outputs.
+    pub fn run_with_timeout<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<Array<TIn, D>>,
+        timeout: Duration,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let run_options = Arc::new(RunOptions::new()?);
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let (done_sender, done_receiver) = mpsc::channel::<()>();
+        let watchdog = {
+            let run_options = Arc::clone(&run_options);
+            let timed_out = Arc::clone(&timed_out);
+            thread::spawn(move || {
+                if let Err(mpsc::RecvTimeoutError::Timeout) = done_receiver.recv_timeout(timeout) {
+                    warn!(?timeout, "Run timed out, terminating it.");
+                    timed_out.store(true, Ordering::SeqCst);
+                    if let Err(e) = run_options.set_terminate() {
+                        error!("Failed to terminate the run: {}", e);
+                    }
+                }
+            })
+        };
+
+        let outputs = self.run_with_options(input_arrays, &run_options);
+        // Dropping the sender wakes the watchdog up if the deadline has not passed yet
+        drop(done_sender);
+        if watchdog.join().is_err() {
+            error!("The run's watchdog thread panicked.");
+        }
+
+        match outputs {
+            Err(_) if timed_out.load(Ordering::SeqCst) => Err(OrtError::Timeout(timeout)),
+            outputs => outputs,
+        }
+    }
+
+    /// Run the input data through the ONNX graph, copying the outputs into
+    /// [`SerializableTensor`](../tensor/serde_tensor/enum.SerializableTensor.html)s that can be
+    /// serialized with serde (for example to JSON, as a web service response).
+    ///
+    /// Enabled with the `serde` feature.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`run()`](#method.run), an error is returned if an output is
+    /// a sequence or a map rather than a tensor.
+    #[cfg(feature = "serde")]
+    pub fn run_serializable<TIn, D>(
+        &self,
+        input_arrays: Vec<Array<TIn, D>>,
+    ) -> Result<Vec<SerializableTensor>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+    {
+        self.run(input_arrays)?
+            .iter()
+            .map(SerializableTensor::from_output)
+            .collect()
+    }
+
+    /// Run the input data through the ONNX graph, also reporting statistics about the run.
+    ///
+    /// This is a lighter alternative to ONNX Runtime's profiling when only the latency of each
+    /// run is needed (for example for monitoring); [`run()`](#method.run) doesn't measure anything.
+    pub fn run_with_stats<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: Vec<Array<TIn, D>>,
+    ) -> Result<(Vec<DynOrtTensor<'m, ndarray::IxDyn>>, RunStats)>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        self.validate_input_shapes(&input_arrays)?;
+        let input_names = self
+            .inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect();
+        let mut stats = RunStats::default();
+        let outputs = self.run_with_output_allocator(
+            input_names,
+            cow_arrays(input_arrays),
+            None,
+            None,
+            Some(&mut stats),
+        )?;
+        Ok((outputs, stats))
+    }
+
+    /// Run the input data through the ONNX graph, retrying runs which failed transiently (for
+    /// example with `ORT_EP_FAIL` under load) as described by `policy`.
+    ///
+    /// The inputs are borrowed: they are copied into new tensors for each attempt. The error of
+    /// the last attempt is returned if none succeeded.
+    pub fn run_with_retry<'s, 't, 'm, TIn, D>(
+        &'s self,
+        input_arrays: &[Array<TIn, D>],
+        policy: &RetryPolicy,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        policy.run(|| self.run(input_arrays.to_vec()))
+    }
+
+    /// Run a large input through the ONNX graph in batches of `batch_size` samples.
+    ///
+    /// The input is split along its first axis, each batch is run separately and every output
+    /// is concatenated back along its first axis. The model must have a single input, and tensor
+    /// outputs of `f32` whose first axis is the batch.
+    ///
+    /// If the model's batch dimension is fixed, `batch_size` should match it: the last batch, if
+    /// incomplete, is padded with zeros and the outputs of the padding are discarded. If the batch
+    /// dimension is dynamic, the last batch is simply run with fewer samples. An input without any
+    /// sample is run once as is (or padded), giving outputs without any sample.
+    ///
+    /// # Errors
+    ///
+    /// In addition to the errors of [`run()`](#method.run),
+    /// [`OrtError::BatchScalarInput`](../error/enum.OrtError.html#variant.BatchScalarInput) is
+    /// returned if `input` is 0-dimensional, and
+    /// [`OrtError::BatchSizeTooLarge`](../error/enum.OrtError.html#variant.BatchSizeTooLarge) if
+    /// `batch_size` exceeds the model's fixed batch dimension.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    pub fn run_batched<D>(
+        &self,
+        input: ArrayView<f32, D>,
+        batch_size: usize,
+    ) -> Result<Vec<Array<f32, ndarray::IxDyn>>>
+    where
+        D: ndarray::Dimension,
+    {
+        assert!(batch_size > 0, "Batch size must be positive");
+        if input.ndim() == 0 {
+            return Err(OrtError::BatchScalarInput);
+        }
+
+        let fixed_batch_size = self
+            .inputs
+            .first()
+            .and_then(|input| input.dimensions.first().cloned().flatten())
+            .map(|dim| dim as usize);
+        if let Some(fixed_batch_size) = fixed_batch_size {
+            if batch_size > fixed_batch_size {
+                return Err(OrtError::BatchSizeTooLarge {
+                    batch_size,
+                    fixed_batch_size,
+                });
+            }
+        }
+
+        // An empty input still goes through the graph once, for its outputs to have their shape
+        let batches: Vec<ArrayView<f32, D>> = if input.len_of(Axis(0)) == 0 {
+            vec![input.view()]
+        } else {
+            input.axis_chunks_iter(Axis(0), batch_size).collect()
+        };
+
+        let mut outputs_batches: Vec<Vec<Array<f32, ndarray::IxDyn>>> =
+            vec![Vec::new(); self.outputs.len()];
+        for batch in batches {
+            let samples = batch.len_of(Axis(0));
+            let batch = match fixed_batch_size {
+                Some(fixed_batch_size) if fixed_batch_size > samples => {
+                    debug!(samples, fixed_batch_size, "Padding last batch.");
+                    let mut shape = batch.raw_dim();
+                    shape[0] = fixed_batch_size;
+                    let mut padded = Array::zeros(shape);
+                    padded
+                        .slice_axis_mut(Axis(0), Slice::from(..samples))
+                        .assign(&batch);
+                    padded
+                }
+                _ => batch.to_owned(),
+            };
+
+            let outputs = self.run(vec![batch])?;
+            for (output, output_batches) in outputs.iter().zip(&mut outputs_batches) {
+                let output = output.try_extract::<f32>()?;
+                output_batches.push(
+                    output
+                        .view()
+                        .slice_axis(Axis(0), Slice::from(..samples))
+                        .to_owned(),
+                );
+            }
+        }
+
+        outputs_batches
+            .iter()
+            .map(|output_batches| {
+                let views: Vec<_> = output_batches.iter().map(|batch| batch.view()).collect();
+                ndarray::concatenate(Axis(0), &views).map_err(OrtError::BatchConcatenation)
+            })
+            .collect()
+    }
+
+    /// Run the session `count` times on representative inputs, discarding the outputs.
+    ///
+    /// The first runs of a session are slower than the following ones (memory is allocated lazily,
+    /// kernels are selected and compiled...): warming it up before serving traffic avoids paying for
+    /// it on actual requests.
+    pub fn warm_up<TIn, D>(&self, sample_inputs: &[Array<TIn, D>], count: usize) -> Result<()>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+    {
+        debug!(count, "Warming up session.");
+        for _ in 0..count {
+            self.run(sample_inputs.to_vec())?;
+        }
+        Ok(())
+    }
+
+    /// Run the session `count` times on inputs filled with zeros, discarding the outputs.
+    ///
+    /// Same as [`warm_up()`](#method.warm_up), with inputs synthesized from the model's
+    /// [inputs](#structfield.inputs) information: dynamic dimensions are given a size of 1. All the
+    /// inputs must be tensors of the same numeric element type.
+    pub fn warm_up_with_zeros(&self, count: usize) -> Result<()> {
+        let input_type = match self.inputs.first() {
+            Some(input) => input.input_type,
+            None => return self.warm_up::<f32, ndarray::IxDyn>(&[], count),
+        };
+        if let Some(input) = self.inputs.iter().find(|input| {
+            input.value_type != ValueType::Tensor
+                || input.input_type != input_type
+                || input.input_type == TensorElementDataType::String
+        }) {
+            return Err(OrtError::SynthesizeInput {
+                name: input.name.clone(),
+                input_type: input.input_type,
+            });
+        }
+
+        match input_type {
+            TensorElementDataType::Float => self.warm_up(&self.default_inputs::<f32>(), count),
+            TensorElementDataType::Uint8 => self.warm_up(&self.default_inputs::<u8>(), count),
+            TensorElementDataType::Int8 => self.warm_up(&self.default_inputs::<i8>(), count),
+            TensorElementDataType::Uint16 => self.warm_up(&self.default_inputs::<u16>(), count),
+            TensorElementDataType::Int16 => self.warm_up(&self.default_inputs::<i16>(), count),
+            TensorElementDataType::Int32 => self.warm_up(&self.default_inputs::<i32>(), count),
+            TensorElementDataType::Int64 => self.warm_up(&self.default_inputs::<i64>(), count),
+            TensorElementDataType::Double => self.warm_up(&self.default_inputs::<f64>(), count),
+            TensorElementDataType::Uint32 => self.warm_up(&self.default_inputs::<u32>(), count),
+            TensorElementDataType::Uint64 => self.warm_up(&self.default_inputs::<u64>(), count),
+            TensorElementDataType::Bool => self.warm_up(&self.default_inputs::<bool>(), count),
+            #[cfg(feature = "complex")]
+            TensorElementDataType::Complex64 => {
+                self.warm_up(&self.default_inputs::<num_complex::Complex<f32>>(), count)
+            }
+            #[cfg(feature = "complex")]
+            TensorElementDataType::Complex128 => {
+                self.warm_up(&self.default_inputs::<num_complex::Complex<f64>>(), count)
+            }
+            TensorElementDataType::String => unreachable!("String inputs are rejected above"),
+        }
+    }
+
+    /// Build one array per input, filled with `T::default()` (zero for numeric types)
+    fn default_inputs<T>(&self) -> Vec<Array<T, ndarray::IxDyn>>
+    where
+        T: Clone + Default,
+    {
+        self.inputs
+            .iter()
+            .map(|input| {
+                let shape: Vec<usize> = input.dimensions().map(|d| d.unwrap_or(1)).collect();
+                Array::default(shape)
+            })
+            .collect()
+    }
+
+    /// Run the session in a loop, feeding back its outputs as its next inputs.
+    ///
+    /// See [`InferenceLoop`](struct.InferenceLoop.html).
+    pub fn run_iter<TIn, D, F>(
+        &self,
+        initial_inputs: Vec<Array<TIn, D>>,
+        update: F,
+    ) -> InferenceLoop<'_, 'a, TIn, D, F>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        F: FnMut(&[DynOrtTensor<'_, ndarray::IxDyn>]) -> Result<Option<Vec<Array<TIn, D>>>>,
+    {
+        InferenceLoop {
+            session: self,
+            inputs: Some(initial_inputs),
+            update,
+        }
+    }
+
+    /// Create an [`IoBinding`](../io_binding/struct.IoBinding.html), to bind the session's inputs
+    /// and outputs to memory before running it with [`run_with_binding()`](#method.run_with_binding)
+    pub fn io_binding(&self) -> Result<IoBinding<'_>> {
+        IoBinding::new(
+            self.inner.session_ptr,
+            &self.inner.memory_info,
+            self.inner.allocator_ptr,
+        )
+    }
+
+    /// Run the session on the inputs and outputs bound to `binding`
+    ///
+    /// The outputs are written to the memory they are bound to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `binding` was created by another session.
+    pub fn run_with_binding(&self, binding: &IoBinding) -> Result<()> {
+        assert_eq!(
+            binding.session_ptr, self.inner.session_ptr,
+            "The IO binding was created by another session"
+        );
+        let status = unsafe {
+            g_ort().RunWithBinding.unwrap()(self.inner.session_ptr, std::ptr::null(), binding.ptr)
+        };
+        run_status_to_result(status)
+    }
+
+    /// Run the input data through the ONNX graph, binding the inputs by name.
+    ///
+    /// Only the given inputs are passed to ONNX Runtime: [optional inputs](#structfield.optional_inputs)
+    /// which are omitted use their default value from the model. All the (non optional)
+    /// [inputs](#structfield.inputs) must still be provided.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if a name is not one of the model's inputs, if an input has the wrong
+    /// shape, or if a required input is missing
+    /// ([`OrtError::MissingInput`](../error/enum.OrtError.html#variant.MissingInput)).
+    pub fn run_with_inputs<'s, 't, 'm, TIn, D, K>(
+        &'s self,
+        inputs: HashMap<K, Array<TIn, D>>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        K: AsRef<str>,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let mut input_names = Vec::with_capacity(inputs.len());
+        let mut input_arrays = Vec::with_capacity(inputs.len());
+        for (name, array) in inputs {
+            let name = name.as_ref();
+            let input = self
+                .inputs
+                .iter()
+                .chain(self.optional_inputs.iter())
+                .find(|input| input.name == name)
+                .ok_or_else(|| OrtError::UnknownInput {
+                    name: name.to_string(),
+                })?;
+            validate_input_shape(input, array.shape())?;
+            input_names.push(input.name.as_str());
+            input_arrays.push(array);
+        }
+        if let Some(missing) = self
+            .inputs
+            .iter()
+            .find(|input| !input_names.contains(&input.name.as_str()))
+        {
+            return Err(OrtError::MissingInput {
+                name: missing.name.clone(),
+            });
+        }
+
+        self.run_with_output_allocator(input_names, cow_arrays(input_arrays), None, None, None)
+    }
+
+    /// Run the input data through the ONNX graph, binding inputs of any element type by name.
     ///
-    /// C API uses a i64 for the dimensions. We use an unsigned of the same range of the positive values.
-    pub dimensions: Vec<Option<u32>>,
-}
-
-impl Input {
-    /// Return an iterator over the shape elements of the input layer
+    /// Unlike [`run_with_inputs()`](#method.run_with_inputs), the inputs can have different
+    /// element types: arrays are converted with `InputTensor::from(array)`. Omitted
+    /// [optional inputs](#structfield.optional_inputs) use their default value from the model.
     ///
-    /// Note: The member [`Input::dimensions`](struct.Input.html#structfield.dimensions)
-    /// stores `u32` (since ONNX uses `i64` but which cannot be negative) so the
-    /// iterator converts to `usize`.
-    pub fn dimensions(&self) -> impl Iterator<Item = Option<usize>> + '_ {
-        self.dimensions.iter().map(|d| d.map(|d2| d2 as usize))
+    /// # Errors
+    ///
+    /// An error is returned if a name is not one of the model's inputs, if an input has the wrong
+    /// element type or shape, or if a required input is missing.
+    pub fn run_map<'s, 'm, K>(
+        &'s self,
+        inputs: HashMap<K, InputTensor>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        K: AsRef<str>,
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let mut input_names = Vec::with_capacity(inputs.len());
+        let mut input_tensors = Vec::with_capacity(inputs.len());
+        for (name, tensor) in inputs {
+            let name = name.as_ref();
+            let input = self
+                .inputs
+                .iter()
+                .chain(self.optional_inputs.iter())
+                .find(|input| input.name == name)
+                .ok_or_else(|| OrtError::UnknownInput {
+                    name: name.to_string(),
+                })?;
+            let tensor =
+                match self.auto_cast_input(input, tensor.data_type(), |to| tensor.cast(to))? {
+                    Some(cast) => cast,
+                    None => tensor,
+                };
+            if tensor.data_type() != input.input_type {
+                return Err(OrtError::DataTypeMismatch {
+                    expected: input.input_type,
+                    actual: tensor.data_type(),
+                });
+            }
+            validate_input_shape(input, tensor.shape())?;
+            input_names.push(input.name.as_str());
+            input_tensors.push(tensor);
+        }
+        if let Some(missing) = self
+            .inputs
+            .iter()
+            .find(|input| !input_names.contains(&input.name.as_str()))
+        {
+            return Err(OrtError::MissingInput {
+                name: missing.name.clone(),
+            });
+        }
+
+        let input_ort_tensors = input_tensors
+            .into_iter()
+            .map(|tensor| tensor.into_ort_value(&self.inner.memory_info, self.inner.allocator_ptr))
+            .collect::<Result<Vec<_>>>()?;
+        let input_ort_values: Vec<*const sys::OrtValue> = input_ort_tensors
+            .iter()
+            .map(|tensor| tensor.ort_value())
+            .collect();
+
+        self.run_ort_values(input_names, &input_ort_values, None, None, None)
     }
-}
 
-impl Output {
-    /// Return an iterator over the shape elements of the output layer
+    /// Run the input data through the ONNX graph, with optional inputs given positionally.
     ///
-    /// Note: The member [`Output::dimensions`](struct.Output.html#structfield.dimensions)
-    /// stores `u32` (since ONNX uses `i64` but which cannot be negative) so the
-    /// iterator converts to `usize`.
-    pub fn dimensions(&self) -> impl Iterator<Item = Option<usize>> + '_ {
-        self.dimensions.iter().map(|d| d.map(|d2| d2 as usize))
+    /// The inputs are the model's [inputs](#structfield.inputs) followed by its
+    /// [optional inputs](#structfield.optional_inputs), in order. `None` omits an optional input,
+    /// which then uses its default value from the model; trailing optional inputs can also be left
+    /// out of the list. This is the positional equivalent of [`run_with_inputs()`](#method.run_with_inputs).
+    ///
+    /// **NOTE**: The omitted inputs are not passed to ONNX Runtime at all: the version this crate
+    /// is built against (1.6) doesn't accept null values as inputs.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if a required input is `None` or if more inputs are given than the
+    /// model has.
+    pub fn run_with_optional_inputs<'s, 't, 'm, TIn, D>(
+        &'s self,
+        inputs: Vec<Option<Array<TIn, D>>>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let model_input_count = self.inputs.len() + self.optional_inputs.len();
+        if inputs.len() > model_input_count {
+            return Err(OrtError::TooManyInputs {
+                given: inputs.len(),
+                model_input_count,
+            });
+        }
+
+        let mut input_names = Vec::with_capacity(inputs.len());
+        let mut input_arrays = Vec::with_capacity(inputs.len());
+        let mut inputs = inputs.into_iter();
+        for (index, input) in self
+            .inputs
+            .iter()
+            .chain(self.optional_inputs.iter())
+            .enumerate()
+        {
+            let required = index < self.inputs.len();
+            match inputs.next().flatten() {
+                Some(array) => {
+                    validate_input_shape(input, array.shape())?;
+                    input_names.push(input.name.as_str());
+                    input_arrays.push(array);
+                }
+                None if required => {
+                    return Err(OrtError::MissingInput {
+                        name: input.name.clone(),
+                    })
+                }
+                None => {}
+            }
+        }
+
+        self.run_with_output_allocator(input_names, cow_arrays(input_arrays), None, None, None)
     }
-}
 
-impl<'a> Drop for Session<'a> {
-    #[tracing::instrument]
-    fn drop(&mut self) {
-        debug!("Dropping the session.");
-        unsafe { g_ort().ReleaseSession.unwrap()(self.session_ptr) };
-        // FIXME: There is no C function to release the allocator?
+    /// Run a BERT-style transformer model on a single pre-tokenized sequence.
+    ///
+    /// The token ids are bound to the model's `input_ids`, `attention_mask` and (if given)
+    /// `token_type_ids` inputs, as `int64` tensors of shape `[1, sequence length]`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the slices don't all have the same length, or if the model doesn't
+    /// have inputs of these names.
+    pub fn run_ids<'s, 't, 'm>(
+        &'s self,
+        input_ids: &[i64],
+        attention_mask: &[i64],
+        token_type_ids: Option<&[i64]>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        'm: 't, // 'm outlives 't (memory info outlives tensor)
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
+        let mut inputs = HashMap::with_capacity(3);
+        inputs.insert("input_ids", input_ids);
+        inputs.insert("attention_mask", attention_mask);
+        if let Some(token_type_ids) = token_type_ids {
+            inputs.insert("token_type_ids", token_type_ids);
+        }
 
-        self.session_ptr = std::ptr::null_mut();
-        self.allocator_ptr = std::ptr::null_mut();
+        let inputs = inputs
+            .into_iter()
+            .map(|(name, ids)| {
+                if ids.len() != input_ids.len() {
+                    return Err(OrtError::IdsLengthMismatch {
+                        name,
+                        expected: input_ids.len(),
+                        actual: ids.len(),
+                    });
+                }
+                Ok((name, Array::from(ids.to_vec()).insert_axis(Axis(0))))
+            })
+            .collect::<Result<HashMap<_, _>>>()?;
+
+        self.run_with_inputs(inputs)
     }
-}
 
-impl<'a> Session<'a> {
-    /// Run the input data through the ONNX graph, performing inference.
+    /// Run the input data through the ONNX graph, allocating the outputs from `allocator`.
     ///
-    /// Note that ONNX models can have multiple inputs; a `Vec<_>` is thus
-    /// used for the input data here.
-    pub fn run<'s, 't, 'm, TIn, D>(
-        &'s mut self,
+    /// Outputs whose shape is fully known from the model are pre-allocated from the given
+    /// [`OrtAllocator`](../allocator/struct.OrtAllocator.html) before running; outputs with dynamic
+    /// dimensions can only be allocated by ONNX Runtime itself.
+    ///
+    /// The allocator must outlive the returned tensors.
+    pub fn run_with_allocator<'s, 'al, 't, 'm, TIn, D>(
+        &'s self,
         input_arrays: Vec<Array<TIn, D>>,
+        allocator: &'al OrtAllocator,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone,
+        D: ndarray::Dimension,
+        'm: 't,  // 'm outlives 't (memory info outlives tensor)
+        's: 'm,  // 's outlives 'm (session outlives memory info)
+        'al: 'm, // 'al outlives 'm (allocator outlives tensor)
+    {
+        self.validate_input_shapes(&input_arrays)?;
+        let input_names = self
+            .inputs
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect();
+        self.run_with_output_allocator(
+            input_names,
+            cow_arrays(input_arrays),
+            Some(allocator.ptr()),
+            None,
+            None,
+        )
+    }
+
+    fn run_with_output_allocator<'s, 't, 'i, 'm, TIn, D>(
+        &'s self,
+        input_names: Vec<&str>,
+        input_arrays: Vec<CowArray<'i, TIn, D>>,
+        output_allocator_ptr: Option<*mut sys::OrtAllocator>,
+        run_options: Option<&RunOptions>,
+        stats: Option<&mut RunStats>,
     ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
     where
         TIn: TypeToTensorElementDataType + Debug + Clone,
@@ -371,19 +2274,114 @@ impl<'a> Session<'a> {
         'm: 't, // 'm outlives 't (memory info outlives tensor)
         's: 'm, // 's outlives 'm (session outlives memory info)
     {
-        self.validate_input_shapes(&input_arrays)?;
+        // The C API expects pointers for the arrays (pointers to C-arrays)
+        let mut input_ort_tensors: Vec<OrtTensor<TIn, D>> = Vec::with_capacity(input_arrays.len());
+        let mut cast_ort_tensors: Vec<Box<dyn AsOrtValue + '_>> = Vec::new();
+        let mut input_ort_values: Vec<*const sys::OrtValue> =
+            Vec::with_capacity(input_arrays.len());
+        // The arrays outlive the run: they are handed to ONNX Runtime as views, which avoids
+        // copying both the owned and the borrowed ones
+        for (i, input_array) in input_arrays.iter().enumerate() {
+            let input = input_names.get(i).and_then(|name| {
+                self.inputs
+                    .iter()
+                    .chain(self.optional_inputs.iter())
+                    .find(|input| input.name == *name)
+            });
+            let cast = match input {
+                Some(input) => {
+                    self.auto_cast_input(input, TIn::tensor_element_data_type(), |to| {
+                        cast_array(input_array, to)
+                    })?
+                }
+                None => None,
+            };
+            if let Some(tensor) = cast {
+                let input_ort_tensor =
+                    tensor.into_ort_value(&self.inner.memory_info, self.inner.allocator_ptr)?;
+                input_ort_values.push(input_ort_tensor.ort_value());
+                cast_ort_tensors.push(input_ort_tensor);
+            } else {
+                let input_ort_tensor = OrtTensor::from_array(
+                    &self.inner.memory_info,
+                    self.inner.allocator_ptr,
+                    input_array.view().into(),
+                )?;
+                input_ort_values.push(input_ort_tensor.c_ptr as *const sys::OrtValue);
+                input_ort_tensors.push(input_ort_tensor);
+            }
+        }
+
+        self.run_ort_values(
+            input_names,
+            &input_ort_values,
+            output_allocator_ptr,
+            run_options,
+            stats,
+        )
+    }
 
+    /// Cast an input of element type `from` with `cast` if [auto-cast](struct.SessionBuilder.html#method.with_auto_cast)
+    /// is enabled and the model expects another type
+    fn auto_cast_input<F>(
+        &self,
+        input: &Input,
+        from: TensorElementDataType,
+        cast: F,
+    ) -> Result<Option<InputTensor>>
+    where
+        F: FnOnce(TensorElementDataType) -> Option<(InputTensor, usize)>,
+    {
+        let policy = match self.auto_cast {
+            Some(policy) if input.input_type != from => policy,
+            _ => return Ok(None),
+        };
+        let to = input.input_type;
+        let (tensor, changed) = cast(to).ok_or(OrtError::DataTypeMismatch {
+            expected: to,
+            actual: from,
+        })?;
+        warn!("Casting input {:?} from {:?} to {:?}", input.name, from, to);
+        if changed > 0 {
+            match policy {
+                LossyCastPolicy::Warn => warn!(
+                    "Casting input {:?} from {:?} to {:?} changed {} element(s)",
+                    input.name, from, to, changed
+                ),
+                LossyCastPolicy::Error => {
+                    return Err(OrtError::LossyCast {
+                        input: input.name.clone(),
+                        from,
+                        to,
+                        changed,
+                    })
+                }
+            }
+        }
+        Ok(Some(tensor))
+    }
+
+    /// Run values already handed to ONNX Runtime, which must outlive the call
+    fn run_ort_values<'s, 'm>(
+        &'s self,
+        input_names: Vec<&str>,
+        input_ort_values: &[*const sys::OrtValue],
+        output_allocator_ptr: Option<*mut sys::OrtAllocator>,
+        run_options: Option<&RunOptions>,
+        stats: Option<&mut RunStats>,
+    ) -> Result<Vec<DynOrtTensor<'m, ndarray::IxDyn>>>
+    where
+        's: 'm, // 's outlives 'm (session outlives memory info)
+    {
         // Build arguments to Run()
 
-        let input_names: Vec<String> = self.inputs.iter().map(|input| input.name.clone()).collect();
         let input_names_cstring: Vec<CString> = input_names
-            .iter()
-            .cloned()
+            .into_iter()
             .map(|n| CString::new(n).unwrap())
             .collect();
         let input_names_ptr: Vec<*const i8> = input_names_cstring
-            .into_iter()
-            .map(|n| n.into_raw() as *const i8)
+            .iter()
+            .map(|n| n.as_ptr() as *const i8)
             .collect();
 
         let output_names: Vec<String> = self
@@ -402,24 +2400,47 @@ impl<'a> Session<'a> {
 
         let mut output_tensor_ptrs: Vec<*mut sys::OrtValue> =
             vec![std::ptr::null_mut(); self.outputs.len()];
+        if let Some(allocator_ptr) = output_allocator_ptr {
+            for (output, output_tensor_ptr) in self.outputs.iter().zip(&mut output_tensor_ptrs) {
+                if output.value_type != ValueType::Tensor {
+                    continue;
+                }
+                if let Some(shape) = output
+                    .dimensions
+                    .iter()
+                    .map(|d| d.map(i64::from))
+                    .collect::<Option<Vec<i64>>>()
+                {
+                    let output_tensor_ptr_ptr: *mut *mut sys::OrtValue = output_tensor_ptr;
+                    let result = unsafe {
+                        call_ort(|ort| {
+                            ort.CreateTensorAsOrtValue.unwrap()(
+                                allocator_ptr,
+                                shape.as_ptr(),
+                                shape.len() as u64,
+                                output.output_type.into(),
+                                output_tensor_ptr_ptr,
+                            )
+                        })
+                    }
+                    .map_err(OrtError::CreateTensor);
+                    if let Err(e) = result {
+                        release_values(&output_tensor_ptrs);
+                        return Err(e);
+                    }
+                }
+            }
+        }
 
-        // The C API expects pointers for the arrays (pointers to C-arrays)
-        let input_ort_tensors: Vec<OrtTensor<TIn, D>> = input_arrays
-            .into_iter()
-            .map(|input_array| {
-                OrtTensor::from_array(&self.memory_info, self.allocator_ptr, input_array)
-            })
-            .collect::<Result<Vec<OrtTensor<TIn, D>>>>()?;
-        let input_ort_values: Vec<*const sys::OrtValue> = input_ort_tensors
-            .iter()
-            .map(|input_array_ort| input_array_ort.c_ptr as *const sys::OrtValue)
-            .collect();
-
-        let run_options_ptr: *const sys::OrtRunOptions = std::ptr::null();
+        let run_options_ptr: *const sys::OrtRunOptions = run_options
+            .map(|run_options| run_options.ptr as *const sys::OrtRunOptions)
+            .unwrap_or(std::ptr::null());
 
+        // Only measure the run when asked to
+        let start = stats.as_ref().map(|_| Instant::now());
         let status = unsafe {
             g_ort().Run.unwrap()(
-                self.session_ptr,
+                self.inner.session_ptr,
                 run_options_ptr,
                 input_names_ptr.as_ptr(),
                 input_ort_values.as_ptr(),
@@ -429,61 +2450,24 @@ impl<'a> Session<'a> {
                 output_tensor_ptrs.as_mut_ptr(),
             )
         };
-        status_to_result(status).map_err(OrtError::Run)?;
+        if let (Some(stats), Some(start)) = (stats, start) {
+            stats.duration = start.elapsed();
+        }
+        if let Err(e) = run_status_to_result(status) {
+            release_values(&output_tensor_ptrs);
+            return Err(e);
+        }
 
-        let memory_info_ref = &self.memory_info;
+        let memory_info_ref = &self.inner.memory_info;
         let outputs: Result<Vec<DynOrtTensor<ndarray::Dim<ndarray::IxDynImpl>>>> =
             output_tensor_ptrs
                 .into_iter()
-                .map(|tensor_ptr| {
-                    let (dims, data_type, len) = unsafe {
-                        call_with_tensor_info(tensor_ptr, |tensor_info_ptr| {
-                            get_tensor_dimensions(tensor_info_ptr)
-                                .map(|dims| dims.iter().map(|&n| n as usize).collect::<Vec<_>>())
-                                .and_then(|dims| {
-                                    extract_data_type(tensor_info_ptr)
-                                        .map(|data_type| (dims, data_type))
-                                })
-                                .and_then(|(dims, data_type)| {
-                                    let mut len = 0_u64;
-
-                                    call_ort(|ort| {
-                                        ort.GetTensorShapeElementCount.unwrap()(
-                                            tensor_info_ptr,
-                                            &mut len,
-                                        )
-                                    })
-                                    .map_err(OrtError::GetTensorShapeElementCount)?;
-
-                                    Ok((
-                                        dims,
-                                        data_type,
-                                        len.try_into()
-                                            .expect("u64 length could not fit into usize"),
-                                    ))
-                                })
-                        })
-                    }?;
-
-                    Ok(DynOrtTensor::new(
-                        tensor_ptr,
-                        memory_info_ref,
-                        ndarray::IxDyn(&dims),
-                        len,
-                        data_type,
-                    ))
+                .zip(self.outputs.iter())
+                .map(|(tensor_ptr, output)| {
+                    dyn_tensor_from_value(tensor_ptr, memory_info_ref, output.output_type)
                 })
                 .collect();
 
-        // Reconvert to CString so drop impl is called and memory is freed
-        let _: Vec<CString> = input_names_ptr
-            .into_iter()
-            .map(|p| {
-                assert_ne!(p, std::ptr::null());
-                unsafe { CString::from_raw(p as *mut i8) }
-            })
-            .collect();
-
         outputs
     }
 
@@ -494,8 +2478,9 @@ impl<'a> Session<'a> {
     //     Tensor::from_array(self, array)
     // }
 
-    fn validate_input_shapes<TIn, D>(&mut self, input_arrays: &[Array<TIn, D>]) -> Result<()>
+    fn validate_input_shapes<S, TIn, D>(&self, input_arrays: &[ArrayBase<S, D>]) -> Result<()>
     where
+        S: ndarray::Data<Elem = TIn>,
         TIn: TypeToTensorElementDataType + Debug + Clone,
         D: ndarray::Dimension,
     {
@@ -585,6 +2570,225 @@ impl<'a> Session<'a> {
     }
 }
 
+#[cfg(feature = "tokio")]
+impl Session<'static> {
+    /// Run the input data through the ONNX graph on tokio's blocking thread pool
+    ///
+    /// The outputs borrow the session, so they are handed to `extract` on the blocking thread,
+    /// which returns what the future resolves to (e.g. an owned copy of an output).
+    ///
+    /// [Cancelling](../run_options/struct.CancellationToken.html#method.cancel) `token` (for
+    /// example on a server's shutdown) terminates the run, the future then resolving to
+    /// [`OrtError::Cancelled`](../error/enum.OrtError.html#variant.Cancelled). Dropping the future
+    /// before it completes terminates the run as well. As with
+    /// [`run_with_cancellation()`](#method.run_with_cancellation), terminating is cooperative.
+    ///
+    /// Enabled with the `tokio` feature; must be called from within a tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned right away if the run's options cannot be created. The future resolves
+    /// to the errors of [`run()`](#method.run) and `extract`.
+    ///
+    /// ```no_run
+    /// # use std::sync::Arc;
+    /// # use onnxruntime::{environment::Environment, ndarray::array, run_options::CancellationToken};
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let session = Arc::new(
+    ///     Environment::global()?
+    ///         .new_session_builder()?
+    ///         .with_model_from_file("model.onnx")?,
+    /// );
+    /// let token = CancellationToken::new();
+    /// let output = session
+    ///     .run_async(vec![array![1.0_f32, 2.0, 3.0]], &token, |outputs| {
+    ///         Ok(outputs[0].try_extract::<f32>()?.view().to_owned())
+    ///     })?
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn run_async<TIn, D, F, R>(
+        self: Arc<Self>,
+        input_arrays: Vec<Array<TIn, D>>,
+        token: &CancellationToken,
+        extract: F,
+    ) -> Result<RunFuture<R>>
+    where
+        TIn: TypeToTensorElementDataType + Debug + Clone + Send + 'static,
+        D: ndarray::Dimension + 'static,
+        F: FnOnce(&[DynOrtTensor<'_, ndarray::IxDyn>]) -> Result<R> + Send + 'static,
+        R: Send + 'static,
+    {
+        let run_options = Arc::new(RunOptions::new()?);
+        let handle = {
+            let run_options = Arc::clone(&run_options);
+            let token = token.clone();
+            tokio::task::spawn_blocking(move || {
+                let outputs = self.run_cancellable(input_arrays, &run_options, &token)?;
+                extract(&outputs)
+            })
+        };
+        Ok(RunFuture {
+            handle,
+            run_options: Some(run_options),
+        })
+    }
+}
+
+/// Future of a run on tokio's blocking thread pool
+///
+/// Returned by [`Session::run_async()`](struct.Session.html#method.run_async). Dropping it before
+/// it completes terminates the run.
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct RunFuture<R> {
+    handle: tokio::task::JoinHandle<Result<R>>,
+    /// Options of the run, until it completes
+    run_options: Option<Arc<RunOptions>>,
+}
+
+#[cfg(feature = "tokio")]
+impl<R> Future for RunFuture<R> {
+    type Output = Result<R>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<R>> {
+        let result = match Pin::new(&mut self.handle).poll(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => return Poll::Pending,
+        };
+        self.run_options = None;
+        Poll::Ready(match result {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            // The runtime is shutting down
+            Err(_) => Err(OrtError::Cancelled),
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R> Drop for RunFuture<R> {
+    fn drop(&mut self) {
+        if let Some(run_options) = self.run_options.take() {
+            debug!("Terminating the run of a dropped future.");
+            if let Err(e) = run_options.set_terminate() {
+                error!("Failed to terminate a run: {}", e);
+            }
+        }
+    }
+}
+
+/// Hand owned arrays over as copy-on-write arrays, without copying them
+fn cow_arrays<'i, T, D>(arrays: Vec<Array<T, D>>) -> Vec<CowArray<'i, T, D>>
+where
+    D: ndarray::Dimension,
+{
+    arrays.into_iter().map(CowArray::from).collect()
+}
+
+/// Make sure an input's dimensions match the model's (except dynamic ones)
+fn validate_input_shape(input: &Input, shape: &[usize]) -> Result<()> {
+    let different_shape = shape.len() != input.dimensions.len()
+        || shape
+            .iter()
+            .zip(input.dimensions.iter())
+            .any(|(l, r)| match r {
+                Some(r) => *r as usize != *l,
+                None => false, // None means dynamic size; in that case shape always match
+            });
+    if different_shape {
+        error!(
+            "Different input shape for {}: {:?} vs {:?}",
+            input.name, shape, input.dimensions
+        );
+        return Err(OrtError::NonMatchingDimensions(
+            NonMatchingDimensionsError::InputsLength {
+                inference_input: vec![shape.to_vec()],
+                model_input: vec![input.dimensions.clone()],
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Wrap a value produced by ONNX Runtime, taking ownership of it
+///
+/// Tensors have their shape and element type read from the value itself. Sequences and maps are
+/// given a one dimensional shape of their number of elements (as reported by `GetValueCount`) and
+/// `element_type`, the type of the tensors they contain as declared by the model.
+pub(crate) fn dyn_tensor_from_value<'m>(
+    value_ptr: *mut sys::OrtValue,
+    memory_info: &'m MemoryInfo,
+    element_type: TensorElementDataType,
+) -> Result<DynOrtTensor<'m, ndarray::IxDyn>> {
+    let mut onnx_type = sys::ONNXType::ONNX_TYPE_UNKNOWN;
+    let info = unsafe { call_ort(|ort| ort.GetValueType.unwrap()(value_ptr, &mut onnx_type)) }
+        .map_err(OrtError::GetValueType)
+        .and_then(|_| match ValueType::from(onnx_type) {
+            ValueType::Tensor => tensor_info_from_value(value_ptr),
+            ValueType::Sequence | ValueType::Map => {
+                let mut count = 0_u64;
+                unsafe { call_ort(|ort| ort.GetValueCount.unwrap()(value_ptr, &mut count)) }
+                    .map_err(OrtError::GetValueCount)
+                    .map(|_| {
+                        let count: usize = count
+                            .try_into()
+                            .expect("u64 length could not fit into usize");
+                        (vec![count], element_type, count)
+                    })
+            }
+            other => Err(OrtError::UnsupportedValueType(other)),
+        });
+
+    match info {
+        Ok((dims, data_type, len)) => Ok(DynOrtTensor::new(
+            value_ptr,
+            memory_info,
+            ndarray::IxDyn(&dims),
+            len,
+            data_type,
+            onnx_type.into(),
+        )),
+        Err(e) => {
+            release_values(&[value_ptr]);
+            Err(e)
+        }
+    }
+}
+
+/// Read the dimensions, element type and number of elements of a tensor value
+pub(crate) fn tensor_info_from_value(
+    value_ptr: *const sys::OrtValue,
+) -> Result<(Vec<usize>, TensorElementDataType, usize)> {
+    unsafe {
+        call_with_tensor_info(value_ptr, |tensor_info_ptr| {
+            let dims = get_tensor_dimensions(tensor_info_ptr)?
+                .iter()
+                .map(|&n| n as usize)
+                .collect();
+            let data_type = extract_data_type(tensor_info_ptr)?;
+
+            let mut len = 0_u64;
+            call_ort(|ort| ort.GetTensorShapeElementCount.unwrap()(tensor_info_ptr, &mut len))
+                .map_err(OrtError::GetTensorShapeElementCount)?;
+
+            Ok((
+                dims,
+                data_type,
+                len.try_into().expect("u64 length could not fit into usize"),
+            ))
+        })
+    }
+}
+
+/// Release the (non-null) values, used to clean up outputs on failure
+fn release_values(values: &[*mut sys::OrtValue]) {
+    for value in values.iter().filter(|value| !value.is_null()) {
+        unsafe { g_ort().ReleaseValue.unwrap()(*value) };
+    }
+}
+
 unsafe fn get_tensor_dimensions(
     tensor_info_ptr: *const sys::OrtTensorTypeAndShapeInfo,
 ) -> Result<Vec<i64>> {
@@ -619,6 +2823,60 @@ unsafe fn extract_data_type(
     Ok(std::mem::transmute(type_sys))
 }
 
+unsafe fn extract_value_type(typeinfo_ptr: *const sys::OrtTypeInfo) -> Result<ValueType> {
+    let mut onnx_type = sys::ONNXType::ONNX_TYPE_UNKNOWN;
+    call_ort(|ort| ort.GetOnnxTypeFromTypeInfo.unwrap()(typeinfo_ptr, &mut onnx_type))
+        .map_err(OrtError::GetOnnxTypeFromTypeInfo)?;
+    Ok(onnx_type.into())
+}
+
+/// Type of the tensor elements described by a type information
+///
+/// For sequences and maps, this is the element type of the tensors they (recursively) contain;
+/// the values' for maps.
+unsafe fn extract_element_data_type(
+    typeinfo_ptr: *const sys::OrtTypeInfo,
+) -> Result<TensorElementDataType> {
+    let mut element_typeinfo_ptr: *mut sys::OrtTypeInfo = std::ptr::null_mut();
+    match extract_value_type(typeinfo_ptr)? {
+        ValueType::Tensor => {
+            let mut tensor_info_ptr: *const sys::OrtTensorTypeAndShapeInfo = std::ptr::null_mut();
+            call_ort(|ort| {
+                ort.CastTypeInfoToTensorInfo.unwrap()(typeinfo_ptr, &mut tensor_info_ptr)
+            })
+            .map_err(OrtError::CastTypeInfoToTensorInfo)?;
+            assert_ne!(tensor_info_ptr, std::ptr::null_mut());
+            return extract_data_type(tensor_info_ptr);
+        }
+        ValueType::Sequence => {
+            let mut sequence_info_ptr: *const sys::OrtSequenceTypeInfo = std::ptr::null_mut();
+            call_ort(|ort| {
+                ort.CastTypeInfoToSequenceTypeInfo.unwrap()(typeinfo_ptr, &mut sequence_info_ptr)
+            })
+            .map_err(OrtError::GetElementType)?;
+            assert_ne!(sequence_info_ptr, std::ptr::null_mut());
+            call_ort(|ort| {
+                ort.GetSequenceElementType.unwrap()(sequence_info_ptr, &mut element_typeinfo_ptr)
+            })
+            .map_err(OrtError::GetElementType)?;
+        }
+        ValueType::Map => {
+            let mut map_info_ptr: *const sys::OrtMapTypeInfo = std::ptr::null_mut();
+            call_ort(|ort| ort.CastTypeInfoToMapTypeInfo.unwrap()(typeinfo_ptr, &mut map_info_ptr))
+                .map_err(OrtError::GetElementType)?;
+            assert_ne!(map_info_ptr, std::ptr::null_mut());
+            call_ort(|ort| ort.GetMapValueType.unwrap()(map_info_ptr, &mut element_typeinfo_ptr))
+                .map_err(OrtError::GetElementType)?;
+        }
+        other => return Err(OrtError::UnsupportedValueType(other)),
+    }
+    assert_ne!(element_typeinfo_ptr, std::ptr::null_mut());
+
+    let element_type = extract_element_data_type(element_typeinfo_ptr);
+    g_ort().ReleaseTypeInfo.unwrap()(element_typeinfo_ptr);
+    element_type
+}
+
 /// Calls the provided closure with the result of `GetTensorTypeAndShape`, deallocating the
 /// resulting `*OrtTensorTypeAndShapeInfo` before returning.
 unsafe fn call_with_tensor_info<F, T>(tensor_ptr: *const sys::OrtValue, mut f: F) -> Result<T>
@@ -642,7 +2900,7 @@ where
 /// `SessionBuilder::with_model_from_file()` method.
 mod dangerous {
     use super::*;
-    use crate::tensor::TensorElementDataType;
+    use crate::tensor::{TensorElementDataType, ValueType};
 
     pub(super) fn extract_inputs_count(session_ptr: *mut sys::OrtSession) -> Result<u64> {
         let f = g_ort().SessionGetInputCount.unwrap();
@@ -654,6 +2912,18 @@ mod dangerous {
         extract_io_count(f, session_ptr)
     }
 
+    pub(super) fn extract_overridable_initializers_count(
+        session_ptr: *mut sys::OrtSession,
+    ) -> Result<u64> {
+        // Unlike inputs and outputs, models commonly have no overridable initializers
+        let mut count: u64 = 0;
+        let status = unsafe {
+            g_ort().SessionGetOverridableInitializerCount.unwrap()(session_ptr, &mut count)
+        };
+        status_to_result(status).map_err(OrtError::InOutCount)?;
+        Ok(count)
+    }
+
     fn extract_io_count(
         f: unsafe extern "C" fn(*const sys::OrtSession, *mut u64) -> *mut sys::OrtStatus,
         session_ptr: *mut sys::OrtSession,
@@ -707,6 +2977,69 @@ mod dangerous {
         Ok(name)
     }
 
+    /// Name of each input whose leading dimension is dynamic, with the name of that dimension
+    /// (empty if it has none)
+    pub(super) fn extract_dynamic_leading_dimensions(
+        session_ptr: *mut sys::OrtSession,
+        allocator_ptr: *mut sys::OrtAllocator,
+    ) -> Result<Vec<(String, String)>> {
+        let mut dimensions = Vec::new();
+        for i in 0..extract_inputs_count(session_ptr)? {
+            let mut typeinfo_ptr: *mut sys::OrtTypeInfo = std::ptr::null_mut();
+            unsafe {
+                call_ort(|ort| {
+                    ort.SessionGetInputTypeInfo.unwrap()(session_ptr, i, &mut typeinfo_ptr)
+                })
+            }
+            .map_err(OrtError::GetTypeInfo)?;
+            assert_ne!(typeinfo_ptr, std::ptr::null_mut());
+
+            let dimension = unsafe { extract_dynamic_leading_dimension(typeinfo_ptr) };
+
+            unsafe { g_ort().ReleaseTypeInfo.unwrap()(typeinfo_ptr) };
+
+            if let Some(dimension) = dimension? {
+                let input_name = extract_input_name(session_ptr, allocator_ptr, i)?;
+                dimensions.push((input_name, dimension));
+            }
+        }
+        Ok(dimensions)
+    }
+
+    /// Name of the leading dimension of a tensor if it is dynamic (empty if it has none)
+    unsafe fn extract_dynamic_leading_dimension(
+        typeinfo_ptr: *const sys::OrtTypeInfo,
+    ) -> Result<Option<String>> {
+        if extract_value_type(typeinfo_ptr)? != ValueType::Tensor {
+            return Ok(None);
+        }
+
+        let mut tensor_info_ptr: *const sys::OrtTensorTypeAndShapeInfo = std::ptr::null_mut();
+        call_ort(|ort| ort.CastTypeInfoToTensorInfo.unwrap()(typeinfo_ptr, &mut tensor_info_ptr))
+            .map_err(OrtError::CastTypeInfoToTensorInfo)?;
+        assert_ne!(tensor_info_ptr, std::ptr::null_mut());
+
+        let mut num_dims = 0;
+        call_ort(|ort| ort.GetDimensionsCount.unwrap()(tensor_info_ptr, &mut num_dims))
+            .map_err(OrtError::GetDimensionsCount)?;
+        // Scalars have no batch
+        if num_dims == 0 || get_tensor_dimensions(tensor_info_ptr)?[0] != -1 {
+            return Ok(None);
+        }
+
+        // The names are owned by the tensor info
+        let mut dim_params: Vec<*const i8> = vec![std::ptr::null(); num_dims as usize];
+        call_ort(|ort| {
+            ort.GetSymbolicDimensions.unwrap()(tensor_info_ptr, dim_params.as_mut_ptr(), num_dims)
+        })
+        .map_err(OrtError::GetDimensions)?;
+        if dim_params[0].is_null() {
+            Ok(Some(String::new()))
+        } else {
+            char_p_to_string(dim_params[0]).map(Some)
+        }
+    }
+
     pub(super) fn extract_input(
         session_ptr: *mut sys::OrtSession,
         allocator_ptr: *mut sys::OrtAllocator,
@@ -714,9 +3047,27 @@ mod dangerous {
     ) -> Result<Input> {
         let input_name = extract_input_name(session_ptr, allocator_ptr, i)?;
         let f = g_ort().SessionGetInputTypeInfo.unwrap();
-        let (input_type, dimensions) = extract_io(f, session_ptr, i)?;
+        let (value_type, input_type, dimensions) = extract_io(f, session_ptr, i)?;
+        Ok(Input {
+            name: input_name,
+            value_type,
+            input_type,
+            dimensions,
+        })
+    }
+
+    pub(super) fn extract_overridable_initializer(
+        session_ptr: *mut sys::OrtSession,
+        allocator_ptr: *mut sys::OrtAllocator,
+        i: u64,
+    ) -> Result<Input> {
+        let f = g_ort().SessionGetOverridableInitializerName.unwrap();
+        let input_name = extract_io_name(f, session_ptr, allocator_ptr, i)?;
+        let f = g_ort().SessionGetOverridableInitializerTypeInfo.unwrap();
+        let (value_type, input_type, dimensions) = extract_io(f, session_ptr, i)?;
         Ok(Input {
             name: input_name,
+            value_type,
             input_type,
             dimensions,
         })
@@ -729,9 +3080,10 @@ mod dangerous {
     ) -> Result<Output> {
         let output_name = extract_output_name(session_ptr, allocator_ptr, i)?;
         let f = g_ort().SessionGetOutputTypeInfo.unwrap();
-        let (output_type, dimensions) = extract_io(f, session_ptr, i)?;
+        let (value_type, output_type, dimensions) = extract_io(f, session_ptr, i)?;
         Ok(Output {
             name: output_name,
+            value_type,
             output_type,
             dimensions,
         })
@@ -745,33 +3097,47 @@ mod dangerous {
         ) -> *mut sys::OrtStatus,
         session_ptr: *mut sys::OrtSession,
         i: u64,
-    ) -> Result<(TensorElementDataType, Vec<Option<u32>>)> {
+    ) -> Result<(ValueType, TensorElementDataType, Vec<Option<u32>>)> {
         let mut typeinfo_ptr: *mut sys::OrtTypeInfo = std::ptr::null_mut();
 
         let status = unsafe { f(session_ptr, i as u64, &mut typeinfo_ptr) };
         status_to_result(status).map_err(OrtError::GetTypeInfo)?;
         assert_ne!(typeinfo_ptr, std::ptr::null_mut());
 
+        let io = unsafe { extract_io_type_and_dimensions(typeinfo_ptr) };
+
+        unsafe { g_ort().ReleaseTypeInfo.unwrap()(typeinfo_ptr) };
+
+        io
+    }
+
+    /// Sequences and maps have no dimensions; their type is the one of the tensors they contain.
+    unsafe fn extract_io_type_and_dimensions(
+        typeinfo_ptr: *const sys::OrtTypeInfo,
+    ) -> Result<(ValueType, TensorElementDataType, Vec<Option<u32>>)> {
+        let value_type = extract_value_type(typeinfo_ptr)?;
+        if value_type != ValueType::Tensor {
+            let io_type = extract_element_data_type(typeinfo_ptr)?;
+            return Ok((value_type, io_type, Vec::new()));
+        }
+
         let mut tensor_info_ptr: *const sys::OrtTensorTypeAndShapeInfo = std::ptr::null_mut();
-        let status = unsafe {
-            g_ort().CastTypeInfoToTensorInfo.unwrap()(typeinfo_ptr, &mut tensor_info_ptr)
-        };
+        let status = g_ort().CastTypeInfoToTensorInfo.unwrap()(typeinfo_ptr, &mut tensor_info_ptr);
         status_to_result(status).map_err(OrtError::CastTypeInfoToTensorInfo)?;
         assert_ne!(tensor_info_ptr, std::ptr::null_mut());
 
-        let io_type: TensorElementDataType = unsafe { extract_data_type(tensor_info_ptr)? };
+        let io_type: TensorElementDataType = extract_data_type(tensor_info_ptr)?;
 
         // info!("{} : type={}", i, type_);
 
-        let node_dims = unsafe { get_tensor_dimensions(tensor_info_ptr)? };
+        let node_dims = get_tensor_dimensions(tensor_info_ptr)?;
 
         // for j in 0..num_dims {
         //     info!("{} : dim {}={}", i, j, node_dims[j as usize]);
         // }
 
-        unsafe { g_ort().ReleaseTypeInfo.unwrap()(typeinfo_ptr) };
-
         Ok((
+            value_type,
             io_type,
             node_dims
                 .into_iter()
@@ -23,17 +23,33 @@
 //! will be returned by the method which can be derefed into its internal
 //! [`ndarray::ArrayView`](https://docs.rs/ndarray/latest/ndarray/type.ArrayView.html).
 
+#[cfg(feature = "image")]
+pub mod image_tensor;
+pub mod input_tensor;
 pub mod ndarray_tensor;
 pub mod ort_owned_tensor;
+pub mod ort_owned_value;
 pub mod ort_tensor;
-
-pub use ort_owned_tensor::{DynOrtTensor, OrtOwnedTensor};
+pub mod packed_int4;
+#[cfg(feature = "serde")]
+pub mod serde_tensor;
+#[cfg(feature = "stats")]
+pub(crate) mod stats;
+pub mod tensor_builder;
+
+pub use input_tensor::InputTensor;
+pub use ort_owned_tensor::{
+    DynOrtTensor, ExtractedStrings, InvalidUtf8, OrtOwnedTensor, StringExtractOptions, StringViews,
+};
+pub use ort_owned_value::{MapOutput, SequenceOutput};
 pub use ort_tensor::OrtTensor;
+pub use packed_int4::PackedInt4;
+pub use tensor_builder::TensorBuilder;
 
 use crate::tensor::ort_owned_tensor::TensorPointerHolder;
 use crate::{error::call_ort, OrtError, Result};
 use onnxruntime_sys::{self as sys, OnnxEnumInt};
-use std::{convert::TryInto as _, ffi, fmt, ptr, rc, result, string};
+use std::{convert::TryInto as _, ffi, fmt, ptr, result, string, sync};
 
 // FIXME: Use https://docs.rs/bindgen/0.54.1/bindgen/struct.Builder.html#method.rustified_enum
 // FIXME: Add tests to cover the commented out types
@@ -58,8 +74,8 @@ pub enum TensorElementDataType {
     Int64 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_INT64 as OnnxEnumInt,
     /// String, equivalent to Rust's `String`
     String = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_STRING as OnnxEnumInt,
-    // /// Boolean, equivalent to Rust's `bool`
-    // Bool = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_BOOL as OnnxEnumInt,
+    /// Boolean, equivalent to Rust's `bool`
+    Bool = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_BOOL as OnnxEnumInt,
     // /// 16-bit floating point, equivalent to Rust's `f16`
     // Float16 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_FLOAT16 as OnnxEnumInt,
     /// 64-bit floating point, equivalent to Rust's `f64`
@@ -68,10 +84,21 @@ pub enum TensorElementDataType {
     Uint32 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_UINT32 as OnnxEnumInt,
     /// Unsigned 64-bit int, equivalent to Rust's `u64`
     Uint64 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_UINT64 as OnnxEnumInt,
-    // /// Complex 64-bit floating point, equivalent to Rust's `???`
-    // Complex64 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX64 as OnnxEnumInt,
-    // /// Complex 128-bit floating point, equivalent to Rust's `???`
-    // Complex128 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX128 as OnnxEnumInt,
+    /// Complex number of two 32-bit floating points, equivalent to `num_complex::Complex<f32>`
+    ///
+    /// **NOTE**: The ONNX Runtime version this crate is built against (1.6) doesn't implement
+    /// complex tensors: creating one returns an error.
+    ///
+    /// Enabled with the `complex` feature.
+    #[cfg(feature = "complex")]
+    Complex64 =
+        sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX64 as OnnxEnumInt,
+    /// Complex number of two 64-bit floating points, equivalent to `num_complex::Complex<f64>`
+    ///
+    /// Enabled with the `complex` feature.
+    #[cfg(feature = "complex")]
+    Complex128 =
+        sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX128 as OnnxEnumInt,
     // /// Brain 16-bit floating point
     // Bfloat16 = sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_BFLOAT16 as OnnxEnumInt,
 }
@@ -88,21 +115,17 @@ impl Into<sys::ONNXTensorElementDataType> for TensorElementDataType {
             Int32 => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_INT32,
             Int64 => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_INT64,
             String => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_STRING,
-            // Bool => {
-            //     sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_BOOL
-            // }
+            Bool => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_BOOL,
             // Float16 => {
             //     sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_FLOAT16
             // }
             Double => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_DOUBLE,
             Uint32 => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_UINT32,
             Uint64 => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_UINT64,
-            // Complex64 => {
-            //     sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX64
-            // }
-            // Complex128 => {
-            //     sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX128
-            // }
+            #[cfg(feature = "complex")]
+            Complex64 => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX64,
+            #[cfg(feature = "complex")]
+            Complex128 => sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_COMPLEX128,
             // Bfloat16 => {
             //     sys::ONNXTensorElementDataType::ONNX_TENSOR_ELEMENT_DATA_TYPE_BFLOAT16
             // }
@@ -110,6 +133,89 @@ impl Into<sys::ONNXTensorElementDataType> for TensorElementDataType {
     }
 }
 
+impl TensorElementDataType {
+    /// Every supported type, with its name in ONNX type strings
+    const ONNX_TYPE_NAMES: &'static [(TensorElementDataType, &'static str)] = &[
+        (TensorElementDataType::Float, "tensor(float)"),
+        (TensorElementDataType::Uint8, "tensor(uint8)"),
+        (TensorElementDataType::Int8, "tensor(int8)"),
+        (TensorElementDataType::Uint16, "tensor(uint16)"),
+        (TensorElementDataType::Int16, "tensor(int16)"),
+        (TensorElementDataType::Int32, "tensor(int32)"),
+        (TensorElementDataType::Int64, "tensor(int64)"),
+        (TensorElementDataType::String, "tensor(string)"),
+        (TensorElementDataType::Bool, "tensor(bool)"),
+        (TensorElementDataType::Double, "tensor(double)"),
+        (TensorElementDataType::Uint32, "tensor(uint32)"),
+        (TensorElementDataType::Uint64, "tensor(uint64)"),
+        #[cfg(feature = "complex")]
+        (TensorElementDataType::Complex64, "tensor(complex64)"),
+        #[cfg(feature = "complex")]
+        (TensorElementDataType::Complex128, "tensor(complex128)"),
+    ];
+
+    /// Name of the tensor type as written by ONNX (and ONNX Runtime) in type strings, for example
+    /// `"tensor(float)"`
+    pub fn onnx_type_name(&self) -> &'static str {
+        Self::ONNX_TYPE_NAMES
+            .iter()
+            .find(|(data_type, _)| data_type == self)
+            .map(|(_, name)| *name)
+            .expect("Every type has an ONNX type name")
+    }
+
+    /// Type of an ONNX type string, for example `"tensor(int64)"`
+    ///
+    /// Returns `None` for types which are not tensors of a supported element type.
+    pub fn from_onnx_type_name(name: &str) -> Option<TensorElementDataType> {
+        Self::ONNX_TYPE_NAMES
+            .iter()
+            .find(|(_, onnx_name)| *onnx_name == name)
+            .map(|(data_type, _)| *data_type)
+    }
+
+    /// Type of an ONNX `TensorProto.DataType`, whose values match ONNX Runtime's
+    pub(crate) fn from_proto_data_type(proto_data_type: i64) -> Option<TensorElementDataType> {
+        Self::ONNX_TYPE_NAMES
+            .iter()
+            .map(|(data_type, _)| *data_type)
+            .find(|data_type| *data_type as i64 == proto_data_type)
+    }
+}
+
+/// Enum mapping the kinds of values ONNX Runtime can produce (`ONNXType`)
+///
+/// **NOTE**: Optional values were introduced after the ONNX Runtime version this crate is built
+/// against (1.6) and are thus not represented.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ValueType {
+    /// A value of unknown kind
+    Unknown,
+    /// A tensor
+    Tensor,
+    /// A sequence of values, see [`SequenceOutput`](ort_owned_value/struct.SequenceOutput.html)
+    Sequence,
+    /// A map from keys to values, see [`MapOutput`](ort_owned_value/struct.MapOutput.html)
+    Map,
+    /// An opaque value
+    Opaque,
+    /// A sparse tensor
+    SparseTensor,
+}
+
+impl From<sys::ONNXType> for ValueType {
+    fn from(onnx_type: sys::ONNXType) -> Self {
+        match onnx_type {
+            sys::ONNXType::ONNX_TYPE_UNKNOWN => ValueType::Unknown,
+            sys::ONNXType::ONNX_TYPE_TENSOR => ValueType::Tensor,
+            sys::ONNXType::ONNX_TYPE_SEQUENCE => ValueType::Sequence,
+            sys::ONNXType::ONNX_TYPE_MAP => ValueType::Map,
+            sys::ONNXType::ONNX_TYPE_OPAQUE => ValueType::Opaque,
+            sys::ONNXType::ONNX_TYPE_SPARSETENSOR => ValueType::SparseTensor,
+        }
+    }
+}
+
 /// Trait used to map Rust types (for example `f32`) to ONNX types (for example `Float`)
 pub trait TypeToTensorElementDataType {
     /// Return the ONNX type for a Rust type
@@ -117,6 +223,24 @@ pub trait TypeToTensorElementDataType {
 
     /// If the type is `String`, returns `Some` with utf8 contents, else `None`.
     fn try_utf8_bytes(&self) -> Option<&[u8]>;
+
+    /// If the type is a real number (or a `bool`), returns `Some` with its value, else `None`.
+    ///
+    /// Used to cast inputs to the element type expected by the model.
+    #[doc(hidden)]
+    fn try_number(&self) -> Option<Number> {
+        None
+    }
+}
+
+/// The value of a numeric element, wide enough to hold any of them exactly
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    /// An integer (or a `bool`, as 0 or 1)
+    Int(i128),
+    /// A floating point number
+    Float(f64),
 }
 
 macro_rules! impl_prim_type_to_ort_trait {
@@ -132,22 +256,42 @@ macro_rules! impl_prim_type_to_ort_trait {
             }
         }
     };
+    ($type_:ty, $variant:ident, $number:ident, $as_number:ty) => {
+        impl TypeToTensorElementDataType for $type_ {
+            fn tensor_element_data_type() -> TensorElementDataType {
+                TensorElementDataType::$variant
+            }
+
+            fn try_utf8_bytes(&self) -> Option<&[u8]> {
+                None
+            }
+
+            fn try_number(&self) -> Option<Number> {
+                Some(Number::$number(*self as $as_number))
+            }
+        }
+    };
 }
 
-impl_prim_type_to_ort_trait!(f32, Float);
-impl_prim_type_to_ort_trait!(u8, Uint8);
-impl_prim_type_to_ort_trait!(i8, Int8);
-impl_prim_type_to_ort_trait!(u16, Uint16);
-impl_prim_type_to_ort_trait!(i16, Int16);
-impl_prim_type_to_ort_trait!(i32, Int32);
-impl_prim_type_to_ort_trait!(i64, Int64);
-// impl_type_trait!(bool, Bool);
+impl_prim_type_to_ort_trait!(f32, Float, Float, f64);
+impl_prim_type_to_ort_trait!(u8, Uint8, Int, i128);
+impl_prim_type_to_ort_trait!(i8, Int8, Int, i128);
+impl_prim_type_to_ort_trait!(u16, Uint16, Int, i128);
+impl_prim_type_to_ort_trait!(i16, Int16, Int, i128);
+impl_prim_type_to_ort_trait!(i32, Int32, Int, i128);
+impl_prim_type_to_ort_trait!(i64, Int64, Int, i128);
+// A Rust `bool` is a byte holding 0 or 1, just like ONNX Runtime's: it is given to the runtime as is
+impl_prim_type_to_ort_trait!(bool, Bool, Int, i128);
 // impl_type_trait!(f16, Float16);
-impl_prim_type_to_ort_trait!(f64, Double);
-impl_prim_type_to_ort_trait!(u32, Uint32);
-impl_prim_type_to_ort_trait!(u64, Uint64);
-// impl_type_trait!(, Complex64);
-// impl_type_trait!(, Complex128);
+impl_prim_type_to_ort_trait!(f64, Double, Float, f64);
+impl_prim_type_to_ort_trait!(u32, Uint32, Int, i128);
+impl_prim_type_to_ort_trait!(u64, Uint64, Int, i128);
+// `num_complex::Complex` is `#[repr(C)]`: its real part followed by its imaginary part, just like
+// ONNX Runtime's complex elements
+#[cfg(feature = "complex")]
+impl_prim_type_to_ort_trait!(num_complex::Complex<f32>, Complex64);
+#[cfg(feature = "complex")]
+impl_prim_type_to_ort_trait!(num_complex::Complex<f64>, Complex128);
 // impl_type_trait!(, Bfloat16);
 
 /// Adapter for common Rust string types to Onnx strings.
@@ -192,7 +336,7 @@ pub trait TensorDataToType: Sized + Clone + fmt::Debug {
     fn extract_data<'t, D>(
         shape: D,
         tensor_element_len: usize,
-        tensor_ptr: rc::Rc<TensorPointerHolder>,
+        tensor_ptr: sync::Arc<TensorPointerHolder>,
     ) -> Result<TensorData<'t, Self, D>>
     where
         D: ndarray::Dimension;
@@ -211,7 +355,7 @@ where
     /// primitive numeric types.
     TensorPtr {
         /// The pointer ort produced. Kept alive so that `array_view` is valid.
-        ptr: rc::Rc<TensorPointerHolder>,
+        ptr: sync::Arc<TensorPointerHolder>,
         /// A view into `ptr`
         array_view: ndarray::ArrayView<'t, T, D>,
     },
@@ -222,6 +366,12 @@ where
         /// Owned Strings copied out of ort's output
         strings: ndarray::Array<T, D>,
     },
+    /// Data whose in-memory form from ort can't be used as is, like booleans, is converted into an
+    /// owned array.
+    Copied {
+        /// The converted elements
+        array: ndarray::Array<T, D>,
+    },
 }
 
 /// Implements `OwnedTensorDataToType` for primitives, which can use `GetTensorMutableData`
@@ -234,13 +384,13 @@ macro_rules! impl_prim_type_from_ort_trait {
 
             fn extract_data<'t, D>(
                 shape: D,
-                _tensor_element_len: usize,
-                tensor_ptr: rc::Rc<TensorPointerHolder>,
+                tensor_element_len: usize,
+                tensor_ptr: sync::Arc<TensorPointerHolder>,
             ) -> Result<TensorData<'t, Self, D>>
             where
                 D: ndarray::Dimension,
             {
-                extract_primitive_array(shape, tensor_ptr.tensor_ptr).map(|v| {
+                extract_primitive_array(shape, tensor_element_len, tensor_ptr.tensor_ptr).map(|v| {
                     TensorData::TensorPtr {
                         ptr: tensor_ptr,
                         array_view: v,
@@ -256,13 +406,13 @@ macro_rules! impl_prim_type_from_ort_trait {
 
             fn extract_data<'t, D>(
                 shape: D,
-                _tensor_element_len: usize,
-                tensor_ptr: rc::Rc<TensorPointerHolder>,
+                tensor_element_len: usize,
+                tensor_ptr: sync::Arc<TensorPointerHolder>,
             ) -> Result<TensorData<'t, Self, D>>
             where
                 D: ndarray::Dimension,
             {
-                extract_primitive_array(shape, tensor_ptr.tensor_ptr).map(|v| {
+                extract_primitive_array(shape, tensor_element_len, tensor_ptr.tensor_ptr).map(|v| {
                     TensorData::TensorPtr {
                         ptr: tensor_ptr,
                         array_view: v,
@@ -273,17 +423,35 @@ macro_rules! impl_prim_type_from_ort_trait {
     };
 }
 
+/// Make sure a tensor's shape matches its number of elements
+pub(crate) fn check_shape<D>(shape: &D, element_count: usize) -> Result<()>
+where
+    D: ndarray::Dimension,
+{
+    if shape.size_checked() == Some(element_count) {
+        Ok(())
+    } else {
+        Err(OrtError::ShapeMismatch {
+            shape: shape.slice().to_vec(),
+            element_count,
+        })
+    }
+}
+
 /// Construct an [ndarray::ArrayView] over an Ort tensor.
 ///
 /// Only to be used on types whose Rust in-memory representation matches Ort's (e.g. primitive
 /// numeric types like u32).
 fn extract_primitive_array<'t, D, T: TensorDataToType>(
     shape: D,
+    tensor_element_len: usize,
     tensor: *mut sys::OrtValue,
 ) -> Result<ndarray::ArrayView<'t, T, D>>
 where
     D: ndarray::Dimension,
 {
+    check_shape(&shape, tensor_element_len)?;
+
     // Get pointer to output tensor float values
     let mut output_array_ptr: *mut T = ptr::null_mut();
     let output_array_ptr_ptr: *mut *mut T = &mut output_array_ptr;
@@ -295,7 +463,13 @@ where
         })
     }
     .map_err(OrtError::GetTensorMutableData)?;
-    assert_ne!(output_array_ptr, ptr::null_mut());
+    if output_array_ptr.is_null() {
+        if tensor_element_len != 0 {
+            return Err(OrtError::NullTensorData);
+        }
+        // An empty view never reads its pointer, but it must still be non-null and aligned
+        output_array_ptr = ptr::NonNull::dangling().as_ptr();
+    }
 
     let array_view = unsafe { ndarray::ArrayView::from_shape_ptr(shape, output_array_ptr) };
     Ok(array_view)
@@ -311,6 +485,10 @@ impl_prim_type_from_ort_trait!(i64, Int64);
 impl_prim_type_from_ort_trait!(f64, Double);
 impl_prim_type_from_ort_trait!(u32, Uint32);
 impl_prim_type_from_ort_trait!(u64, Uint64);
+#[cfg(feature = "complex")]
+impl_prim_type_from_ort_trait!(num_complex::Complex<f32>, Complex64);
+#[cfg(feature = "complex")]
+impl_prim_type_from_ort_trait!(num_complex::Complex<f64>, Complex128);
 
 impl TensorDataToType for String {
     fn tensor_element_data_type() -> TensorElementDataType {
@@ -320,61 +498,316 @@ impl TensorDataToType for String {
     fn extract_data<'t, D: ndarray::Dimension>(
         shape: D,
         tensor_element_len: usize,
-        tensor_ptr: rc::Rc<TensorPointerHolder>,
+        tensor_ptr: sync::Arc<TensorPointerHolder>,
+    ) -> Result<TensorData<'t, Self, D>> {
+        let (string_contents, offsets) = read_string_tensor(&tensor_ptr, tensor_element_len)?;
+
+        let strings = offsets
+            // offsets has 1 extra offset past the end so that all windows work
+            .windows(2)
+            .map(|w| {
+                let slice = &string_contents[w[0]..w[1]];
+                String::from_utf8(slice.into())
+            })
+            .collect::<result::Result<Vec<String>, string::FromUtf8Error>>()
+            .map_err(OrtError::StringFromUtf8Error)?;
+
+        check_shape(&shape, strings.len())?;
+        let array = ndarray::Array::from_shape_vec(shape, strings)
+            .expect("Shape was checked against the number of strings");
+
+        Ok(TensorData::Strings { strings: array })
+    }
+}
+
+/// Booleans are read as bytes: ONNX Runtime should only produce 0 or 1, but any other value would
+/// be an invalid `bool` (undefined behavior) in a view, so each byte is converted instead.
+impl TensorDataToType for bool {
+    fn tensor_element_data_type() -> TensorElementDataType {
+        TensorElementDataType::Bool
+    }
+
+    fn extract_data<'t, D: ndarray::Dimension>(
+        shape: D,
+        tensor_element_len: usize,
+        tensor_ptr: sync::Arc<TensorPointerHolder>,
     ) -> Result<TensorData<'t, Self, D>> {
-        // Total length of string data, not including \0 suffix
-        let mut total_length = 0_u64;
+        let bytes: ndarray::ArrayView<u8, D> =
+            extract_primitive_array(shape, tensor_element_len, tensor_ptr.tensor_ptr)?;
+        Ok(TensorData::Copied {
+            array: bytes.mapv(|byte| byte != 0),
+        })
+    }
+}
+
+/// Copy the contents of a string tensor out of ort
+///
+/// Returns the concatenated strings and the offset of each string in it, followed by the total
+/// length so that each string lies between two consecutive offsets.
+pub(crate) fn read_string_tensor(
+    tensor_ptr: &TensorPointerHolder,
+    tensor_element_len: usize,
+) -> Result<(Vec<u8>, Vec<usize>)> {
+    // Total length of string data, not including \0 suffix
+    let mut total_length = 0_u64;
+    unsafe {
+        call_ort(|ort| {
+            ort.GetStringTensorDataLength.unwrap()(tensor_ptr.tensor_ptr, &mut total_length)
+        })
+        .map_err(OrtError::GetStringTensorDataLength)?
+    }
+
+    // In the JNI impl of this, tensor_element_len was included in addition to total_length,
+    // but that seems contrary to the docs of GetStringTensorDataLength, and those extra bytes
+    // don't seem to be written to in practice either.
+    // If the string data actually did go farther, it would panic when using the offset
+    // data to get slices for each string.
+    let mut string_contents = vec![0_u8; total_length as usize];
+    // one extra slot so that the total length can go in the last one, making all per-string
+    // length calculations easy
+    let mut offsets = vec![0_u64; tensor_element_len as usize + 1];
+
+    unsafe {
+        call_ort(|ort| {
+            ort.GetStringTensorContent.unwrap()(
+                tensor_ptr.tensor_ptr,
+                string_contents.as_mut_ptr() as *mut ffi::c_void,
+                total_length,
+                offsets.as_mut_ptr(),
+                tensor_element_len as u64,
+            )
+        })
+        .map_err(OrtError::GetStringTensorContent)?
+    }
+
+    // final offset = overall length so that per-string length calculations work for the last
+    // string
+    debug_assert_eq!(0, offsets[tensor_element_len]);
+    offsets[tensor_element_len] = total_length;
+
+    let offsets = offsets
+        .into_iter()
+        .map(|offset| offset.try_into().expect("Offset didn't fit into usize"))
+        .collect();
+
+    Ok((string_contents, offsets))
+}
+
+/// Number of elements shown at each end of a tensor by [debug_summary]
+const SUMMARY_EDGE_ELEMENTS: usize = 3;
+
+/// Describe a tensor in one line: its element type, its shape and its first and last elements
+pub(crate) fn debug_summary<T, D>(
+    data_type: TensorElementDataType,
+    array: ndarray::ArrayView<T, D>,
+) -> String
+where
+    T: fmt::Debug,
+    D: ndarray::Dimension,
+{
+    let len = array.len();
+    let elements: Vec<String> = if len <= 2 * SUMMARY_EDGE_ELEMENTS {
+        array.iter().map(|elt| format!("{:?}", elt)).collect()
+    } else {
+        let first = array.iter().take(SUMMARY_EDGE_ELEMENTS);
+        let last = array.iter().skip(len - SUMMARY_EDGE_ELEMENTS);
+        first
+            .map(|elt| format!("{:?}", elt))
+            .chain(std::iter::once(String::from("...")))
+            .chain(last.map(|elt| format!("{:?}", elt)))
+            .collect()
+    };
+    format!(
+        "{:?} tensor of shape {:?}: [{}]",
+        data_type,
+        array.shape(),
+        elements.join(", ")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    /// Create a tensor value of the given type and shape, with uninitialized (or empty string) data
+    fn create_tensor(
+        shape: &[i64],
+        data_type: TensorElementDataType,
+    ) -> sync::Arc<TensorPointerHolder> {
+        sync::Arc::new(TensorPointerHolder {
+            tensor_ptr: create_value(shape, data_type),
+        })
+    }
+
+    /// A tensor value created through the C API, as other libraries would, owned by the caller
+    pub(crate) fn create_value(
+        shape: &[i64],
+        data_type: TensorElementDataType,
+    ) -> *mut sys::OrtValue {
+        let mut allocator_ptr: *mut sys::OrtAllocator = ptr::null_mut();
+        unsafe { call_ort(|ort| ort.GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr)) }
+            .unwrap();
+        let mut tensor_ptr: *mut sys::OrtValue = ptr::null_mut();
         unsafe {
             call_ort(|ort| {
-                ort.GetStringTensorDataLength.unwrap()(tensor_ptr.tensor_ptr, &mut total_length)
+                ort.CreateTensorAsOrtValue.unwrap()(
+                    allocator_ptr,
+                    shape.as_ptr(),
+                    shape.len() as u64,
+                    data_type.into(),
+                    &mut tensor_ptr,
+                )
             })
-            .map_err(OrtError::GetStringTensorDataLength)?
+        }
+        .unwrap();
+        tensor_ptr
+    }
+
+    #[test]
+    fn primitive_shape_mismatch() {
+        let tensor = create_tensor(&[2, 2], TensorElementDataType::Float);
+
+        // A shape disagreeing with the number of elements must not be trusted
+        let result = f32::extract_data(ndarray::IxDyn(&[2, 3]), 4, tensor.clone());
+        match result {
+            Err(OrtError::ShapeMismatch {
+                shape,
+                element_count,
+            }) => {
+                assert_eq!(shape, vec![2, 3]);
+                assert_eq!(element_count, 4);
+            }
+            other => panic!("Expected a shape mismatch, got {:?}", other),
         }
 
-        // In the JNI impl of this, tensor_element_len was included in addition to total_length,
-        // but that seems contrary to the docs of GetStringTensorDataLength, and those extra bytes
-        // don't seem to be written to in practice either.
-        // If the string data actually did go farther, it would panic below when using the offset
-        // data to get slices for each string.
-        let mut string_contents = vec![0_u8; total_length as usize];
-        // one extra slot so that the total length can go in the last one, making all per-string
-        // length calculations easy
-        let mut offsets = vec![0_u64; tensor_element_len as usize + 1];
+        let data = f32::extract_data(ndarray::IxDyn(&[4]), 4, tensor).unwrap();
+        match data {
+            TensorData::TensorPtr { array_view, .. } => assert_eq!(array_view.shape(), &[4]),
+            other => panic!("Expected a tensor pointer, got {:?}", other),
+        }
+    }
 
+    #[test]
+    fn string_shape_mismatch() {
+        let tensor = create_tensor(&[3], TensorElementDataType::String);
+        let strings: Vec<ffi::CString> = ["a", "bc", "def"]
+            .iter()
+            .map(|s| ffi::CString::new(*s).unwrap())
+            .collect();
+        let string_ptrs: Vec<*const std::os::raw::c_char> =
+            strings.iter().map(|s| s.as_ptr()).collect();
         unsafe {
             call_ort(|ort| {
-                ort.GetStringTensorContent.unwrap()(
-                    tensor_ptr.tensor_ptr,
-                    string_contents.as_mut_ptr() as *mut ffi::c_void,
-                    total_length,
-                    offsets.as_mut_ptr(),
-                    tensor_element_len as u64,
+                ort.FillStringTensor.unwrap()(
+                    tensor.tensor_ptr,
+                    string_ptrs.as_ptr(),
+                    string_ptrs.len() as u64,
                 )
             })
-            .map_err(OrtError::GetStringTensorContent)?
         }
+        .unwrap();
 
-        // final offset = overall length so that per-string length calculations work for the last
-        // string
-        debug_assert_eq!(0, offsets[tensor_element_len]);
-        offsets[tensor_element_len] = total_length;
+        assert!(matches!(
+            String::extract_data(ndarray::IxDyn(&[2, 2]), 3, tensor.clone()),
+            Err(OrtError::ShapeMismatch {
+                element_count: 3,
+                ..
+            })
+        ));
 
-        let strings = offsets
-            // offsets has 1 extra offset past the end so that all windows work
-            .windows(2)
-            .map(|w| {
-                let start: usize = w[0].try_into().expect("Offset didn't fit into usize");
-                let next_start: usize = w[1].try_into().expect("Offset didn't fit into usize");
+        match String::extract_data(ndarray::IxDyn(&[3]), 3, tensor).unwrap() {
+            TensorData::Strings { strings } => {
+                assert_eq!(strings.as_slice().unwrap(), &["a", "bc", "def"])
+            }
+            other => panic!("Expected strings, got {:?}", other),
+        }
+    }
 
-                let slice = &string_contents[start..next_start];
-                String::from_utf8(slice.into())
+    #[test]
+    fn bool_from_bytes() {
+        let tensor = create_tensor(&[4], TensorElementDataType::Bool);
+        // Any non-zero byte is read as `true`, without ever viewing it as a `bool`
+        let mut data_ptr: *mut u8 = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    tensor.tensor_ptr,
+                    &mut data_ptr as *mut *mut u8 as *mut *mut ffi::c_void,
+                )
             })
-            .collect::<result::Result<Vec<String>, string::FromUtf8Error>>()
-            .map_err(OrtError::StringFromUtf8Error)?;
+        }
+        .unwrap();
+        unsafe { std::slice::from_raw_parts_mut(data_ptr, 4) }.copy_from_slice(&[0, 1, 2, 0]);
 
-        let array = ndarray::Array::from_shape_vec(shape, strings)
-            .expect("Shape extracted from tensor didn't match tensor contents");
+        match bool::extract_data(ndarray::IxDyn(&[4]), 4, tensor).unwrap() {
+            TensorData::Copied { array } => {
+                assert_eq!(array.as_slice().unwrap(), &[false, true, true, false])
+            }
+            other => panic!("Expected copied data, got {:?}", other),
+        }
+    }
 
-        Ok(TensorData::Strings { strings: array })
+    #[cfg(feature = "complex")]
+    #[test]
+    fn complex_types() {
+        use num_complex::Complex;
+
+        assert_eq!(
+            <Complex<f32> as TypeToTensorElementDataType>::tensor_element_data_type(),
+            TensorElementDataType::Complex64
+        );
+        assert_eq!(
+            <Complex<f64> as TensorDataToType>::tensor_element_data_type(),
+            TensorElementDataType::Complex128
+        );
+        assert_eq!(
+            TensorElementDataType::from_onnx_type_name("tensor(complex64)"),
+            Some(TensorElementDataType::Complex64)
+        );
+        assert_eq!(
+            TensorElementDataType::from_proto_data_type(15),
+            Some(TensorElementDataType::Complex128)
+        );
+        // Laid out as ONNX Runtime expects: the real part, then the imaginary part
+        assert_eq!(std::mem::size_of::<Complex<f32>>(), 8);
+        let pair: [f32; 2] = unsafe { std::mem::transmute(Complex::new(1.0_f32, 2.0)) };
+        assert_eq!(pair, [1.0, 2.0]);
+    }
+
+    #[test]
+    fn onnx_type_names() {
+        assert_eq!(
+            TensorElementDataType::Float.onnx_type_name(),
+            "tensor(float)"
+        );
+        assert_eq!(
+            TensorElementDataType::Int64.onnx_type_name(),
+            "tensor(int64)"
+        );
+        assert_eq!(
+            TensorElementDataType::String.onnx_type_name(),
+            "tensor(string)"
+        );
+
+        for data_type in &[
+            TensorElementDataType::Float,
+            TensorElementDataType::Uint8,
+            TensorElementDataType::Int32,
+            TensorElementDataType::Double,
+            TensorElementDataType::Uint64,
+            TensorElementDataType::Bool,
+        ] {
+            assert_eq!(
+                TensorElementDataType::from_onnx_type_name(data_type.onnx_type_name()),
+                Some(*data_type)
+            );
+        }
+
+        assert_eq!(TensorElementDataType::from_onnx_type_name("float"), None);
+        assert_eq!(
+            TensorElementDataType::from_onnx_type_name("seq(tensor(float))"),
+            None
+        );
     }
 }
@@ -0,0 +1,172 @@
+//! Module converting [`image`](https://docs.rs/image) buffers into input tensors
+//!
+//! Enabled with the `image` feature.
+
+use ::image::RgbImage;
+use ndarray::{Array, Axis, Ix4};
+
+use crate::{
+    tensor::ndarray_tensor::{normalize_hwc_mut, normalize_mut},
+    Result,
+};
+
+/// Memory layout of an image tensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageLayout {
+    /// Batch, channels, height, width: the layout of most vision models (e.g. converted from PyTorch)
+    Nchw,
+    /// Batch, height, width, channels: the layout of models converted from TensorFlow
+    Nhwc,
+}
+
+/// Normalization applied to each pixel's channel value `x` (in `0..=255`)
+///
+/// The value becomes `(x * scale - mean[c]) / std[c]` for channel `c`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    /// Factor applied to the raw values first
+    pub scale: f32,
+    /// Per channel (red, green, blue) mean subtracted from the scaled values
+    pub mean: [f32; 3],
+    /// Per channel (red, green, blue) standard deviation dividing the centered values
+    pub std: [f32; 3],
+}
+
+impl Normalization {
+    /// Scale the values to `[0, 1]` and normalize them with ImageNet's mean and standard deviation
+    pub fn imagenet() -> Normalization {
+        Normalization {
+            scale: 1.0 / 255.0,
+            mean: [0.485, 0.456, 0.406],
+            std: [0.229, 0.224, 0.225],
+        }
+    }
+}
+
+impl Default for Normalization {
+    /// Scale the values to `[0, 1]`, without further normalization
+    fn default() -> Self {
+        Normalization {
+            scale: 1.0 / 255.0,
+            mean: [0.0; 3],
+            std: [1.0; 3],
+        }
+    }
+}
+
+/// Convert an RGB image into a normalized batch of one image, see
+/// [`OrtTensor::from_image()`](../struct.OrtTensor.html#method.from_image)
+pub(crate) fn normalized_array(
+    image: &RgbImage,
+    layout: ImageLayout,
+    normalization: Normalization,
+) -> Result<Array<f32, Ix4>> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let Normalization { scale, mean, std } = normalization;
+
+    let array = match layout {
+        ImageLayout::Nchw => {
+            let mut array = Array::from_shape_fn((3, height, width), |(c, y, x)| {
+                f32::from(image.get_pixel(x as u32, y as u32)[c]) * scale
+            });
+            normalize_mut(&mut array, mean, std)?;
+            array
+        }
+        ImageLayout::Nhwc => {
+            let mut array = Array::from_shape_fn((height, width, 3), |(y, x, c)| {
+                f32::from(image.get_pixel(x as u32, y as u32)[c]) * scale
+            });
+            normalize_hwc_mut(&mut array, mean, std)?;
+            array
+        }
+    };
+
+    Ok(array.insert_axis(Axis(0)))
+}
+
+/// Convert an RGB image into a batch of one image of raw `u8` channel values, without
+/// normalization
+///
+/// This suits models which normalize their input themselves, with preprocessing nodes. See also
+/// [`OrtTensor::from_image_raw()`](../struct.OrtTensor.html#method.from_image_raw).
+pub fn from_image_raw(image: &RgbImage, layout: ImageLayout) -> Array<u8, Ix4> {
+    let (width, height) = (image.width() as usize, image.height() as usize);
+    let array = match layout {
+        ImageLayout::Nchw => Array::from_shape_fn((3, height, width), |(c, y, x)| {
+            image.get_pixel(x as u32, y as u32)[c]
+        }),
+        ImageLayout::Nhwc => Array::from_shape_vec((height, width, 3), image.as_raw().clone())
+            .expect("RGB images hold 3 values per pixel"),
+    };
+    array.insert_axis(Axis(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::OrtTensor;
+    use ::image::Rgb;
+    use test_env_log::test;
+
+    fn test_image() -> RgbImage {
+        // 2x1 image: a red pixel, then a gray one
+        let mut image = RgbImage::new(2, 1);
+        image.put_pixel(0, 0, Rgb([255, 0, 0]));
+        image.put_pixel(1, 0, Rgb([51, 102, 153]));
+        image
+    }
+
+    #[test]
+    fn from_image_nchw() {
+        let tensor =
+            OrtTensor::from_image(&test_image(), ImageLayout::Nchw, Normalization::default())
+                .unwrap();
+        assert_eq!(tensor.shape(), &[1, 3, 1, 2]);
+        let expected = [1.0, 0.2, 0.0, 0.4, 0.0, 0.6];
+        for (value, expected) in tensor.view().iter().zip(expected.iter()) {
+            assert!((value - expected).abs() < 1e-6, "{} != {}", value, expected);
+        }
+    }
+
+    #[test]
+    fn from_image_nhwc_normalized() {
+        let normalization = Normalization {
+            scale: 1.0,
+            mean: [51.0, 0.0, 153.0],
+            std: [2.0, 1.0, 3.0],
+        };
+        let tensor =
+            OrtTensor::from_image(&test_image(), ImageLayout::Nhwc, normalization).unwrap();
+        assert_eq!(tensor.shape(), &[1, 1, 2, 3]);
+        assert_eq!(
+            tensor.view().iter().cloned().collect::<Vec<_>>(),
+            vec![102.0, 0.0, -51.0, 0.0, 102.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn from_image_raw_layouts() {
+        let array = from_image_raw(&test_image(), ImageLayout::Nchw);
+        assert_eq!(array.shape(), &[1, 3, 1, 2]);
+        assert_eq!(
+            array.iter().cloned().collect::<Vec<_>>(),
+            vec![255, 51, 0, 102, 0, 153]
+        );
+
+        let tensor = OrtTensor::from_image_raw(&test_image(), ImageLayout::Nhwc).unwrap();
+        assert_eq!(tensor.shape(), &[1, 1, 2, 3]);
+        assert_eq!(
+            tensor.view().iter().cloned().collect::<Vec<_>>(),
+            vec![255, 0, 0, 51, 102, 153]
+        );
+    }
+
+    #[test]
+    fn from_image_zero_std() {
+        let normalization = Normalization {
+            std: [1.0, 0.0, 1.0],
+            ..Normalization::default()
+        };
+        assert!(OrtTensor::from_image(&test_image(), ImageLayout::Nchw, normalization).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+//! Module containing an input tensor of any element type
+//!
+//! [`Session::run()`](../../session/struct.Session.html#method.run) takes inputs all of the same
+//! element type. An [`InputTensor`](enum.InputTensor.html) erases the element type of an array so
+//! that inputs of different types can be given together, for example to
+//! [`Session::run_map()`](../../session/struct.Session.html#method.run_map).
+
+use std::{convert::TryFrom, fmt::Debug};
+
+use ndarray::{Array, ArrayBase, IxDyn};
+
+use onnxruntime_sys as sys;
+
+use crate::{
+    memory::MemoryInfo,
+    tensor::{Number, OrtTensor, TensorElementDataType, TypeToTensorElementDataType},
+    Result,
+};
+
+/// An input array, of any element type
+///
+/// Build it from an array with `From`:
+///
+/// ```
+/// # use onnxruntime::tensor::{input_tensor::InputTensor, TensorElementDataType};
+/// let tensor = InputTensor::from(ndarray::arr1(&[true, false]));
+/// assert_eq!(tensor.data_type(), TensorElementDataType::Bool);
+/// assert_eq!(tensor.shape(), &[2]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum InputTensor {
+    Float(Array<f32, IxDyn>),
+    Uint8(Array<u8, IxDyn>),
+    Int8(Array<i8, IxDyn>),
+    Uint16(Array<u16, IxDyn>),
+    Int16(Array<i16, IxDyn>),
+    Int32(Array<i32, IxDyn>),
+    Int64(Array<i64, IxDyn>),
+    String(Array<String, IxDyn>),
+    Double(Array<f64, IxDyn>),
+    Uint32(Array<u32, IxDyn>),
+    Uint64(Array<u64, IxDyn>),
+    Bool(Array<bool, IxDyn>),
+}
+
+macro_rules! impl_from_array {
+    ($type_:ty, $variant:ident) => {
+        impl<D> From<Array<$type_, D>> for InputTensor
+        where
+            D: ndarray::Dimension,
+        {
+            fn from(array: Array<$type_, D>) -> InputTensor {
+                InputTensor::$variant(array.into_dyn())
+            }
+        }
+    };
+}
+
+impl_from_array!(f32, Float);
+impl_from_array!(u8, Uint8);
+impl_from_array!(i8, Int8);
+impl_from_array!(u16, Uint16);
+impl_from_array!(i16, Int16);
+impl_from_array!(i32, Int32);
+impl_from_array!(i64, Int64);
+impl_from_array!(String, String);
+impl_from_array!(f64, Double);
+impl_from_array!(u32, Uint32);
+impl_from_array!(u64, Uint64);
+impl_from_array!(bool, Bool);
+
+/// Apply `$body` to the array of any variant, bound to `$array`
+macro_rules! with_array {
+    ($tensor:expr, $array:ident => $body:expr) => {
+        match $tensor {
+            InputTensor::Float($array) => $body,
+            InputTensor::Uint8($array) => $body,
+            InputTensor::Int8($array) => $body,
+            InputTensor::Uint16($array) => $body,
+            InputTensor::Int16($array) => $body,
+            InputTensor::Int32($array) => $body,
+            InputTensor::Int64($array) => $body,
+            InputTensor::String($array) => $body,
+            InputTensor::Double($array) => $body,
+            InputTensor::Uint32($array) => $body,
+            InputTensor::Uint64($array) => $body,
+            InputTensor::Bool($array) => $body,
+        }
+    };
+}
+
+impl InputTensor {
+    /// The element type of the tensor
+    pub fn data_type(&self) -> TensorElementDataType {
+        match self {
+            InputTensor::Float(_) => TensorElementDataType::Float,
+            InputTensor::Uint8(_) => TensorElementDataType::Uint8,
+            InputTensor::Int8(_) => TensorElementDataType::Int8,
+            InputTensor::Uint16(_) => TensorElementDataType::Uint16,
+            InputTensor::Int16(_) => TensorElementDataType::Int16,
+            InputTensor::Int32(_) => TensorElementDataType::Int32,
+            InputTensor::Int64(_) => TensorElementDataType::Int64,
+            InputTensor::String(_) => TensorElementDataType::String,
+            InputTensor::Double(_) => TensorElementDataType::Double,
+            InputTensor::Uint32(_) => TensorElementDataType::Uint32,
+            InputTensor::Uint64(_) => TensorElementDataType::Uint64,
+            InputTensor::Bool(_) => TensorElementDataType::Bool,
+        }
+    }
+
+    /// The shape of the tensor
+    pub fn shape(&self) -> &[usize] {
+        with_array!(self, array => array.shape())
+    }
+
+    /// Cast the tensor to another element type, see `cast_array()`
+    pub(crate) fn cast(&self, data_type: TensorElementDataType) -> Option<(InputTensor, usize)> {
+        with_array!(self, array => cast_array(array, data_type))
+    }
+
+    /// Hand the array to ONNX Runtime
+    pub(crate) fn into_ort_value<'t>(
+        self,
+        memory_info: &'t MemoryInfo,
+        allocator_ptr: *mut sys::OrtAllocator,
+    ) -> Result<Box<dyn AsOrtValue + 't>> {
+        with_array!(self, array => {
+            Ok(Box::new(OrtTensor::from_array(memory_info, allocator_ptr, array.into())?))
+        })
+    }
+}
+
+/// Cast a numeric array to the numeric element type `data_type`
+///
+/// Returns the cast tensor and the number of elements whose value changed (out of range integers
+/// are saturated, floats are rounded), or `None` if either element type isn't numeric.
+pub(crate) fn cast_array<S, T, D>(
+    array: &ArrayBase<S, D>,
+    data_type: TensorElementDataType,
+) -> Option<(InputTensor, usize)>
+where
+    S: ndarray::Data<Elem = T>,
+    T: TypeToTensorElementDataType,
+    D: ndarray::Dimension,
+{
+    // Checked upfront so that empty arrays of strings aren't cast either
+    if matches!(T::tensor_element_data_type(), TensorElementDataType::String) {
+        return None;
+    }
+    match data_type {
+        TensorElementDataType::Float => cast_to(array).map(|(a, n)| (InputTensor::Float(a), n)),
+        TensorElementDataType::Uint8 => cast_to(array).map(|(a, n)| (InputTensor::Uint8(a), n)),
+        TensorElementDataType::Int8 => cast_to(array).map(|(a, n)| (InputTensor::Int8(a), n)),
+        TensorElementDataType::Uint16 => cast_to(array).map(|(a, n)| (InputTensor::Uint16(a), n)),
+        TensorElementDataType::Int16 => cast_to(array).map(|(a, n)| (InputTensor::Int16(a), n)),
+        TensorElementDataType::Int32 => cast_to(array).map(|(a, n)| (InputTensor::Int32(a), n)),
+        TensorElementDataType::Int64 => cast_to(array).map(|(a, n)| (InputTensor::Int64(a), n)),
+        TensorElementDataType::Double => cast_to(array).map(|(a, n)| (InputTensor::Double(a), n)),
+        TensorElementDataType::Uint32 => cast_to(array).map(|(a, n)| (InputTensor::Uint32(a), n)),
+        TensorElementDataType::Uint64 => cast_to(array).map(|(a, n)| (InputTensor::Uint64(a), n)),
+        TensorElementDataType::Bool => cast_to(array).map(|(a, n)| (InputTensor::Bool(a), n)),
+        _ => None,
+    }
+}
+
+fn cast_to<U, S, T, D>(array: &ArrayBase<S, D>) -> Option<(Array<U, IxDyn>, usize)>
+where
+    U: CastTarget,
+    S: ndarray::Data<Elem = T>,
+    T: TypeToTensorElementDataType,
+    D: ndarray::Dimension,
+{
+    let mut changed = 0;
+    let mut values = Vec::with_capacity(array.len());
+    for element in array.iter() {
+        let (value, exact) = U::from_number(element.try_number()?);
+        if !exact {
+            changed += 1;
+        }
+        values.push(value);
+    }
+    let array = Array::from_shape_vec(IxDyn(array.shape()), values).ok()?;
+    Some((array, changed))
+}
+
+/// An element type numbers can be cast to
+trait CastTarget: Sized {
+    /// The cast value, and whether it is exactly `number`
+    fn from_number(number: Number) -> (Self, bool);
+}
+
+macro_rules! impl_cast_target_int {
+    ($type_:ty) => {
+        impl CastTarget for $type_ {
+            fn from_number(number: Number) -> ($type_, bool) {
+                match number {
+                    Number::Int(i) => match <$type_>::try_from(i) {
+                        Ok(value) => (value, true),
+                        Err(_) if i < 0 => (<$type_>::MIN, false),
+                        Err(_) => (<$type_>::MAX, false),
+                    },
+                    // `as` saturates, and maps NaN to 0
+                    Number::Float(f) => (
+                        f as $type_,
+                        f.fract() == 0.0
+                            && f >= <$type_>::MIN as f64
+                            && f < <$type_>::MAX as f64 + 1.0,
+                    ),
+                }
+            }
+        }
+    };
+}
+
+impl_cast_target_int!(u8);
+impl_cast_target_int!(i8);
+impl_cast_target_int!(u16);
+impl_cast_target_int!(i16);
+impl_cast_target_int!(i32);
+impl_cast_target_int!(i64);
+impl_cast_target_int!(u32);
+impl_cast_target_int!(u64);
+
+impl CastTarget for f32 {
+    fn from_number(number: Number) -> (f32, bool) {
+        match number {
+            Number::Int(i) => (i as f32, i as f32 as i128 == i),
+            Number::Float(f) => (f as f32, f as f32 as f64 == f || f.is_nan()),
+        }
+    }
+}
+
+impl CastTarget for f64 {
+    fn from_number(number: Number) -> (f64, bool) {
+        match number {
+            Number::Int(i) => (i as f64, i as f64 as i128 == i),
+            Number::Float(f) => (f, true),
+        }
+    }
+}
+
+impl CastTarget for bool {
+    fn from_number(number: Number) -> (bool, bool) {
+        match number {
+            Number::Int(i) => (i != 0, i == 0 || i == 1),
+            Number::Float(f) => (f != 0.0, f == 0.0 || f == 1.0),
+        }
+    }
+}
+
+/// A value handed to ONNX Runtime, whatever its element type
+pub(crate) trait AsOrtValue: Debug {
+    fn ort_value(&self) -> *const sys::OrtValue;
+}
+
+impl<'t, T, D> AsOrtValue for OrtTensor<'t, T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone,
+    D: ndarray::Dimension,
+{
+    fn ort_value(&self) -> *const sys::OrtValue {
+        self.c_ptr as *const sys::OrtValue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{arr1, arr2};
+    use test_env_log::test;
+
+    #[test]
+    fn from_arrays() {
+        let tensor = InputTensor::from(arr2(&[[1_i64, 2, 3], [4, 5, 6]]));
+        assert_eq!(tensor.data_type(), TensorElementDataType::Int64);
+        assert_eq!(tensor.shape(), &[2, 3]);
+
+        let tensor = InputTensor::from(arr1(&[String::from("hello")]));
+        assert_eq!(tensor.data_type(), TensorElementDataType::String);
+        assert_eq!(tensor.shape(), &[1]);
+    }
+
+    #[test]
+    fn cast_arrays() {
+        let (tensor, changed) =
+            cast_array(&arr1(&[1.0_f64, 2.5]), TensorElementDataType::Float).unwrap();
+        assert_eq!(tensor, InputTensor::from(arr1(&[1.0_f32, 2.5])));
+        assert_eq!(changed, 0);
+
+        let (tensor, changed) =
+            cast_array(&arr1(&[0.1_f64]), TensorElementDataType::Float).unwrap();
+        assert_eq!(tensor, InputTensor::from(arr1(&[0.1_f32])));
+        assert_eq!(changed, 1);
+
+        let (tensor, changed) = cast_array(
+            &arr2(&[[1_i64, -1], [i64::MAX, i64::MIN]]),
+            TensorElementDataType::Int32,
+        )
+        .unwrap();
+        assert_eq!(
+            tensor,
+            InputTensor::from(arr2(&[[1_i32, -1], [i32::MAX, i32::MIN]]))
+        );
+        assert_eq!(changed, 2);
+
+        let (tensor, changed) =
+            cast_array(&arr1(&[-1.0_f32, 1.5]), TensorElementDataType::Uint8).unwrap();
+        assert_eq!(tensor, InputTensor::from(arr1(&[0_u8, 1])));
+        assert_eq!(changed, 2);
+
+        assert!(cast_array(&arr1(&[String::new()]), TensorElementDataType::Float).is_none());
+        assert!(cast_array(&arr1(&[1_i32]), TensorElementDataType::String).is_none());
+    }
+}
@@ -1,6 +1,16 @@
 //! Module containing a tensor trait extending [`ndarray::ArrayBase`](https://docs.rs/ndarray/latest/ndarray/struct.ArrayBase.html)
+//! and preprocessing helpers working on `ndarray` arrays
 
-use ndarray::{Array, ArrayBase};
+use std::cmp::Ordering;
+
+use ndarray::{
+    Array, Array2, Array4, ArrayBase, ArrayView, ArrayView1, Axis, Data, DataMut, Ix3, Ix4,
+};
+
+use crate::{
+    error::{NormalizationError, OrtError},
+    Result,
+};
 
 /// Trait extending [`ndarray::ArrayBase`](https://docs.rs/ndarray/latest/ndarray/struct.ArrayBase.html)
 /// with useful tensor operations.
@@ -31,6 +41,20 @@ pub trait NdArrayTensor<S, T, D> {
         S: ndarray::RawData + ndarray::Data + ndarray::RawData<Elem = T>,
         <S as ndarray::RawData>::Elem: std::clone::Clone,
         T: ndarray::NdFloat + std::ops::SubAssign + std::ops::DivAssign;
+
+    /// Find the index of the maximum value of the tensor along a given axis
+    ///
+    /// `NaN` values are never selected as the maximum (unless the whole lane is `NaN`, in which case
+    /// index `0` is returned).
+    ///
+    /// # Trait Bounds
+    ///
+    /// See [`softmax()`](#tymethod.softmax).
+    fn argmax(&self, axis: ndarray::Axis) -> Array<usize, D::Smaller>
+    where
+        D: ndarray::RemoveAxis,
+        S: ndarray::RawData + ndarray::Data + ndarray::RawData<Elem = T>,
+        T: ndarray::NdFloat;
 }
 
 impl<S, T, D> NdArrayTensor<S, T, D> for ArrayBase<S, D>
@@ -42,21 +66,265 @@ where
 {
     fn softmax(&self, axis: ndarray::Axis) -> Array<T, D> {
         let mut new_array: Array<T, D> = self.to_owned();
-        // FIXME: Change to non-overflowing formula
-        // e = np.exp(A - np.sum(A, axis=1, keepdims=True))
-        // np.exp(a) / np.sum(np.exp(a))
+        // Subtract the maximum before exponentiating so large values cannot overflow:
+        // np.exp(a - np.max(a)) / np.sum(np.exp(a - np.max(a)))
+        let max = new_array
+            .fold_axis(axis, T::neg_infinity(), |&acc, &v| acc.max(v))
+            .insert_axis(axis);
+        new_array -= &max;
         new_array.map_inplace(|v| *v = v.exp());
         let sum = new_array.sum_axis(axis).insert_axis(axis);
         new_array /= &sum;
 
         new_array
     }
+
+    fn argmax(&self, axis: ndarray::Axis) -> Array<usize, D::Smaller> {
+        self.map_axis(axis, |lane| {
+            lane.iter()
+                .enumerate()
+                .fold((0, T::neg_infinity()), |(max_idx, max), (idx, &v)| {
+                    if v > max {
+                        (idx, v)
+                    } else {
+                        (max_idx, max)
+                    }
+                })
+                .0
+        })
+    }
+}
+
+/// Find the `k` largest values of a 1-D tensor (for example a classifier's probabilities)
+///
+/// Returns `(index, value)` pairs sorted by descending value. Ties keep the order of their
+/// indices, and `NaN` values are sorted after all the others. If `k` is larger than the tensor,
+/// all its elements are returned.
+pub fn top_k(array: &ArrayView1<f32>, k: usize) -> Vec<(usize, f32)> {
+    let mut indexed: Vec<(usize, f32)> = array.iter().cloned().enumerate().collect();
+    // `sort_by` is stable: equal values stay sorted by index
+    indexed.sort_by(|(_, a), (_, b)| match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.partial_cmp(a).unwrap(),
+    });
+    indexed.truncate(k);
+    indexed
+}
+
+/// One-hot encode class indices, for example categorical features of a classic ML model
+///
+/// Row `i` of the `[indices.len(), num_classes]` tensor is all zeros except for a one in column
+/// `indices[i]`. It can be passed as is to [`Session::run()`](../../session/struct.Session.html#method.run).
+///
+/// # Errors
+///
+/// An error is returned if an index is not smaller than `num_classes`.
+pub fn one_hot(indices: &[usize], num_classes: usize) -> Result<Array2<f32>> {
+    if let Some(&index) = indices.iter().find(|&&index| index >= num_classes) {
+        return Err(OrtError::OneHotIndex { index, num_classes });
+    }
+
+    let mut array = Array2::zeros((indices.len(), num_classes));
+    for (mut row, &index) in array.outer_iter_mut().zip(indices) {
+        row[index] = 1.0;
+    }
+    Ok(array)
+}
+
+/// Normalize a `CHW` (channels, height, width) image tensor in place, per channel
+///
+/// Each value `x` of channel `c` becomes `(x - mean[c]) / std[c]`, the usual preprocessing of
+/// vision models (for example with ImageNet's mean and standard deviation).
+///
+/// # Errors
+///
+/// An error is returned if the tensor doesn't have 3 channels or if a standard deviation is zero;
+/// the tensor is then left untouched.
+pub fn normalize_mut<S>(array: &mut ArrayBase<S, Ix3>, mean: [f32; 3], std: [f32; 3]) -> Result<()>
+where
+    S: DataMut<Elem = f32>,
+{
+    normalize_along_mut(array, Axis(0), mean, std)
+}
+
+/// Normalize a `HWC` (height, width, channels) image tensor in place, per channel
+///
+/// See [`normalize_mut()`](fn.normalize_mut.html).
+pub fn normalize_hwc_mut<S>(
+    array: &mut ArrayBase<S, Ix3>,
+    mean: [f32; 3],
+    std: [f32; 3],
+) -> Result<()>
+where
+    S: DataMut<Elem = f32>,
+{
+    normalize_along_mut(array, Axis(2), mean, std)
+}
+
+fn normalize_along_mut<S>(
+    array: &mut ArrayBase<S, Ix3>,
+    channel_axis: Axis,
+    mean: [f32; 3],
+    std: [f32; 3],
+) -> Result<()>
+where
+    S: DataMut<Elem = f32>,
+{
+    let channels = array.len_of(channel_axis);
+    if channels != mean.len() {
+        return Err(OrtError::Normalization(NormalizationError::ChannelCount {
+            expected: mean.len(),
+            actual: channels,
+        }));
+    }
+    if let Some(channel) = std.iter().position(|&s| s == 0.0) {
+        return Err(OrtError::Normalization(
+            NormalizationError::ZeroStandardDeviation { channel },
+        ));
+    }
+
+    for ((mut lane, &mean), &std) in array
+        .axis_iter_mut(channel_axis)
+        .zip(mean.iter())
+        .zip(std.iter())
+    {
+        lane.map_inplace(|v| *v = (*v - mean) / std);
+    }
+
+    Ok(())
+}
+
+/// Convert a batch of images from the `NCHW` layout (as PyTorch exports expect) to `NHWC` (as
+/// TensorFlow models expect)
+///
+/// The result is copied into standard (C) layout, which ONNX Runtime requires.
+pub fn nchw_to_nhwc<S>(array: &ArrayBase<S, Ix4>) -> Array4<f32>
+where
+    S: Data<Elem = f32>,
+{
+    array
+        .view()
+        .permuted_axes([0, 2, 3, 1])
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// Convert a batch of images from the `NHWC` layout to `NCHW`
+///
+/// See [`nchw_to_nhwc()`](fn.nchw_to_nhwc.html).
+pub fn nhwc_to_nchw<S>(array: &ArrayBase<S, Ix4>) -> Array4<f32>
+where
+    S: Data<Elem = f32>,
+{
+    array
+        .view()
+        .permuted_axes([0, 3, 1, 2])
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// Whether two tensors are element-wise equal within a tolerance, like numpy's `allclose`
+///
+/// Elements `a` and `b` are close when `|a - b| <= atol + rtol * |b|`: `b` is the reference (for
+/// example the output of the original model, `a` being ONNX Runtime's). Infinite values are only
+/// close to themselves and `NaN` values are never close (see
+/// [`allclose_with_nan()`](fn.allclose_with_nan.html) to consider them equal).
+///
+/// Tensors of different shapes are never close.
+pub fn allclose<D>(a: &ArrayView<f32, D>, b: &ArrayView<f32, D>, rtol: f32, atol: f32) -> bool
+where
+    D: ndarray::Dimension,
+{
+    allclose_with_nan(a, b, rtol, atol, false)
+}
+
+/// Same as [`allclose()`](fn.allclose.html), with `NaN` values considered equal to each other if
+/// `equal_nan` is `true`
+pub fn allclose_with_nan<D>(
+    a: &ArrayView<f32, D>,
+    b: &ArrayView<f32, D>,
+    rtol: f32,
+    atol: f32,
+    equal_nan: bool,
+) -> bool
+where
+    D: ndarray::Dimension,
+{
+    a.shape() == b.shape()
+        && a.iter().zip(b.iter()).all(|(&a, &b)| {
+            if a.is_nan() || b.is_nan() {
+                equal_nan && a.is_nan() && b.is_nan()
+            } else {
+                a == b || (a - b).abs() <= atol + rtol * b.abs()
+            }
+        })
+}
+
+/// Largest absolute difference `|a - b|` between the elements of two tensors
+///
+/// Useful to report by how much tensors which are not [close](fn.allclose.html) differ. Elements
+/// which are both `NaN` are ignored; the difference is `NaN` if only one of them is.
+/// Returns `None` if the tensors have different shapes.
+pub fn max_abs_diff<D>(a: &ArrayView<f32, D>, b: &ArrayView<f32, D>) -> Option<f32>
+where
+    D: ndarray::Dimension,
+{
+    // Equal infinite values have no difference
+    max_diff(a, b, |a, b| if a == b { 0.0 } else { (a - b).abs() })
+}
+
+/// Largest relative difference `|a - b| / |b|` between the elements of two tensors, `b` being
+/// the reference
+///
+/// The difference is infinite where `b` is zero but `a` isn't. `NaN` values are handled as in
+/// [`max_abs_diff()`](fn.max_abs_diff.html). Returns `None` if the tensors have different shapes.
+pub fn max_rel_diff<D>(a: &ArrayView<f32, D>, b: &ArrayView<f32, D>) -> Option<f32>
+where
+    D: ndarray::Dimension,
+{
+    max_diff(
+        a,
+        b,
+        |a, b| {
+            if a == b {
+                0.0
+            } else {
+                (a - b).abs() / b.abs()
+            }
+        },
+    )
+}
+
+fn max_diff<D, F>(a: &ArrayView<f32, D>, b: &ArrayView<f32, D>, diff: F) -> Option<f32>
+where
+    D: ndarray::Dimension,
+    F: Fn(f32, f32) -> f32,
+{
+    if a.shape() != b.shape() {
+        return None;
+    }
+    let max = a
+        .iter()
+        .zip(b.iter())
+        .filter(|(a, b)| !(a.is_nan() && b.is_nan()))
+        .map(|(&a, &b)| diff(a, b))
+        // Once a `NaN` difference is found, it is kept
+        .fold(0.0_f32, |max, diff| {
+            if diff.is_nan() || diff > max {
+                diff
+            } else {
+                max
+            }
+        });
+    Some(max)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ndarray::{arr1, arr2, arr3};
+    use ndarray::{arr0, arr1, arr2, arr3, Array3};
     use test_env_log::test;
 
     #[test]
@@ -153,4 +421,232 @@ mod tests {
 
         assert!(diff.iter().all(|d| d.abs() < 1.0e-7));
     }
+
+    #[test]
+    fn softmax_sums_to_one() {
+        let array = arr2(&[[1.0_f32, 2.0, 3.0], [-4.0, 0.0, 7.5]]);
+
+        let softmax = array.softmax(ndarray::Axis(1));
+
+        let sums = softmax.sum_axis(ndarray::Axis(1));
+        assert!(sums.iter().all(|s| (s - 1.0).abs() < 1.0e-6));
+    }
+
+    #[test]
+    fn softmax_large_values() {
+        let array = arr1(&[1000.0_f32, 1000.0, 1000.0, 1000.0]);
+
+        let softmax = array.softmax(ndarray::Axis(0));
+
+        assert!(softmax.iter().all(|v| (v - 0.25).abs() < 1.0e-7));
+    }
+
+    #[test]
+    fn argmax_2d() {
+        let array = arr2(&[[0.1_f32, 0.7, 0.2], [0.9, 0.05, 0.05], [0.3, 0.3, 0.4]]);
+
+        assert_eq!(array.argmax(ndarray::Axis(1)), arr1(&[1, 0, 2]));
+        assert_eq!(array.argmax(ndarray::Axis(0)), arr1(&[1, 0, 2]));
+    }
+
+    #[test]
+    fn argmax_skips_nan() {
+        let array = arr1(&[0.1_f32, f32::NAN, 0.3, 0.2]);
+
+        assert_eq!(array.argmax(ndarray::Axis(0)), arr0(2));
+    }
+
+    #[test]
+    fn normalize_chw() {
+        // 3 channels of 2x2 pixels
+        let mut array = Array3::from_shape_fn((3, 2, 2), |(c, h, w)| (c * 4 + h * 2 + w) as f32);
+
+        normalize_mut(&mut array, [1.0, 5.0, 9.0], [1.0, 2.0, 4.0]).unwrap();
+
+        let expected = arr3(&[
+            [[-1.0_f32, 0.0], [1.0, 2.0]],
+            [[-0.5, 0.0], [0.5, 1.0]],
+            [[-0.25, 0.0], [0.25, 0.5]],
+        ]);
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn normalize_hwc() {
+        // 2x2 pixels of 3 channels
+        let mut array = arr3(&[
+            [[0.0_f32, 4.0, 8.0], [1.0, 5.0, 9.0]],
+            [[2.0, 6.0, 10.0], [3.0, 7.0, 11.0]],
+        ]);
+
+        normalize_hwc_mut(&mut array, [1.0, 5.0, 9.0], [1.0, 2.0, 4.0]).unwrap();
+
+        let expected = arr3(&[
+            [[-1.0_f32, -0.5, -0.25], [0.0, 0.0, 0.0]],
+            [[1.0, 0.5, 0.25], [2.0, 1.0, 0.5]],
+        ]);
+        assert_eq!(array, expected);
+    }
+
+    #[test]
+    fn normalize_zero_std() {
+        let mut array = Array3::<f32>::ones((3, 2, 2));
+
+        let result = normalize_mut(&mut array, [0.0; 3], [1.0, 0.0, 1.0]);
+
+        assert!(matches!(
+            result,
+            Err(OrtError::Normalization(
+                NormalizationError::ZeroStandardDeviation { channel: 1 }
+            ))
+        ));
+        assert_eq!(array, Array3::<f32>::ones((3, 2, 2)));
+    }
+
+    #[test]
+    fn normalize_wrong_channel_count() {
+        let mut array = Array3::<f32>::ones((2, 2, 2));
+
+        let result = normalize_mut(&mut array, [0.0; 3], [1.0; 3]);
+
+        assert!(matches!(
+            result,
+            Err(OrtError::Normalization(NormalizationError::ChannelCount {
+                expected: 3,
+                actual: 2
+            }))
+        ));
+    }
+
+    #[test]
+    fn top_k_sorted() {
+        let array = arr1(&[0.1_f32, 0.4, 0.2, 0.3]);
+
+        let top = top_k(&array.view(), 2);
+
+        assert_eq!(top, vec![(1, 0.4), (3, 0.3)]);
+    }
+
+    #[test]
+    fn top_k_ties() {
+        let array = arr1(&[0.25_f32, 0.5, 0.25, 0.5]);
+
+        let top = top_k(&array.view(), 3);
+
+        assert_eq!(top, vec![(1, 0.5), (3, 0.5), (0, 0.25)]);
+    }
+
+    #[test]
+    fn top_k_larger_than_array() {
+        let array = arr1(&[0.1_f32, f32::NAN, 0.7]);
+
+        let top = top_k(&array.view(), 10);
+
+        assert_eq!(top.len(), 3);
+        assert_eq!(&top[..2], &[(2, 0.7), (0, 0.1)]);
+        assert_eq!(top[2].0, 1);
+        assert!(top[2].1.is_nan());
+    }
+
+    #[test]
+    fn one_hot_rows() {
+        assert_eq!(
+            one_hot(&[0, 2, 1, 2], 3).unwrap(),
+            arr2(&[
+                [1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0]
+            ])
+        );
+        assert_eq!(one_hot(&[0], 1).unwrap(), arr2(&[[1.0]]));
+        assert_eq!(one_hot(&[], 4).unwrap().shape(), &[0, 4]);
+    }
+
+    #[test]
+    fn one_hot_out_of_range() {
+        assert!(matches!(
+            one_hot(&[0, 3, 1], 3),
+            Err(OrtError::OneHotIndex {
+                index: 3,
+                num_classes: 3
+            })
+        ));
+        assert!(matches!(
+            one_hot(&[0], 0),
+            Err(OrtError::OneHotIndex {
+                index: 0,
+                num_classes: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn nchw_nhwc_round_trip() {
+        // 1 image of 2 channels, 2 rows and 3 columns, each value encoding its (c, h, w) index
+        let nchw = Array::from_shape_fn((1, 2, 2, 3), |(_, c, h, w)| (100 * c + 10 * h + w) as f32);
+
+        let nhwc = nchw_to_nhwc(&nchw);
+        assert_eq!(nhwc.shape(), &[1, 2, 3, 2]);
+        assert!(nhwc.is_standard_layout());
+        assert_eq!(nhwc[[0, 1, 2, 0]], 12.0);
+        assert_eq!(nhwc[[0, 1, 2, 1]], 112.0);
+        assert_eq!(
+            nhwc.as_slice().unwrap(),
+            &[0.0, 100.0, 1.0, 101.0, 2.0, 102.0, 10.0, 110.0, 11.0, 111.0, 12.0, 112.0]
+        );
+
+        let back = nhwc_to_nchw(&nhwc);
+        assert_eq!(back.shape(), &[1, 2, 2, 3]);
+        assert!(back.is_standard_layout());
+        assert_eq!(back, nchw);
+    }
+
+    #[test]
+    fn allclose_equal() {
+        let a = arr2(&[[1.0_f32, -2.0], [f32::INFINITY, 0.0]]);
+
+        assert!(allclose(&a.view(), &a.view(), 0.0, 0.0));
+        assert_eq!(max_abs_diff(&a.view(), &a.view()), Some(0.0));
+        assert_eq!(max_rel_diff(&a.view(), &a.view()), Some(0.0));
+    }
+
+    #[test]
+    fn allclose_within_tolerance() {
+        let a = arr1(&[1.0_f32, 100.5, 0.0005]);
+        let b = arr1(&[1.0_f32, 100.0, 0.0]);
+
+        assert!(allclose(&a.view(), &b.view(), 0.01, 0.001));
+        // Neither tolerance alone is enough
+        assert!(!allclose(&a.view(), &b.view(), 0.01, 0.0));
+        assert!(!allclose(&a.view(), &b.view(), 0.0, 0.001));
+        assert_eq!(max_abs_diff(&a.view(), &b.view()), Some(0.5));
+        assert_eq!(max_rel_diff(&a.view(), &b.view()), Some(f32::INFINITY));
+    }
+
+    #[test]
+    fn allclose_out_of_tolerance() {
+        let a = arr1(&[1.0_f32, 2.5]);
+        let b = arr1(&[1.0_f32, 2.0]);
+
+        assert!(!allclose(&a.view(), &b.view(), 1e-5, 1e-8));
+        assert_eq!(max_abs_diff(&a.view(), &b.view()), Some(0.5));
+        assert_eq!(max_rel_diff(&a.view(), &b.view()), Some(0.25));
+    }
+
+    #[test]
+    fn allclose_nan_and_shapes() {
+        let a = arr1(&[1.0_f32, f32::NAN]);
+        let b = arr1(&[1.0_f32, f32::NAN]);
+
+        assert!(!allclose(&a.view(), &b.view(), 0.0, 0.0));
+        assert!(allclose_with_nan(&a.view(), &b.view(), 0.0, 0.0, true));
+        assert_eq!(max_abs_diff(&a.view(), &b.view()), Some(0.0));
+        let c = arr1(&[f32::NAN, 1.0]);
+        assert!(max_abs_diff(&a.view(), &c.view()).unwrap().is_nan());
+
+        let d = arr1(&[1.0_f32, 2.0, 3.0]);
+        assert!(!allclose(&a.view(), &d.view(), 1.0, 1.0));
+        assert_eq!(max_abs_diff(&a.view(), &d.view()), None);
+    }
 }
@@ -1,18 +1,24 @@
 //! Module containing tensor with memory owned by the ONNX Runtime
 
-use std::{fmt::Debug, ops::Deref, ptr, rc, result};
+use std::{
+    collections::HashMap, convert::TryFrom, fmt::Debug, hash::Hash, ops::Deref, ptr, result, sync,
+};
 
-use ndarray::ArrayView;
+use ndarray::{iter::AxisChunksIter, Array, ArrayView, CowArray, SliceArg};
 use thiserror::Error;
 use tracing::debug;
 
 use onnxruntime_sys as sys;
 
 use crate::{
-    error::call_ort,
+    error::{call_ort, Result},
     g_ort,
     memory::MemoryInfo,
-    tensor::{TensorData, TensorDataToType, TensorElementDataType},
+    session::{dyn_tensor_from_value, tensor_info_from_value},
+    tensor::{
+        check_shape, debug_summary, ndarray_tensor::NdArrayTensor, read_string_tensor, MapOutput,
+        SequenceOutput, TensorData, TensorDataToType, TensorElementDataType, ValueType,
+    },
     OrtError,
 };
 
@@ -31,6 +37,18 @@ pub enum TensorExtractError {
         /// The type corresponding to the attempted conversion into a Rust type, not equal to `actual`
         requested: TensorElementDataType,
     },
+    /// The user tried to extract the wrong kind of value (for example a tensor out of a sequence)
+    #[error(
+        "Value type mismatch: was {:?}, tried to extract {:?}",
+        actual,
+        requested
+    )]
+    ValueTypeMismatch {
+        /// The actual kind of the ort output
+        actual: ValueType,
+        /// The kind of value the extraction expected, not equal to `actual`
+        requested: ValueType,
+    },
     /// An onnxruntime error occurred
     #[error("Onnxruntime error: {:?}", 0)]
     OrtError(#[from] OrtError),
@@ -40,7 +58,14 @@ pub enum TensorExtractError {
 ///
 /// Since different outputs for the same model can have different types, this type is used to allow
 /// the user to dynamically query each output's type and extract the appropriate tensor type with
-/// [try_extract].
+/// [`try_extract()`](#method.try_extract).
+///
+/// Like the tensors extracted from it, a `DynOrtTensor` can be sent to and shared between threads.
+///
+/// Outputs which are sequences or maps (for example the probabilities produced by a classifier
+/// converted from scikit-learn) are extracted with
+/// [`try_extract_sequence()`](#method.try_extract_sequence) and
+/// [`try_extract_map()`](#method.try_extract_map) instead.
 #[derive(Debug)]
 pub struct DynOrtTensor<'m, D>
 where
@@ -48,11 +73,12 @@ where
 {
     // TODO could this also hold a Vec<u8> for strings so that the extracted tensor could then
     // hold a Vec<&str>?
-    tensor_ptr_holder: rc::Rc<TensorPointerHolder>,
+    tensor_ptr_holder: sync::Arc<TensorPointerHolder>,
     memory_info: &'m MemoryInfo,
     shape: D,
     tensor_element_len: usize,
     data_type: TensorElementDataType,
+    value_type: ValueType,
 }
 
 impl<'m, D> DynOrtTensor<'m, D>
@@ -65,29 +91,57 @@ where
         shape: D,
         tensor_element_len: usize,
         data_type: TensorElementDataType,
+        value_type: ValueType,
     ) -> DynOrtTensor<'m, D> {
         DynOrtTensor {
-            tensor_ptr_holder: rc::Rc::from(TensorPointerHolder { tensor_ptr }),
+            tensor_ptr_holder: sync::Arc::new(TensorPointerHolder { tensor_ptr }),
             memory_info,
             shape,
             tensor_element_len,
             data_type,
+            value_type,
         }
     }
 
     /// The ONNX data type this tensor contains.
+    ///
+    /// For sequences and maps, this is the type of the tensors they contain (the values' for maps).
     pub fn data_type(&self) -> TensorElementDataType {
         self.data_type
     }
 
+    /// The kind of value this is (a tensor, a sequence or a map), as reported by onnxruntime.
+    ///
+    /// Use it to pick the right extraction method: [`try_extract()`](#method.try_extract) for
+    /// tensors, [`try_extract_sequence()`](#method.try_extract_sequence) for sequences and
+    /// [`try_extract_map()`](#method.try_extract_map) for maps.
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    /// Description of the memory holding this value, telling whether it lives on CPU or on another
+    /// device
+    ///
+    /// **NOTE**: The ONNX Runtime version this crate is built against (1.6) has no
+    /// `GetTensorMemoryInfo()`; the memory info of the session which produced the value is returned
+    /// instead. This is exact since [`Session::run()`](../../session/struct.Session.html#method.run)
+    /// always returns its outputs in CPU memory.
+    pub fn memory_info(&self) -> &'m MemoryInfo {
+        self.memory_info
+    }
+
     /// Extract a tensor containing `T`.
     ///
     /// Where the type permits it, the tensor will be a view into existing memory.
     ///
     /// # Errors
     ///
-    /// An error will be returned if `T`'s ONNX type doesn't match this tensor's type, or if an
-    /// onnxruntime error occurs.
+    /// An error will be returned if this is not a tensor, if `T`'s ONNX type doesn't match this
+    /// tensor's type, or if an onnxruntime error occurs.
+    ///
+    /// [TensorExtractError] converts into [`OrtError`](../../error/enum.OrtError.html) (for example
+    /// with `?` in a function returning [`onnxruntime::Result`](../../error/type.Result.html)),
+    /// a mismatching type becoming `OrtError::DataTypeMismatch`.
     pub fn try_extract<'t, T>(&self) -> result::Result<OrtOwnedTensor<'t, T, D>, TensorExtractError>
     where
         T: TensorDataToType + Clone + Debug,
@@ -95,6 +149,8 @@ where
         D: 't,  // not clear why this is needed since we clone the shape, but it doesn't make
                 // a difference in practice since the shape is extracted from the tensor
     {
+        self.check_value_type(ValueType::Tensor)?;
+
         if self.data_type != T::tensor_element_data_type() {
             Err(TensorExtractError::DataTypeMismatch {
                 actual: self.data_type,
@@ -117,12 +173,274 @@ where
             let data = T::extract_data(
                 self.shape.clone(),
                 self.tensor_element_len,
-                rc::Rc::clone(&self.tensor_ptr_holder),
+                sync::Arc::clone(&self.tensor_ptr_holder),
             )?;
 
             Ok(OrtOwnedTensor { data })
         }
     }
+
+    /// Extract a string tensor as views into a single buffer.
+    ///
+    /// Extracting `String`s with [`try_extract()`](#method.try_extract) allocates each string
+    /// separately. Instead, the contents of the tensor are copied once into a contiguous buffer,
+    /// validated as UTF-8 as a whole, and
+    /// [`StringViews::view()`](struct.StringViews.html#method.view) borrows `&str`s from it. This
+    /// is much cheaper for large string outputs.
+    ///
+    /// # Errors
+    ///
+    /// An error will be returned if this is not a tensor of strings, if the strings are not valid
+    /// UTF-8, or if an onnxruntime error occurs.
+    pub fn try_extract_string_views(&self) -> result::Result<StringViews<D>, TensorExtractError> {
+        self.check_value_type(ValueType::Tensor)?;
+        if self.data_type != TensorElementDataType::String {
+            return Err(TensorExtractError::DataTypeMismatch {
+                actual: self.data_type,
+                requested: TensorElementDataType::String,
+            });
+        }
+
+        let (contents, offsets) =
+            read_string_tensor(&self.tensor_ptr_holder, self.tensor_element_len)?;
+        let buffer = String::from_utf8(contents).map_err(OrtError::StringFromUtf8Error)?;
+        // Each string is valid as long as it starts and ends on a character boundary
+        if let Some(w) = offsets
+            .windows(2)
+            .find(|w| !buffer.is_char_boundary(w[0]) || !buffer.is_char_boundary(w[1]))
+        {
+            // Slicing `buffer` itself would panic off a character boundary
+            let bytes = &buffer.as_bytes()[w[0]..w[1]];
+            let error = std::str::from_utf8(bytes)
+                .expect_err("String not on character boundaries must be invalid");
+            return Err(OrtError::StringUtf8Error(error).into());
+        }
+
+        check_shape(&self.shape, offsets.len() - 1)?;
+
+        Ok(StringViews {
+            buffer,
+            offsets,
+            shape: self.shape.clone(),
+        })
+    }
+
+    /// Extract a string tensor, handling strings which are not valid UTF-8 as `options` says.
+    ///
+    /// [`try_extract()`](#method.try_extract) fails on the first string which isn't valid UTF-8,
+    /// as can happen with models producing byte-level tokens. With
+    /// [`InvalidUtf8::Lossy`](enum.InvalidUtf8.html#variant.Lossy) invalid sequences are replaced
+    /// with `U+FFFD` instead, and with [`InvalidUtf8::Bytes`](enum.InvalidUtf8.html#variant.Bytes)
+    /// the raw bytes of every string are returned, without any validation.
+    ///
+    /// # Errors
+    ///
+    /// An error will be returned if this is not a tensor of strings, if a string is not valid UTF-8
+    /// with [`InvalidUtf8::Error`](enum.InvalidUtf8.html#variant.Error), or if an onnxruntime error
+    /// occurs.
+    pub fn try_extract_strings_with(
+        &self,
+        options: StringExtractOptions,
+    ) -> result::Result<ExtractedStrings<D>, TensorExtractError> {
+        self.check_value_type(ValueType::Tensor)?;
+        if self.data_type != TensorElementDataType::String {
+            return Err(TensorExtractError::DataTypeMismatch {
+                actual: self.data_type,
+                requested: TensorElementDataType::String,
+            });
+        }
+
+        let (contents, offsets) =
+            read_string_tensor(&self.tensor_ptr_holder, self.tensor_element_len)?;
+        check_shape(&self.shape, offsets.len() - 1)?;
+        let elements = offsets.windows(2).map(|w| &contents[w[0]..w[1]]);
+
+        let extracted = match options.invalid_utf8 {
+            InvalidUtf8::Error => ExtractedStrings::Strings(
+                elements
+                    .map(|bytes| String::from_utf8(bytes.to_vec()))
+                    .collect::<result::Result<Vec<_>, _>>()
+                    .map_err(OrtError::StringFromUtf8Error)
+                    .map(|strings| self.to_array(strings))?,
+            ),
+            InvalidUtf8::Lossy => ExtractedStrings::Strings(
+                self.to_array(
+                    elements
+                        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                        .collect(),
+                ),
+            ),
+            InvalidUtf8::Bytes => {
+                ExtractedStrings::Bytes(self.to_array(elements.map(<[u8]>::to_vec).collect()))
+            }
+        };
+        Ok(extracted)
+    }
+
+    /// Shape the elements of this tensor, their number having been checked
+    fn to_array<T>(&self, elements: Vec<T>) -> Array<T, D> {
+        Array::from_shape_vec(self.shape.clone(), elements)
+            .expect("Shape was checked against the number of elements")
+    }
+
+    /// Extract the elements of a sequence.
+    ///
+    /// Each element is itself a `DynOrtTensor` (holding either a tensor or a map), copied out of
+    /// the sequence by onnxruntime.
+    ///
+    /// # Errors
+    ///
+    /// An error will be returned if this is not a sequence, or if an onnxruntime error occurs.
+    pub fn try_extract_sequence(&self) -> result::Result<SequenceOutput<'m>, TensorExtractError> {
+        self.check_value_type(ValueType::Sequence)?;
+
+        let elements = (0..self.tensor_element_len)
+            .map(|i| self.get_value(i, self.data_type))
+            .collect::<crate::Result<Vec<_>>>()?;
+
+        Ok(SequenceOutput::new(elements))
+    }
+
+    /// Extract the entries of a map, copying them into a `HashMap`.
+    ///
+    /// `K` is the type of the keys (`i64` or `String` in practice) and `V` the type of the values.
+    ///
+    /// # Errors
+    ///
+    /// An error will be returned if this is not a map, if `K` or `V` don't match the ONNX types of
+    /// the keys or values, or if an onnxruntime error occurs.
+    pub fn try_extract_map<K, V>(&self) -> result::Result<MapOutput<K, V>, TensorExtractError>
+    where
+        K: TensorDataToType + Eq + Hash,
+        V: TensorDataToType,
+    {
+        self.check_value_type(ValueType::Map)?;
+
+        // A map is made of two tensors: its keys and its values.
+        let keys = self.get_value(0, K::tensor_element_data_type())?;
+        let values = self.get_value(1, self.data_type)?;
+
+        let keys = keys.try_extract::<K>()?;
+        let values = values.try_extract::<V>()?;
+        let map: HashMap<K, V> = keys
+            .view()
+            .iter()
+            .cloned()
+            .zip(values.view().iter().cloned())
+            .collect();
+
+        Ok(MapOutput::new(map))
+    }
+
+    fn check_value_type(&self, requested: ValueType) -> result::Result<(), TensorExtractError> {
+        if self.value_type != requested {
+            Err(TensorExtractError::ValueTypeMismatch {
+                actual: self.value_type,
+                requested,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get the element `index` of a sequence or map, as a new value owned by the caller
+    fn get_value(
+        &self,
+        index: usize,
+        element_type: TensorElementDataType,
+    ) -> crate::Result<DynOrtTensor<'m, ndarray::IxDyn>> {
+        // The default allocator lives for the whole process, so the element is not tied to the
+        // session's allocator.
+        let mut allocator_ptr: *mut sys::OrtAllocator = ptr::null_mut();
+        unsafe { call_ort(|ort| ort.GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr)) }
+            .map_err(OrtError::Allocator)?;
+        assert_ne!(allocator_ptr, ptr::null_mut());
+
+        let mut value_ptr: *mut sys::OrtValue = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetValue.unwrap()(
+                    self.tensor_ptr_holder.tensor_ptr,
+                    index as i32,
+                    allocator_ptr,
+                    &mut value_ptr,
+                )
+            })
+        }
+        .map_err(OrtError::GetValue)?;
+        assert_ne!(value_ptr, ptr::null_mut());
+
+        dyn_tensor_from_value(value_ptr, self.memory_info, element_type)
+    }
+}
+
+/// What to do with strings which are not valid UTF-8, see
+/// [`DynOrtTensor::try_extract_strings_with()`](struct.DynOrtTensor.html#method.try_extract_strings_with)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidUtf8 {
+    /// Fail the whole extraction
+    #[default]
+    Error,
+    /// Replace invalid sequences with `U+FFFD REPLACEMENT CHARACTER`
+    Lossy,
+    /// Return the raw bytes of every string, valid UTF-8 or not
+    Bytes,
+}
+
+/// Options of [`DynOrtTensor::try_extract_strings_with()`](struct.DynOrtTensor.html#method.try_extract_strings_with)
+///
+/// The default options fail on invalid UTF-8, like [`DynOrtTensor::try_extract()`](struct.DynOrtTensor.html#method.try_extract).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StringExtractOptions {
+    /// What to do with strings which are not valid UTF-8
+    pub invalid_utf8: InvalidUtf8,
+}
+
+/// Strings extracted by [`DynOrtTensor::try_extract_strings_with()`](struct.DynOrtTensor.html#method.try_extract_strings_with)
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtractedStrings<D>
+where
+    D: ndarray::Dimension,
+{
+    /// Strings, valid or made valid UTF-8
+    Strings(Array<String, D>),
+    /// Raw bytes of each string, with [`InvalidUtf8::Bytes`](enum.InvalidUtf8.html#variant.Bytes)
+    Bytes(Array<Vec<u8>, D>),
+}
+
+/// String tensor produced by onnxruntime inference, held in a single buffer.
+///
+/// Obtained through [`DynOrtTensor::try_extract_string_views()`](struct.DynOrtTensor.html#method.try_extract_string_views).
+#[derive(Debug)]
+pub struct StringViews<D>
+where
+    D: ndarray::Dimension,
+{
+    buffer: String,
+    // One more offset than strings: the last one is the buffer's length
+    offsets: Vec<usize>,
+    shape: D,
+}
+
+impl<D> StringViews<D>
+where
+    D: ndarray::Dimension,
+{
+    /// Produce an array of the strings, borrowed from the buffer
+    pub fn view(&self) -> Array<&str, D> {
+        let strings: Vec<&str> = self
+            .offsets
+            .windows(2)
+            .map(|w| &self.buffer[w[0]..w[1]])
+            .collect();
+        Array::from_shape_vec(self.shape.clone(), strings)
+            .expect("Shape was checked against the number of strings when extracting the views")
+    }
+
+    /// The concatenated contents of all the strings
+    pub fn buffer(&self) -> &str {
+        &self.buffer
+    }
 }
 
 /// Tensor containing data owned by the ONNX Runtime C library, used to return values from inference.
@@ -144,6 +462,56 @@ where
     data: TensorData<'t, T, D>,
 }
 
+impl<'t, T> OrtOwnedTensor<'t, T, ndarray::IxDyn>
+where
+    T: TensorDataToType,
+{
+    /// Wrap a tensor `OrtValue` produced by other ONNX Runtime C API code
+    ///
+    /// # Safety
+    ///
+    /// `value_ptr` must be a valid, non-null `OrtValue` holding a tensor, which the caller owns.
+    /// Ownership is transferred to the returned `OrtOwnedTensor`, which releases the value when
+    /// dropped: the caller must not use nor release it afterwards. The value is released on error
+    /// too.
+    ///
+    /// # Errors
+    ///
+    /// An error will be returned if the value is not a tensor, if `T`'s ONNX type doesn't match
+    /// the tensor's type, or if an onnxruntime error occurs.
+    pub unsafe fn from_raw(
+        value_ptr: *mut sys::OrtValue,
+    ) -> result::Result<OrtOwnedTensor<'t, T, ndarray::IxDyn>, TensorExtractError> {
+        assert_ne!(value_ptr, ptr::null_mut());
+        // Take ownership right away so that the value is released on error
+        let tensor_ptr_holder = sync::Arc::new(TensorPointerHolder {
+            tensor_ptr: value_ptr,
+        });
+
+        let mut onnx_type = sys::ONNXType::ONNX_TYPE_UNKNOWN;
+        call_ort(|ort| ort.GetValueType.unwrap()(value_ptr, &mut onnx_type))
+            .map_err(OrtError::GetValueType)?;
+        let value_type = ValueType::from(onnx_type);
+        if value_type != ValueType::Tensor {
+            return Err(TensorExtractError::ValueTypeMismatch {
+                actual: value_type,
+                requested: ValueType::Tensor,
+            });
+        }
+
+        let (dims, data_type, len) = tensor_info_from_value(value_ptr)?;
+        if data_type != T::tensor_element_data_type() {
+            return Err(TensorExtractError::DataTypeMismatch {
+                actual: data_type,
+                requested: T::tensor_element_data_type(),
+            });
+        }
+
+        let data = T::extract_data(ndarray::IxDyn(&dims), len, tensor_ptr_holder)?;
+        Ok(OrtOwnedTensor { data })
+    }
+}
+
 impl<'t, T, D> OrtOwnedTensor<'t, T, D>
 where
     T: TensorDataToType,
@@ -156,6 +524,252 @@ where
     {
         ViewHolder::new(&self.data)
     }
+
+    /// The tensor's data as a [`CowArray`](https://docs.rs/ndarray/latest/ndarray/type.CowArray.html),
+    /// whatever its element type
+    ///
+    /// Primitive elements are read in place from ONNX Runtime's memory: the array is a
+    /// `CowArray::View` of the tensor. Strings (and booleans) are copied out of ONNX Runtime when
+    /// extracted: the array is a `CowArray::Owned` copy of them. Generic code can thus handle
+    /// both representations with a single type.
+    pub fn as_array_cow<'s>(&'s self) -> CowArray<'s, T, D>
+    where
+        't: 's,
+        T: Clone,
+    {
+        match &self.data {
+            TensorData::TensorPtr { array_view, .. } => CowArray::from(array_view.view()),
+            TensorData::Strings { strings } => CowArray::from(strings.clone()),
+            TensorData::Copied { array } => CowArray::from(array.clone()),
+        }
+    }
+
+    /// A view of a region of the tensor, selected with ndarray's
+    /// [`s![]`](https://docs.rs/ndarray/latest/ndarray/macro.s.html) macro
+    ///
+    /// The view borrows the tensor's memory: cropping a large output (e.g. a segmentation mask)
+    /// doesn't copy it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region is out of bounds, or if `info` doesn't match the number of dimensions
+    /// of the tensor, like [`ArrayBase::slice()`](https://docs.rs/ndarray/latest/ndarray/struct.ArrayBase.html#method.slice).
+    pub fn slice<'s, I>(&'s self, info: I) -> ArrayView<'s, T, I::OutDim>
+    where
+        't: 's,
+        I: SliceArg<D>,
+    {
+        self.view().array_view.slice_move(info)
+    }
+
+    /// Iterate over successive chunks of `chunk_size` elements along `axis` (the last chunk may be
+    /// smaller), without copying them
+    ///
+    /// The chunks are views of ONNX Runtime's buffer, which the borrow of the tensor keeps alive:
+    /// a very large output can be processed piece by piece instead of being copied as a whole.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds or if `chunk_size` is zero, like
+    /// [`ArrayBase::axis_chunks_iter()`](https://docs.rs/ndarray/latest/ndarray/struct.ArrayBase.html#method.axis_chunks_iter).
+    pub fn chunks_along<'s>(
+        &'s self,
+        axis: ndarray::Axis,
+        chunk_size: usize,
+    ) -> AxisChunksIter<'s, T, D>
+    where
+        't: 's,
+    {
+        match &self.data {
+            TensorData::TensorPtr { array_view, .. } => {
+                array_view.axis_chunks_iter(axis, chunk_size)
+            }
+            TensorData::Strings { strings } => strings.axis_chunks_iter(axis, chunk_size),
+            TensorData::Copied { array } => array.axis_chunks_iter(axis, chunk_size),
+        }
+    }
+
+    /// Whether the elements are laid out contiguously in standard (C, row-major) order
+    ///
+    /// This is always the case of the tensors ONNX Runtime produces.
+    pub fn is_standard_layout(&self) -> bool {
+        self.view().is_standard_layout()
+    }
+
+    /// Strides of the tensor, in number of elements, for each axis
+    pub fn strides(&self) -> Vec<isize> {
+        self.view().strides().to_vec()
+    }
+
+    /// The elements as a slice, in standard (C) order
+    ///
+    /// Returns `None` if the tensor is not in [standard layout](#method.is_standard_layout).
+    pub fn as_slice(&self) -> Option<&[T]> {
+        match &self.data {
+            TensorData::TensorPtr { array_view, .. } => array_view.as_slice(),
+            TensorData::Strings { strings } => strings.as_slice(),
+            TensorData::Copied { array } => array.as_slice(),
+        }
+    }
+
+    /// The single value of a tensor holding exactly one element (e.g. a score), whatever its
+    /// number of dimensions
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the tensor doesn't have exactly one element.
+    pub fn into_scalar(self) -> Result<T>
+    where
+        T: Clone,
+    {
+        let view = self.view();
+        match view.as_slice() {
+            Some([value]) => Ok(value.clone()),
+            _ => Err(OrtError::NotScalar {
+                shape: view.shape().to_vec(),
+            }),
+        }
+    }
+
+    /// Apply a softmax on the specified axis
+    pub fn softmax(&self, axis: ndarray::Axis) -> Array<T, D>
+    where
+        D: ndarray::RemoveAxis,
+        T: ndarray::NdFloat + std::ops::SubAssign + std::ops::DivAssign,
+    {
+        self.view().softmax(axis)
+    }
+
+    /// Find the index of the maximum value along the specified axis
+    pub fn argmax(&self, axis: ndarray::Axis) -> Array<usize, D::Smaller>
+    where
+        D: ndarray::RemoveAxis,
+        T: ndarray::NdFloat,
+    {
+        self.view().argmax(axis)
+    }
+
+    /// Mean of all the elements of the tensor, or `None` if it is empty
+    ///
+    /// Enabled with the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn mean(&self) -> Option<f64>
+    where
+        T: Copy + Into<f64>,
+    {
+        crate::tensor::stats::mean(self.view().view())
+    }
+
+    /// Population standard deviation of all the elements of the tensor, or `None` if it is empty
+    ///
+    /// Enabled with the `stats` feature.
+    #[cfg(feature = "stats")]
+    pub fn std(&self) -> Option<f64>
+    where
+        T: Copy + Into<f64>,
+    {
+        crate::tensor::stats::std(self.view().view())
+    }
+
+    /// The `q`-th percentile (between 0 and 100) of all the elements of the tensor, or `None` if
+    /// it is empty
+    ///
+    /// Values are linearly interpolated between the closest elements, and NaNs are ignored.
+    ///
+    /// Enabled with the `stats` feature.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `q` is not between 0 and 100.
+    #[cfg(feature = "stats")]
+    pub fn percentile(&self, q: f64) -> Result<Option<f64>>
+    where
+        T: Copy + Into<f64>,
+    {
+        crate::tensor::stats::percentile(self.view().view(), q)
+    }
+
+    /// Describe the tensor in one line, for debugging: its element type, its shape and its first
+    /// and last elements (e.g. `Float tensor of shape [2, 4]: [1.0, 2.0, 3.0, ..., 8.0]`)
+    pub fn debug_summary(&self) -> String {
+        debug_summary(T::tensor_element_data_type(), self.view().view())
+    }
+
+    /// Write the tensor to `writer` in numpy's [`.npy` format](https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html),
+    /// for example to inspect it with `numpy.load()`
+    ///
+    /// The header records the shape and the element type of the tensor (e.g. `<f4` for `f32`).
+    /// All the numeric element types, and booleans, can be written.
+    ///
+    /// Enabled with the `npy` feature.
+    #[cfg(feature = "npy")]
+    pub fn write_npy<W>(&self, writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ndarray_npy::WritableElement,
+    {
+        use ndarray_npy::WriteNpyExt;
+
+        self.view().write_npy(writer).map_err(OrtError::NpyWrite)
+    }
+
+    /// Copy the tensor's elements, in standard (C) order, into `dst`
+    ///
+    /// This avoids allocating an intermediate `Array` when the results go into a buffer owned by
+    /// the caller (for example reused across runs).
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the length of `dst` is not the number of elements of the tensor.
+    pub fn copy_into_slice(&self, dst: &mut [T]) -> Result<()>
+    where
+        T: Clone,
+    {
+        let view = self.view();
+        if dst.len() != view.len() {
+            return Err(OrtError::OutputBufferSizeMismatch {
+                expected: view.len(),
+                actual: dst.len(),
+            });
+        }
+
+        match view.as_slice() {
+            // ONNX Runtime tensors are contiguous, so this is a single copy
+            Some(src) => dst.clone_from_slice(src),
+            None => dst
+                .iter_mut()
+                .zip(view.iter())
+                .for_each(|(dst, src)| *dst = src.clone()),
+        }
+        Ok(())
+    }
+}
+
+impl<'t, D> OrtOwnedTensor<'t, i64, D>
+where
+    D: ndarray::Dimension + 't,
+{
+    /// Map class indices (e.g. a classifier's output) to their labels
+    ///
+    /// Each element is an index into `labels`; the labels are returned in standard (C) order.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if an index is negative or not smaller than `labels.len()`.
+    pub fn to_labels<'l>(&self, labels: &[&'l str]) -> Result<Vec<&'l str>> {
+        self.view()
+            .iter()
+            .map(|&index| {
+                usize::try_from(index)
+                    .ok()
+                    .and_then(|i| labels.get(i).copied())
+                    .ok_or(OrtError::LabelIndex {
+                        index,
+                        num_labels: labels.len(),
+                    })
+            })
+            .collect()
+    }
 }
 
 /// An intermediate step on the way to an ArrayView.
@@ -191,6 +805,9 @@ where
                 // this separate struct to hold the view that refers to the `Array`.
                 array_view: strings.view(),
             },
+            TensorData::Copied { array } => ViewHolder {
+                array_view: array.view(),
+            },
         }
     }
 }
@@ -218,6 +835,11 @@ pub struct TensorPointerHolder {
     pub(crate) tensor_ptr: *mut sys::OrtValue,
 }
 
+// The value is only read once created, which ONNX Runtime allows from any thread, and it is
+// released exactly once, when the last holder is dropped.
+unsafe impl Send for TensorPointerHolder {}
+unsafe impl Sync for TensorPointerHolder {}
+
 impl Drop for TensorPointerHolder {
     #[tracing::instrument]
     fn drop(&mut self) {
@@ -227,3 +849,207 @@ impl Drop for TensorPointerHolder {
         self.tensor_ptr = ptr::null_mut();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor::tests::create_value, AllocatorType, MemType};
+    use std::ffi;
+    use test_env_log::test;
+
+    /// A string tensor holding `"ok"` and the invalid UTF-8 `[0x66, 0xff, 0x6f]`
+    fn invalid_utf8_tensor(memory_info: &MemoryInfo) -> DynOrtTensor<'_, ndarray::IxDyn> {
+        let value_ptr = string_tensor_value(&[2], &[b"ok", &[0x66, 0xff, 0x6f]]);
+        dyn_tensor_from_value(value_ptr, memory_info, TensorElementDataType::String).unwrap()
+    }
+
+    fn options(invalid_utf8: InvalidUtf8) -> StringExtractOptions {
+        StringExtractOptions { invalid_utf8 }
+    }
+
+    #[test]
+    fn invalid_utf8_error() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let tensor = invalid_utf8_tensor(&memory_info);
+
+        assert_eq!(StringExtractOptions::default(), options(InvalidUtf8::Error));
+        assert!(matches!(
+            tensor.try_extract_strings_with(options(InvalidUtf8::Error)),
+            Err(TensorExtractError::OrtError(OrtError::StringFromUtf8Error(
+                _
+            )))
+        ));
+        assert!(tensor.try_extract::<String>().is_err());
+    }
+
+    #[test]
+    fn invalid_utf8_lossy() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let tensor = invalid_utf8_tensor(&memory_info);
+
+        match tensor
+            .try_extract_strings_with(options(InvalidUtf8::Lossy))
+            .unwrap()
+        {
+            ExtractedStrings::Strings(strings) => {
+                assert_eq!(strings.as_slice().unwrap(), &["ok", "f\u{fffd}o"])
+            }
+            other => panic!("Expected strings, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_utf8_bytes() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let tensor = invalid_utf8_tensor(&memory_info);
+
+        match tensor
+            .try_extract_strings_with(options(InvalidUtf8::Bytes))
+            .unwrap()
+        {
+            ExtractedStrings::Bytes(bytes) => assert_eq!(
+                bytes.as_slice().unwrap(),
+                &[b"ok".to_vec(), vec![0x66, 0xff, 0x6f]]
+            ),
+            other => panic!("Expected bytes, got {:?}", other),
+        }
+    }
+
+    /// A tensor value created through the C API, holding `values`
+    fn tensor_value<T>(shape: &[i64], values: &[T]) -> *mut sys::OrtValue
+    where
+        T: TensorDataToType + Copy,
+    {
+        let value_ptr = create_value(shape, T::tensor_element_data_type());
+        let mut data_ptr: *mut T = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    value_ptr,
+                    &mut data_ptr as *mut *mut T as *mut *mut ffi::c_void,
+                )
+            })
+        }
+        .unwrap();
+        unsafe { std::slice::from_raw_parts_mut(data_ptr, values.len()) }.copy_from_slice(values);
+        value_ptr
+    }
+
+    /// A string tensor value created through the C API, holding `strings` (which need not be valid
+    /// UTF-8)
+    fn string_tensor_value(shape: &[i64], strings: &[&[u8]]) -> *mut sys::OrtValue {
+        let value_ptr = create_value(shape, TensorElementDataType::String);
+        let strings: Vec<ffi::CString> = strings
+            .iter()
+            .map(|s| ffi::CString::new(*s).unwrap())
+            .collect();
+        let string_ptrs: Vec<*const std::os::raw::c_char> =
+            strings.iter().map(|s| s.as_ptr()).collect();
+        unsafe {
+            call_ort(|ort| {
+                ort.FillStringTensor.unwrap()(
+                    value_ptr,
+                    string_ptrs.as_ptr(),
+                    string_ptrs.len() as u64,
+                )
+            })
+        }
+        .unwrap();
+        value_ptr
+    }
+
+    /// A tensor created through the C API, holding `values`
+    fn owned_tensor<T>(shape: &[i64], values: &[T]) -> OrtOwnedTensor<'static, T, ndarray::IxDyn>
+    where
+        T: TensorDataToType + Copy,
+    {
+        unsafe { OrtOwnedTensor::<T, _>::from_raw(tensor_value(shape, values)) }.unwrap()
+    }
+
+    #[test]
+    fn to_labels() {
+        let labels = ["cat", "dog", "bird"];
+
+        let tensor = owned_tensor(&[4], &[2_i64, 0, 0, 1]);
+        assert_eq!(
+            tensor.to_labels(&labels).unwrap(),
+            vec!["bird", "cat", "cat", "dog"]
+        );
+
+        for &index in &[3_i64, -1] {
+            let tensor = owned_tensor(&[2], &[0, index]);
+            assert!(matches!(
+                tensor.to_labels(&labels),
+                Err(OrtError::LabelIndex { index: i, num_labels: 3 }) if i == index
+            ));
+        }
+    }
+
+    #[cfg(feature = "npy")]
+    #[test]
+    fn write_npy() {
+        use ndarray_npy::ReadNpyExt;
+
+        let values = [1.5_f32, -2.0, 0.25, 4.0, 5.0, 6.0];
+        let tensor = owned_tensor(&[2, 3], &values);
+        let mut npy = Vec::new();
+        tensor.write_npy(&mut npy).unwrap();
+
+        let array = ndarray::Array2::<f32>::read_npy(npy.as_slice()).unwrap();
+        assert_eq!(
+            array,
+            ndarray::arr2(&[[1.5_f32, -2.0, 0.25], [4.0, 5.0, 6.0]])
+        );
+    }
+
+    #[test]
+    fn chunks_along() {
+        let values: Vec<f32> = (0..10).map(|i| i as f32).collect();
+        let tensor = owned_tensor(&[5, 2], &values);
+
+        let chunks: Vec<_> = tensor.chunks_along(ndarray::Axis(0), 2).collect();
+        assert_eq!(
+            chunks.iter().map(|chunk| chunk.shape()).collect::<Vec<_>>(),
+            vec![&[2, 2], &[2, 2], &[1, 2]]
+        );
+        assert_eq!(chunks[0].as_slice(), Some(&values[0..4]));
+        assert_eq!(chunks[2].as_slice(), Some(&values[8..10]));
+        // The chunks are views of ONNX Runtime's buffer
+        assert_eq!(chunks[1].as_ptr(), tensor.as_slice().unwrap()[4..].as_ptr());
+
+        let columns: Vec<_> = tensor.chunks_along(ndarray::Axis(1), 1).collect();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(
+            columns[1].iter().cloned().collect::<Vec<_>>(),
+            vec![1.0, 3.0, 5.0, 7.0, 9.0]
+        );
+    }
+
+    #[test]
+    fn from_raw_round_trip() {
+        let tensor = owned_tensor(&[2, 2], &[1.0_f32, 2.0, 3.0, 4.0]);
+        assert_eq!(tensor.view().shape(), &[2, 2]);
+        assert_eq!(
+            tensor.view().iter().cloned().collect::<Vec<_>>(),
+            vec![1.0, 2.0, 3.0, 4.0]
+        );
+
+        assert_eq!(
+            tensor.debug_summary(),
+            "Float tensor of shape [2, 2]: [1.0, 2.0, 3.0, 4.0]"
+        );
+
+        let mut buffer = vec![0.0_f32; 4];
+        tensor.copy_into_slice(&mut buffer).unwrap();
+        assert_eq!(buffer, vec![1.0, 2.0, 3.0, 4.0]);
+
+        let mut too_small = vec![0.0_f32; 3];
+        assert!(matches!(
+            tensor.copy_into_slice(&mut too_small),
+            Err(OrtError::OutputBufferSizeMismatch {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+}
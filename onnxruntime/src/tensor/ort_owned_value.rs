@@ -0,0 +1,129 @@
+//! Module containing sequence and map values produced by the ONNX Runtime
+//!
+//! Classifiers converted from other frameworks (for example scikit-learn's, through `ZipMap`)
+//! commonly output their probabilities as a sequence of maps from class label to probability.
+
+use std::{collections::HashMap, ops::Deref, result, slice, vec};
+
+use ndarray::IxDyn;
+
+use crate::tensor::{
+    ort_owned_tensor::{DynOrtTensor, TensorExtractError},
+    TensorDataToType,
+};
+
+/// A sequence produced by onnxruntime inference.
+///
+/// Obtained through [`DynOrtTensor::try_extract_sequence()`](../ort_owned_tensor/struct.DynOrtTensor.html#method.try_extract_sequence).
+/// Each element is a [`DynOrtTensor`](../ort_owned_tensor/struct.DynOrtTensor.html) which can in turn be
+/// extracted as a tensor or a map.
+#[derive(Debug)]
+pub struct SequenceOutput<'m> {
+    elements: Vec<DynOrtTensor<'m, IxDyn>>,
+}
+
+impl<'m> SequenceOutput<'m> {
+    pub(crate) fn new(elements: Vec<DynOrtTensor<'m, IxDyn>>) -> SequenceOutput<'m> {
+        SequenceOutput { elements }
+    }
+
+    /// Number of elements in the sequence
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Whether the sequence is empty
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Get the element at `index`, if any
+    pub fn get(&self, index: usize) -> Option<&DynOrtTensor<'m, IxDyn>> {
+        self.elements.get(index)
+    }
+
+    /// Iterate over the elements of the sequence
+    pub fn iter(&self) -> slice::Iter<'_, DynOrtTensor<'m, IxDyn>> {
+        self.elements.iter()
+    }
+
+    /// Take the elements of the sequence, each fetched with `GetValue()`
+    ///
+    /// Elements don't need to have the same shape: a sequence can hold tensors of variable
+    /// lengths, each one extracted on its own with
+    /// [`DynOrtTensor::try_extract()`](../ort_owned_tensor/struct.DynOrtTensor.html#method.try_extract).
+    pub fn into_tensors(self) -> Vec<DynOrtTensor<'m, IxDyn>> {
+        self.elements
+    }
+
+    /// Extract every element of a sequence of maps
+    ///
+    /// See [`DynOrtTensor::try_extract_map()`](../ort_owned_tensor/struct.DynOrtTensor.html#method.try_extract_map).
+    pub fn try_extract_maps<K, V>(&self) -> result::Result<Vec<MapOutput<K, V>>, TensorExtractError>
+    where
+        K: TensorDataToType + Eq + std::hash::Hash,
+        V: TensorDataToType,
+    {
+        self.elements
+            .iter()
+            .map(|element| element.try_extract_map::<K, V>())
+            .collect()
+    }
+}
+
+impl<'a, 'm> IntoIterator for &'a SequenceOutput<'m> {
+    type Item = &'a DynOrtTensor<'m, IxDyn>;
+    type IntoIter = slice::Iter<'a, DynOrtTensor<'m, IxDyn>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'m> IntoIterator for SequenceOutput<'m> {
+    type Item = DynOrtTensor<'m, IxDyn>;
+    type IntoIter = vec::IntoIter<DynOrtTensor<'m, IxDyn>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+/// A map produced by onnxruntime inference, copied into a `HashMap`.
+///
+/// Obtained through [`DynOrtTensor::try_extract_map()`](../ort_owned_tensor/struct.DynOrtTensor.html#method.try_extract_map).
+///
+/// `MapOutput` implements the [`std::deref::Deref`](#impl-Deref) trait for ergonomic access to
+/// the underlying `HashMap`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MapOutput<K, V>
+where
+    K: Eq + std::hash::Hash,
+{
+    map: HashMap<K, V>,
+}
+
+impl<K, V> MapOutput<K, V>
+where
+    K: Eq + std::hash::Hash,
+{
+    pub(crate) fn new(map: HashMap<K, V>) -> MapOutput<K, V> {
+        MapOutput { map }
+    }
+
+    /// Consume the output, returning the underlying `HashMap`
+    pub fn into_inner(self) -> HashMap<K, V> {
+        self.map
+    }
+}
+
+impl<K, V> Deref for MapOutput<K, V>
+where
+    K: Eq + std::hash::Hash,
+{
+    type Target = HashMap<K, V>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.map
+    }
+}
@@ -1,24 +1,46 @@
 //! Module containing tensor with memory owned by Rust
 
-use std::{ffi, fmt::Debug, ops::Deref};
+use std::{convert::TryFrom, ffi, fmt::Debug, ptr::NonNull};
 
-use ndarray::Array;
+use lazy_static::lazy_static;
+#[cfg(feature = "image")]
+use ndarray::Ix4;
+#[cfg(feature = "bytemuck")]
+use ndarray::ShapeBuilder;
+use ndarray::{Array, ArrayBase, ArrayView, CowArray, IxDyn};
 use tracing::{debug, error};
 
 use onnxruntime_sys as sys;
 
+#[cfg(feature = "image")]
+use crate::tensor::image_tensor::{self, ImageLayout, Normalization};
 use crate::{
     error::{call_ort, status_to_result},
     g_ort,
     memory::MemoryInfo,
-    tensor::{ndarray_tensor::NdArrayTensor, TensorElementDataType, TypeToTensorElementDataType},
-    OrtError, Result,
+    tensor::{
+        debug_summary, ndarray_tensor::NdArrayTensor, TensorElementDataType,
+        TypeToTensorElementDataType,
+    },
+    AllocatorType, MemType, OrtError, Result,
 };
 
+lazy_static! {
+    /// Describes the memory of the tensors copied by [`OrtTensor::from_array_copied()`]
+    static ref CPU_MEMORY_INFO: MemoryInfo =
+        MemoryInfo::new(AllocatorType::Arena, MemType::Default)
+            .expect("Failed to create CPU memory info");
+}
+
 /// Owned tensor, backed by an [`ndarray::Array`](https://docs.rs/ndarray/latest/ndarray/type.Array.html)
 ///
 /// This tensor bounds the ONNX Runtime to `ndarray`; it is used to copy an
 /// [`ndarray::Array`](https://docs.rs/ndarray/latest/ndarray/type.Array.html) to the runtime's memory.
+/// It can also borrow the data of an
+/// [`ndarray::ArrayView`](https://docs.rs/ndarray/latest/ndarray/type.ArrayView.html), see
+/// [`from_cow_array()`](#method.from_cow_array), or own memory allocated by ONNX Runtime, see
+/// [`from_array_copied()`](#method.from_array_copied). Its data is read with
+/// [`view()`](#method.view).
 ///
 /// **NOTE**: The type is not meant to be used directly, use an [`ndarray::Array`](https://docs.rs/ndarray/latest/ndarray/type.Array.html)
 /// instead.
@@ -29,19 +51,61 @@ where
     D: ndarray::Dimension,
 {
     pub(crate) c_ptr: *mut sys::OrtValue,
-    array: Array<T, D>,
+    data: TensorData<'t, T, D>,
     memory_info: &'t MemoryInfo,
 }
 
+/// Where the data of an [`OrtTensor`] lives
+#[derive(Debug)]
+enum TensorData<'t, T, D>
+where
+    D: ndarray::Dimension,
+{
+    /// An array, which ONNX Runtime reads in place (strings are copied to the value)
+    Array(CowArray<'t, T, D>),
+    /// Memory allocated by ONNX Runtime, released with the value
+    Allocated {
+        /// Start of the elements, in standard (C) order
+        ptr: *mut T,
+        shape: D,
+    },
+}
+
+// The value wraps the array's memory and is only read by ONNX Runtime, which allows it from any
+// thread: the tensor is as thread safe as its array (which may be borrowed, hence `Sync` for
+// `Send`).
+unsafe impl<'t, T, D> Send for OrtTensor<'t, T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone + Send + Sync,
+    D: ndarray::Dimension,
+{
+}
+unsafe impl<'t, T, D> Sync for OrtTensor<'t, T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone + Sync,
+    D: ndarray::Dimension,
+{
+}
+
 impl<'t, T, D> OrtTensor<'t, T, D>
 where
     T: TypeToTensorElementDataType + Debug + Clone,
     D: ndarray::Dimension,
 {
+    /// Build a tensor handing the memory of `array` to ONNX Runtime
+    ///
+    /// The tensor's shape is the array's: its dimensions must each fit in an `i64` (ONNX Runtime's
+    /// dimension type), and their product must be the array's number of elements. Arrays failing
+    /// this invariant are rejected with
+    /// [`OrtError::InvalidTensorShape`](../../error/enum.OrtError.html#variant.InvalidTensorShape)
+    /// rather than producing a tensor of the wrong size.
+    ///
+    /// Owned arrays are moved into the tensor, and borrowed arrays in standard layout are read in
+    /// place: only arrays in another layout are copied.
     pub(crate) fn from_array<'m>(
         memory_info: &'m MemoryInfo,
         allocator_ptr: *mut sys::OrtAllocator,
-        mut array: Array<T, D>,
+        mut array: CowArray<'t, T, D>,
     ) -> Result<OrtTensor<'t, T, D>>
     where
         'm: 't, // 'm outlives 't
@@ -50,6 +114,7 @@ where
         let mut tensor_ptr: *mut sys::OrtValue = std::ptr::null_mut();
         let tensor_ptr_ptr: *mut *mut sys::OrtValue = &mut tensor_ptr;
 
+        check_shape(array.shape(), array.len())?;
         let shape: Vec<i64> = array.shape().iter().map(|d: &usize| *d as i64).collect();
         let shape_ptr: *const i64 = shape.as_ptr();
         let shape_len = array.shape().len() as u64;
@@ -64,32 +129,13 @@ where
             | TensorElementDataType::Int64
             | TensorElementDataType::Double
             | TensorElementDataType::Uint32
-            | TensorElementDataType::Uint64 => {
-                // primitive data is already suitably laid out in memory; provide it to
-                // onnxruntime as is
-                let tensor_values_ptr: *mut std::ffi::c_void =
-                    array.as_mut_ptr() as *mut std::ffi::c_void;
-                assert_ne!(tensor_values_ptr, std::ptr::null_mut());
-
-                unsafe {
-                    call_ort(|ort| {
-                        ort.CreateTensorWithDataAsOrtValue.unwrap()(
-                            memory_info.ptr,
-                            tensor_values_ptr,
-                            (array.len() * std::mem::size_of::<T>()) as u64,
-                            shape_ptr,
-                            shape_len,
-                            T::tensor_element_data_type().into(),
-                            tensor_ptr_ptr,
-                        )
-                    })
-                }
-                .map_err(OrtError::CreateTensorWithData)?;
-                assert_ne!(tensor_ptr, std::ptr::null_mut());
-
-                let mut is_tensor = 0;
-                let status = unsafe { g_ort().IsTensor.unwrap()(tensor_ptr, &mut is_tensor) };
-                status_to_result(status).map_err(OrtError::IsTensor)?;
+            | TensorElementDataType::Uint64
+            | TensorElementDataType::Bool => {
+                tensor_ptr = Self::create_tensor_with_data(memory_info, &mut array, &shape)?;
+            }
+            #[cfg(feature = "complex")]
+            TensorElementDataType::Complex64 | TensorElementDataType::Complex128 => {
+                tensor_ptr = Self::create_tensor_with_data(memory_info, &mut array, &shape)?;
             }
             TensorElementDataType::String => {
                 // create tensor without data -- data is filled in later
@@ -140,21 +186,273 @@ where
 
         Ok(OrtTensor {
             c_ptr: tensor_ptr,
-            array,
+            data: TensorData::Array(array),
             memory_info,
         })
     }
+
+    /// Create a tensor over the memory of `array`, a primitive (number or boolean) array, copying
+    /// it to standard layout first if needed
+    fn create_tensor_with_data(
+        memory_info: &MemoryInfo,
+        array: &mut CowArray<'t, T, D>,
+        shape: &[i64],
+    ) -> Result<*mut sys::OrtValue> {
+        let mut tensor_ptr: *mut sys::OrtValue = std::ptr::null_mut();
+
+        // onnxruntime reads the data in standard (C) layout: arrays in any other layout
+        // (e.g. fortran order, or with negative strides) would be read in the wrong
+        // order, so they are copied first
+        if !array.is_standard_layout() {
+            debug!("Copying non-contiguous input array to standard layout.");
+            *array = CowArray::from(array.as_standard_layout().into_owned());
+        }
+
+        // primitive data is now suitably laid out in memory; provide it to
+        // onnxruntime as is. The pointer is only mutable for the C API's sake: onnxruntime
+        // never writes to input values, so borrowed data can be given too.
+        let tensor_values_ptr: *mut std::ffi::c_void = array.as_ptr() as *mut std::ffi::c_void;
+        assert_ne!(tensor_values_ptr, std::ptr::null_mut());
+
+        unsafe {
+            call_ort(|ort| {
+                ort.CreateTensorWithDataAsOrtValue.unwrap()(
+                    memory_info.ptr,
+                    tensor_values_ptr,
+                    (array.len() * std::mem::size_of::<T>()) as u64,
+                    shape.as_ptr(),
+                    shape.len() as u64,
+                    T::tensor_element_data_type().into(),
+                    &mut tensor_ptr,
+                )
+            })
+        }
+        .map_err(OrtError::CreateTensorWithData)?;
+        assert_ne!(tensor_ptr, std::ptr::null_mut());
+
+        let mut is_tensor = 0;
+        let status = unsafe { g_ort().IsTensor.unwrap()(tensor_ptr, &mut is_tensor) };
+        status_to_result(status).map_err(OrtError::IsTensor)?;
+        Ok(tensor_ptr)
+    }
+}
+
+impl<T, D> OrtTensor<'static, T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone,
+    D: ndarray::Dimension,
+{
+    /// Allocate a tensor of the given shape with ONNX Runtime's default CPU allocator, its memory
+    /// set to zero
+    ///
+    /// Zeroed memory is a valid value of every primitive (number or boolean) type: `T` must not be
+    /// a string type, whose values ONNX Runtime manages itself.
+    pub(crate) fn allocate(shape: D) -> Result<OrtTensor<'static, T, D>> {
+        assert_ne!(
+            T::tensor_element_data_type(),
+            TensorElementDataType::String,
+            "String tensors cannot be allocated"
+        );
+        let len = shape.size_checked().unwrap_or(usize::MAX);
+        check_shape(shape.slice(), len)?;
+        let ort_shape: Vec<i64> = shape.slice().iter().map(|d| *d as i64).collect();
+
+        let allocator_ptr = default_allocator()?;
+        let mut tensor_ptr: *mut sys::OrtValue = std::ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.CreateTensorAsOrtValue.unwrap()(
+                    allocator_ptr,
+                    ort_shape.as_ptr(),
+                    ort_shape.len() as u64,
+                    T::tensor_element_data_type().into(),
+                    &mut tensor_ptr,
+                )
+            })
+        }
+        .map_err(OrtError::CreateTensor)?;
+        assert_ne!(tensor_ptr, std::ptr::null_mut());
+        // Released on error from now on
+        let mut tensor = OrtTensor {
+            c_ptr: tensor_ptr,
+            data: TensorData::Allocated {
+                ptr: NonNull::dangling().as_ptr(),
+                shape,
+            },
+            memory_info: &CPU_MEMORY_INFO,
+        };
+
+        if len > 0 {
+            let mut data_ptr: *mut T = std::ptr::null_mut();
+            unsafe {
+                call_ort(|ort| {
+                    ort.GetTensorMutableData.unwrap()(
+                        tensor_ptr,
+                        &mut data_ptr as *mut *mut T as *mut *mut ffi::c_void,
+                    )
+                })
+            }
+            .map_err(OrtError::GetTensorMutableData)?;
+            assert_ne!(data_ptr, std::ptr::null_mut());
+            unsafe { std::ptr::write_bytes(data_ptr, 0, len) };
+            if let TensorData::Allocated { ptr, .. } = &mut tensor.data {
+                *ptr = data_ptr;
+            }
+        }
+
+        Ok(tensor)
+    }
+
+    /// Elements of a tensor built by [`allocate()`](#method.allocate), in standard (C) order
+    pub(crate) fn allocated_data_mut(&mut self) -> Option<&mut [T]> {
+        match &mut self.data {
+            TensorData::Array(_) => None,
+            TensorData::Allocated { ptr, shape } => {
+                Some(unsafe { std::slice::from_raw_parts_mut(*ptr, shape.size()) })
+            }
+        }
+    }
 }
 
-impl<'t, T, D> Deref for OrtTensor<'t, T, D>
+impl<T> OrtTensor<'static, T, IxDyn>
 where
     T: TypeToTensorElementDataType + Debug + Clone,
+{
+    /// Build a tensor from flat data, in standard (C) order, and its shape
+    ///
+    /// This is handy for data coming from FFI or the network. `data` is moved into the tensor, which
+    /// ONNX Runtime then reads in place.
+    ///
+    /// # Errors
+    ///
+    /// [`OrtError::ShapeDataMismatch`](../../error/enum.OrtError.html#variant.ShapeDataMismatch) is
+    /// returned if the length of `data` is not the product of the dimensions of `shape`.
+    pub fn from_vec(data: Vec<T>, shape: &[usize]) -> Result<OrtTensor<'static, T, IxDyn>> {
+        let data_len = data.len();
+        let array =
+            Array::from_shape_vec(IxDyn(shape), data).map_err(|_| OrtError::ShapeDataMismatch {
+                shape: shape.to_vec(),
+                data_len,
+            })?;
+        OrtTensor::from_array(&CPU_MEMORY_INFO, default_allocator()?, array.into())
+    }
+}
+
+#[cfg(feature = "image")]
+impl OrtTensor<'static, f32, Ix4> {
+    /// Build a tensor of a normalized RGB image, as a batch of one image
+    ///
+    /// The channel values are normalized with `normalization`, see
+    /// [`image_tensor::Normalization`](image_tensor/struct.Normalization.html).
+    ///
+    /// Enabled with the `image` feature.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if a standard deviation of `normalization` is zero.
+    pub fn from_image(
+        image: &::image::RgbImage,
+        layout: ImageLayout,
+        normalization: Normalization,
+    ) -> Result<OrtTensor<'static, f32, Ix4>> {
+        OrtTensor::from_array(
+            &CPU_MEMORY_INFO,
+            default_allocator()?,
+            image_tensor::normalized_array(image, layout, normalization)?.into(),
+        )
+    }
+}
+
+#[cfg(feature = "image")]
+impl OrtTensor<'static, u8, Ix4> {
+    /// Build a tensor of the raw `u8` channel values of an RGB image, as a batch of one image
+    ///
+    /// The values are not normalized: this is for models with built-in preprocessing nodes. See
+    /// [`image_tensor::from_image_raw()`](image_tensor/fn.from_image_raw.html).
+    ///
+    /// Enabled with the `image` feature.
+    pub fn from_image_raw(
+        image: &::image::RgbImage,
+        layout: ImageLayout,
+    ) -> Result<OrtTensor<'static, u8, Ix4>> {
+        OrtTensor::from_array(
+            &CPU_MEMORY_INFO,
+            default_allocator()?,
+            image_tensor::from_image_raw(image, layout).into(),
+        )
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+impl<'b, T, D> OrtTensor<'b, T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone + bytemuck::Pod,
     D: ndarray::Dimension,
 {
-    type Target = Array<T, D>;
+    /// Build a tensor reading raw bytes (e.g. a network payload) in place, as elements of type `T`
+    /// in standard (C) order
+    ///
+    /// The elements are read in the machine's native endianness. Nothing is copied: ONNX Runtime
+    /// reads `bytes` directly, and the tensor borrows them for as long as it lives.
+    ///
+    /// Enabled with the `bytemuck` feature.
+    ///
+    /// # Errors
+    ///
+    /// [`OrtError::BytesCast`](../../error/enum.OrtError.html#variant.BytesCast) is returned if
+    /// `bytes` is not aligned for `T` or if its length is not a multiple of the size of `T`, and
+    /// [`OrtError::ShapeDataMismatch`](../../error/enum.OrtError.html#variant.ShapeDataMismatch)
+    /// if the number of elements doesn't match `shape`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use onnxruntime::tensor::OrtTensor;
+    /// # fn main() -> onnxruntime::Result<()> {
+    /// let values = [1.0_f32, 2.0, 3.0, 4.0];
+    /// let bytes: &[u8] = bytemuck::cast_slice(&values);
+    /// let tensor = OrtTensor::<f32, _>::from_bytes(bytes, (2, 2))?;
+    /// assert_eq!(tensor.view(), ndarray::arr2(&[[1.0, 2.0], [3.0, 4.0]]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_bytes<Sh>(bytes: &'b [u8], shape: Sh) -> Result<OrtTensor<'b, T, D>>
+    where
+        Sh: ShapeBuilder<Dim = D>,
+    {
+        let elements: &[T] = bytemuck::try_cast_slice(bytes).map_err(OrtError::BytesCast)?;
+        let shape = shape.into_shape();
+        let dims = shape.raw_dim().clone();
+        let view =
+            ArrayView::from_shape(shape, elements).map_err(|_| OrtError::ShapeDataMismatch {
+                shape: dims.slice().to_vec(),
+                data_len: elements.len(),
+            })?;
+        OrtTensor::from_array_view(view)
+    }
+}
+
+/// ONNX Runtime's default CPU allocator, needed to create string tensors
+fn default_allocator() -> Result<*mut sys::OrtAllocator> {
+    let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
+    unsafe { call_ort(|ort| ort.GetAllocatorWithDefaultOptions.unwrap()(&mut allocator_ptr)) }
+        .map_err(OrtError::Allocator)?;
+    Ok(allocator_ptr)
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.array
+/// Make sure `shape` can be given to ONNX Runtime, and describes `len` elements
+fn check_shape(shape: &[usize], len: usize) -> Result<()> {
+    let element_count = shape
+        .iter()
+        .try_fold(1_usize, |count, dim| count.checked_mul(*dim));
+    let fits_i64 = shape.iter().all(|dim| i64::try_from(*dim).is_ok());
+    if fits_i64 && element_count == Some(len) {
+        Ok(())
+    } else {
+        Err(OrtError::InvalidTensorShape {
+            shape: shape.to_vec(),
+            len,
+        })
     }
 }
 
@@ -182,13 +480,147 @@ where
     T: TypeToTensorElementDataType + Debug + Clone,
     D: ndarray::Dimension,
 {
+    /// Build a tensor owning a copy of the data of `array`, which can be a view
+    ///
+    /// The tensor isn't tied to `array` in any way: the source can be short-lived and dropped
+    /// right away. The data is copied, in standard (C) layout, to memory allocated by ONNX Runtime.
+    /// String data is copied to the runtime's own string storage.
+    pub fn from_array_copied<S>(array: &ArrayBase<S, D>) -> Result<OrtTensor<'static, T, D>>
+    where
+        S: ndarray::Data<Elem = T>,
+    {
+        if T::tensor_element_data_type() == TensorElementDataType::String {
+            return OrtTensor::from_array(
+                &CPU_MEMORY_INFO,
+                default_allocator()?,
+                array.to_owned().into(),
+            );
+        }
+
+        let mut tensor = OrtTensor::allocate(array.raw_dim())?;
+        if let Some(data) = tensor.allocated_data_mut() {
+            for (element, value) in data.iter_mut().zip(array.iter()) {
+                *element = value.clone();
+            }
+        }
+        Ok(tensor)
+    }
+
+    /// Build a tensor from an owned array or a view, without copying when possible
+    ///
+    /// An [`ndarray::Array`](https://docs.rs/ndarray/latest/ndarray/type.Array.html) is moved into
+    /// the tensor. An [`ndarray::ArrayView`](https://docs.rs/ndarray/latest/ndarray/type.ArrayView.html)
+    /// in standard (C) layout is read in place by ONNX Runtime, the tensor then borrowing its data;
+    /// a view in any other layout (e.g. a transposed or sliced array) is copied. String data is
+    /// always copied to the runtime.
+    ///
+    /// ```
+    /// # use onnxruntime::tensor::OrtTensor;
+    /// # fn main() -> onnxruntime::Result<()> {
+    /// let array = ndarray::arr2(&[[1.0_f32, 2.0], [3.0, 4.0]]);
+    /// let tensor = OrtTensor::from_cow_array(array.view())?;
+    /// assert_eq!(tensor.shape(), &[2, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_cow_array<A>(array: A) -> Result<OrtTensor<'t, T, D>>
+    where
+        A: Into<CowArray<'t, T, D>>,
+    {
+        OrtTensor::from_array(&CPU_MEMORY_INFO, default_allocator()?, array.into())
+    }
+
+    /// Build a tensor reading the data of `array` in place, see
+    /// [`from_cow_array()`](#method.from_cow_array)
+    pub fn from_array_view(array: ArrayView<'t, T, D>) -> Result<OrtTensor<'t, T, D>> {
+        OrtTensor::from_cow_array(array)
+    }
+
+    /// Whether the tensor reads the data of a borrowed array in place, rather than owning it
+    pub fn is_borrowed(&self) -> bool {
+        match &self.data {
+            TensorData::Array(array) => array.is_view(),
+            TensorData::Allocated { .. } => false,
+        }
+    }
+
+    /// View of the tensor's data
+    pub fn view(&self) -> ArrayView<'_, T, D> {
+        match &self.data {
+            TensorData::Array(array) => array.view(),
+            // The memory is owned by the value, and only written to before the tensor is built
+            TensorData::Allocated { ptr, shape } => unsafe {
+                ArrayView::from_shape_ptr(shape.clone(), *ptr)
+            },
+        }
+    }
+
+    /// Shape of the tensor
+    pub fn shape(&self) -> &[usize] {
+        match &self.data {
+            TensorData::Array(array) => array.shape(),
+            TensorData::Allocated { shape, .. } => shape.slice(),
+        }
+    }
+
+    /// Raw `OrtValue` pointer of the tensor, for use with other ONNX Runtime C API code
+    ///
+    /// The value remains owned by the `OrtTensor`: it must not be released, and it is only valid
+    /// as long as the `OrtTensor` is alive. Its data is the tensor's `ndarray::Array`, so it must
+    /// not be modified through the pointer either.
+    pub fn as_raw_ort_value(&self) -> *mut sys::OrtValue {
+        self.c_ptr
+    }
+
+    /// Number of elements in the tensor, as given by its shape
+    pub fn element_count(&self) -> usize {
+        self.shape().iter().product()
+    }
+
+    /// Size of the tensor's data, in bytes
+    ///
+    /// For primitive types this is `element_count() * size_of::<T>()`. Strings have no fixed
+    /// size: for them this is the total length of their utf8 contents, which is what is copied
+    /// to the runtime.
+    pub fn byte_size(&self) -> usize {
+        match T::tensor_element_data_type() {
+            TensorElementDataType::String => self
+                .view()
+                .iter()
+                .map(|elt| elt.try_utf8_bytes().map_or(0, |bytes| bytes.len()))
+                .sum(),
+            _ => self.element_count() * std::mem::size_of::<T>(),
+        }
+    }
+
+    /// Describe the tensor in one line, for debugging: its element type, its shape and its first
+    /// and last elements (e.g. `Float tensor of shape [2, 4]: [1.0, 2.0, 3.0, ..., 8.0]`)
+    pub fn debug_summary(&self) -> String {
+        debug_summary(T::tensor_element_data_type(), self.view())
+    }
+
+    /// Write the tensor to `writer` in numpy's [`.npy` format](https://numpy.org/doc/stable/reference/generated/numpy.lib.format.html),
+    /// see [`OrtOwnedTensor::write_npy()`](struct.OrtOwnedTensor.html#method.write_npy)
+    ///
+    /// Enabled with the `npy` feature.
+    #[cfg(feature = "npy")]
+    pub fn write_npy<W>(&self, writer: W) -> Result<()>
+    where
+        W: std::io::Write,
+        T: ndarray_npy::WritableElement,
+    {
+        use ndarray_npy::WriteNpyExt;
+
+        self.view().write_npy(writer).map_err(OrtError::NpyWrite)
+    }
+
     /// Apply a softmax on the specified axis
     pub fn softmax(&self, axis: ndarray::Axis) -> Array<T, D>
     where
         D: ndarray::RemoveAxis,
         T: ndarray::NdFloat + std::ops::SubAssign + std::ops::DivAssign,
     {
-        self.array.softmax(axis)
+        self.view().softmax(axis)
     }
 }
 
@@ -204,7 +636,7 @@ mod tests {
     fn orttensor_from_array_0d_i32() {
         let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
         let array = arr0::<i32>(123);
-        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array).unwrap();
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
         let expected_shape: &[usize] = &[];
         assert_eq!(tensor.shape(), expected_shape);
     }
@@ -213,7 +645,7 @@ mod tests {
     fn orttensor_from_array_1d_i32() {
         let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
         let array = arr1(&[1_i32, 2, 3, 4, 5, 6]);
-        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array).unwrap();
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
         let expected_shape: &[usize] = &[6];
         assert_eq!(tensor.shape(), expected_shape);
     }
@@ -222,7 +654,7 @@ mod tests {
     fn orttensor_from_array_2d_i32() {
         let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
         let array = arr2(&[[1_i32, 2, 3, 4, 5, 6], [7, 8, 9, 10, 11, 12]]);
-        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array).unwrap();
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
         assert_eq!(tensor.shape(), &[2, 6]);
     }
 
@@ -234,10 +666,75 @@ mod tests {
             [[13, 14, 15, 16, 17, 18], [19, 20, 21, 22, 23, 24]],
             [[25, 26, 27, 28, 29, 30], [31, 32, 33, 34, 35, 36]],
         ]);
-        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array).unwrap();
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
         assert_eq!(tensor.shape(), &[3, 2, 6]);
     }
 
+    #[test]
+    fn orttensor_from_cow_array() {
+        // Owned arrays are moved in
+        let array = arr2(&[[1_i32, 2, 3], [4, 5, 6]]);
+        let data_ptr = array.as_ptr();
+        let tensor = OrtTensor::from_cow_array(array).unwrap();
+        assert!(!tensor.is_borrowed());
+        assert_eq!(tensor_data(&tensor), data_ptr);
+
+        // Contiguous views are read in place
+        let array = arr2(&[[1_i32, 2, 3], [4, 5, 6]]);
+        let view = array.slice(ndarray::s![1.., ..]);
+        let tensor = OrtTensor::from_array_view(view).unwrap();
+        assert!(tensor.is_borrowed());
+        assert_eq!(tensor.shape(), &[1, 3]);
+        assert_eq!(tensor_data(&tensor), view.as_ptr());
+
+        // Other views are copied to standard layout
+        let view = array.t();
+        let tensor = OrtTensor::from_cow_array(view).unwrap();
+        assert!(!tensor.is_borrowed());
+        assert_eq!(tensor.shape(), &[3, 2]);
+        let data = unsafe { std::slice::from_raw_parts(tensor_data(&tensor), 6) };
+        assert_eq!(data, &[1, 4, 2, 5, 3, 6]);
+    }
+
+    /// Pointer to the data ONNX Runtime reads for `tensor`
+    fn tensor_data<D: ndarray::Dimension>(tensor: &OrtTensor<'_, i32, D>) -> *const i32 {
+        let mut data_ptr: *mut i32 = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    tensor.c_ptr,
+                    &mut data_ptr as *mut *mut i32 as *mut *mut ffi::c_void,
+                )
+            })
+        }
+        .unwrap();
+        data_ptr
+    }
+
+    #[test]
+    fn orttensor_from_array_2d_i32_fortran_order() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        // Owned array whose memory is in fortran order
+        let array = arr2(&[[1_i32, 2, 3], [4, 5, 6]]).reversed_axes();
+        assert!(!array.is_standard_layout());
+
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
+        assert_eq!(tensor.shape(), &[3, 2]);
+
+        let mut data_ptr: *mut i32 = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    tensor.c_ptr,
+                    &mut data_ptr as *mut *mut i32 as *mut *mut ffi::c_void,
+                )
+            })
+        }
+        .unwrap();
+        let data = unsafe { std::slice::from_raw_parts(data_ptr, 6) };
+        assert_eq!(data, &[1, 4, 2, 5, 3, 6]);
+    }
+
     #[test]
     fn orttensor_from_array_1d_string() {
         let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
@@ -246,7 +743,8 @@ mod tests {
             String::from("bar"),
             String::from("baz"),
         ]);
-        let tensor = OrtTensor::from_array(&memory_info, ort_default_allocator(), array).unwrap();
+        let tensor =
+            OrtTensor::from_array(&memory_info, ort_default_allocator(), array.into()).unwrap();
         assert_eq!(tensor.shape(), &[3]);
     }
 
@@ -257,10 +755,200 @@ mod tests {
             [["1", "2", "3"], ["4", "5", "6"]],
             [["7", "8", "9"], ["10", "11", "12"]],
         ]);
-        let tensor = OrtTensor::from_array(&memory_info, ort_default_allocator(), array).unwrap();
+        let tensor =
+            OrtTensor::from_array(&memory_info, ort_default_allocator(), array.into()).unwrap();
         assert_eq!(tensor.shape(), &[2, 2, 3]);
     }
 
+    #[test]
+    fn shape_invariant() {
+        assert!(check_shape(&[2, 3], 6).is_ok());
+        assert!(check_shape(&[], 1).is_ok());
+        assert!(check_shape(&[4, 0], 0).is_ok());
+
+        assert!(matches!(
+            check_shape(&[2, 3], 5),
+            Err(OrtError::InvalidTensorShape { len: 5, .. })
+        ));
+        // Overflowing element count
+        assert!(check_shape(&[usize::MAX, 2], 0).is_err());
+        // Dimension not fitting in an i64
+        assert!(check_shape(&[usize::MAX, 0], 0).is_err());
+
+        // Arrays built by ndarray always satisfy it
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let array = arr2(&[[1.0_f32, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
+        assert_eq!(tensor.element_count(), 6);
+    }
+
+    #[test]
+    fn orttensor_from_array_copied() {
+        let tensor = {
+            let array = arr2(&[[1_i32, 2, 3], [4, 5, 6]]);
+            // Copy a short-lived (and not contiguous) view
+            OrtTensor::from_array_copied(&array.t()).unwrap()
+        };
+        assert_eq!(tensor.shape(), &[3, 2]);
+        assert_eq!(tensor.view(), arr2(&[[1, 4], [2, 5], [3, 6]]));
+
+        let mut data_ptr: *mut i32 = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    tensor.as_raw_ort_value(),
+                    &mut data_ptr as *mut *mut i32 as *mut *mut ffi::c_void,
+                )
+            })
+        }
+        .unwrap();
+        let data = unsafe { std::slice::from_raw_parts(data_ptr, 6) };
+        assert_eq!(data, &[1, 4, 2, 5, 3, 6]);
+
+        let strings = {
+            let array = arr1(&["foo", "bar"]);
+            OrtTensor::from_array_copied(&array.view()).unwrap()
+        };
+        assert_eq!(strings.shape(), &[2]);
+    }
+
+    #[test]
+    fn orttensor_from_vec() {
+        let tensor = OrtTensor::from_vec(vec![1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0], &[2, 3]).unwrap();
+        assert_eq!(tensor.shape(), &[2, 3]);
+        assert_eq!(
+            tensor.view(),
+            arr2(&[[1.0_f32, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn()
+        );
+
+        let strings = OrtTensor::from_vec(vec!["foo", "bar"], &[2, 1]).unwrap();
+        assert_eq!(strings.shape(), &[2, 1]);
+
+        let empty = OrtTensor::<i64, _>::from_vec(vec![], &[0, 3]).unwrap();
+        assert_eq!(empty.element_count(), 0);
+    }
+
+    #[test]
+    fn orttensor_from_vec_length_mismatch() {
+        let result = OrtTensor::from_vec(vec![1_i32, 2, 3, 4, 5], &[2, 3]);
+        match result {
+            Err(OrtError::ShapeDataMismatch { shape, data_len }) => {
+                assert_eq!(shape, vec![2, 3]);
+                assert_eq!(data_len, 5);
+            }
+            other => panic!(
+                "Unexpected result: {:?}",
+                other.map(|tensor| tensor.element_count())
+            ),
+        }
+
+        // A scalar
+        assert!(OrtTensor::from_vec(vec![1_u8], &[]).is_ok());
+        assert!(matches!(
+            OrtTensor::from_vec(vec![1_u8, 2], &[]),
+            Err(OrtError::ShapeDataMismatch { data_len: 2, .. })
+        ));
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn orttensor_from_bytes() {
+        let values = [1.5_f32, -2.0, 3.25, 4.0, 0.0, 6.5];
+        // Bytes as they would be received, e.g. from the network
+        let bytes: &[u8] = bytemuck::cast_slice(&values);
+
+        let tensor = OrtTensor::<f32, _>::from_bytes(bytes, (2, 3)).unwrap();
+        assert_eq!(tensor.view(), arr2(&[[1.5, -2.0, 3.25], [4.0, 0.0, 6.5]]));
+
+        // ONNX Runtime reads the bytes in place
+        assert!(tensor.is_borrowed());
+        let mut data_ptr: *mut f32 = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    tensor.as_raw_ort_value(),
+                    &mut data_ptr as *mut *mut f32 as *mut *mut ffi::c_void,
+                )
+            })
+        }
+        .unwrap();
+        assert_eq!(data_ptr as *const u8, bytes.as_ptr());
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn orttensor_from_invalid_bytes() {
+        let aligned = [0.0_f32; 4];
+        let bytes: &[u8] = bytemuck::cast_slice(&aligned);
+
+        // Not a whole number of elements
+        assert!(matches!(
+            OrtTensor::<f32, _>::from_bytes(&bytes[..7], 1),
+            Err(OrtError::BytesCast(_))
+        ));
+        // Not aligned
+        assert!(matches!(
+            OrtTensor::<f32, _>::from_bytes(&bytes[1..5], 1),
+            Err(OrtError::BytesCast(_))
+        ));
+        // Not the number of elements of the shape
+        assert!(matches!(
+            OrtTensor::<f32, _>::from_bytes(bytes, (3, 2)),
+            Err(OrtError::ShapeDataMismatch { data_len: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn orttensor_as_raw_ort_value() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let array = arr2(&[[1_i32, 2, 3], [4, 5, 6]]);
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
+
+        let raw = tensor.as_raw_ort_value();
+        assert_eq!(raw, tensor.c_ptr);
+        let mut is_tensor = 0;
+        unsafe { call_ort(|ort| ort.IsTensor.unwrap()(raw, &mut is_tensor)) }.unwrap();
+        assert_eq!(is_tensor, 1);
+    }
+
+    #[test]
+    fn orttensor_sizes_f32() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let array = ndarray::Array::<f32, _>::zeros((1, 3, 224, 224));
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
+        assert_eq!(tensor.element_count(), 3 * 224 * 224);
+        assert_eq!(tensor.byte_size(), 3 * 224 * 224 * 4);
+    }
+
+    #[test]
+    fn orttensor_sizes_string() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let array = arr1(&[String::from("foo"), String::from("ba"), String::from("ü")]);
+        let tensor =
+            OrtTensor::from_array(&memory_info, ort_default_allocator(), array.into()).unwrap();
+        assert_eq!(tensor.element_count(), 3);
+        assert_eq!(tensor.byte_size(), 3 + 2 + 2);
+    }
+
+    #[test]
+    fn orttensor_debug_summary() {
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let array = arr2(&[[1_i64, 2, 3, 4], [5, 6, 7, 8]]);
+        let tensor = OrtTensor::from_array(&memory_info, ptr::null_mut(), array.into()).unwrap();
+        assert_eq!(
+            tensor.debug_summary(),
+            "Int64 tensor of shape [2, 4]: [1, 2, 3, ..., 6, 7, 8]"
+        );
+
+        let array = arr1(&[String::from("foo"), String::from("bar")]);
+        let tensor =
+            OrtTensor::from_array(&memory_info, ort_default_allocator(), array.into()).unwrap();
+        assert_eq!(
+            tensor.debug_summary(),
+            r#"String tensor of shape [2]: ["foo", "bar"]"#
+        );
+    }
+
     fn ort_default_allocator() -> *mut sys::OrtAllocator {
         let mut allocator_ptr: *mut sys::OrtAllocator = std::ptr::null_mut();
         unsafe {
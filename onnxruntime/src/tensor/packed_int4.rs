@@ -0,0 +1,102 @@
+//! Module containing a view over packed 4-bit integers
+//!
+//! ONNX stores `INT4` and `UINT4` tensors packed, two values per byte: the first value in the low
+//! nibble and the second in the high nibble. Rust has no 4-bit integer type, so the values are
+//! exposed unpacked to `i8` or `u8`.
+//!
+//! **NOTE**: The `INT4` and `UINT4` element types were added to ONNX Runtime after the version
+//! this crate is built against (1.6), which cannot produce such tensors: there is no corresponding
+//! [`TensorElementDataType`](../enum.TensorElementDataType.html) yet. This view decodes packed data
+//! obtained by other means (for example read from a model's initializers).
+
+/// View over packed 4-bit integers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PackedInt4<'a> {
+    bytes: &'a [u8],
+    len: usize,
+}
+
+impl<'a> PackedInt4<'a> {
+    /// View `len` values packed in `bytes`
+    ///
+    /// Returns `None` if `bytes` doesn't hold exactly `len` values (rounded up to a whole byte).
+    pub fn new(bytes: &'a [u8], len: usize) -> Option<PackedInt4<'a>> {
+        if bytes.len() == len.div_ceil(2) {
+            Some(PackedInt4 { bytes, len })
+        } else {
+            None
+        }
+    }
+
+    /// Number of values
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The packed bytes
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Unsigned value at `index` (`UINT4`, in `0..=15`)
+    pub fn get_unsigned(&self, index: usize) -> Option<u8> {
+        if index >= self.len {
+            return None;
+        }
+        let byte = self.bytes[index / 2];
+        Some(if index.is_multiple_of(2) {
+            byte & 0x0F
+        } else {
+            byte >> 4
+        })
+    }
+
+    /// Signed value at `index` (`INT4`, in `-8..=7`)
+    pub fn get_signed(&self, index: usize) -> Option<i8> {
+        // Move the nibble to the high bits, then shift back to extend the sign
+        self.get_unsigned(index)
+            .map(|nibble| ((nibble << 4) as i8) >> 4)
+    }
+
+    /// Unpack the values as unsigned integers (`UINT4`)
+    pub fn unpack_unsigned(&self) -> Vec<u8> {
+        (0..self.len)
+            .map(|index| self.get_unsigned(index).unwrap())
+            .collect()
+    }
+
+    /// Unpack the values as signed integers (`INT4`)
+    pub fn unpack_signed(&self) -> Vec<i8> {
+        (0..self.len)
+            .map(|index| self.get_signed(index).unwrap())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn unpack_int4() {
+        // Values 1, -2, 7, -8, 3: 0xE1 (1, -2), 0x87 (7, -8), 0x03 (3, padding)
+        let packed = PackedInt4::new(&[0xE1, 0x87, 0x03], 5).unwrap();
+        assert_eq!(packed.len(), 5);
+        assert_eq!(packed.unpack_signed(), vec![1, -2, 7, -8, 3]);
+        assert_eq!(packed.unpack_unsigned(), vec![1, 14, 7, 8, 3]);
+        assert_eq!(packed.get_signed(5), None);
+    }
+
+    #[test]
+    fn wrong_length() {
+        assert!(PackedInt4::new(&[0x12, 0x34], 5).is_none());
+        assert!(PackedInt4::new(&[0x12, 0x34], 4).is_some());
+        assert!(PackedInt4::new(&[0x12, 0x34], 3).is_some());
+    }
+}
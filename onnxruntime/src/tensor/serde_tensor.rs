@@ -0,0 +1,329 @@
+//! Module converting tensors to and from a serializable representation
+//!
+//! A [`SerializableTensor`](enum.SerializableTensor.html) serializes as
+//! `{ "dtype": "float", "shape": [2, 2], "data": [1.0, 2.0, 3.0, 4.0] }`, the `dtype` being the
+//! ONNX name of the element type and `data` holding the elements in standard (C) order. This is
+//! convenient to return inference results from a web service, or to read inputs from a request.
+//!
+//! Enabled with the `serde` feature.
+
+use std::fmt::Debug;
+
+use ndarray::{Array, ArrayBase, Data, Dimension, IxDyn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    tensor::{DynOrtTensor, TensorDataToType, TensorElementDataType},
+    OrtError, Result,
+};
+
+/// A tensor that can be serialized and deserialized with serde
+///
+/// Build it from an output with
+/// [`from_output()`](enum.SerializableTensor.html#method.from_output) (or run a session with
+/// [`Session::run_serializable()`](../../session/struct.Session.html#method.run_serializable)),
+/// or from an array with [`from_array()`](enum.SerializableTensor.html#method.from_array). Once
+/// deserialized, [`into_array()`](enum.SerializableTensor.html#method.into_array) turns it back
+/// into an array that can be used as an input.
+///
+/// ```
+/// # use onnxruntime::tensor::serde_tensor::SerializableTensor;
+/// let tensor = SerializableTensor::from_array(&ndarray::arr2(&[[1_i64, 2], [3, 4]]));
+/// assert_eq!(
+///     serde_json::to_string(&tensor).unwrap(),
+///     r#"{"dtype":"int64","shape":[2,2],"data":[1,2,3,4]}"#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "dtype", rename_all = "lowercase")]
+#[allow(missing_docs)]
+pub enum SerializableTensor {
+    Float {
+        shape: Vec<usize>,
+        data: Vec<f32>,
+    },
+    Uint8 {
+        shape: Vec<usize>,
+        data: Vec<u8>,
+    },
+    Int8 {
+        shape: Vec<usize>,
+        data: Vec<i8>,
+    },
+    Uint16 {
+        shape: Vec<usize>,
+        data: Vec<u16>,
+    },
+    Int16 {
+        shape: Vec<usize>,
+        data: Vec<i16>,
+    },
+    Int32 {
+        shape: Vec<usize>,
+        data: Vec<i32>,
+    },
+    Int64 {
+        shape: Vec<usize>,
+        data: Vec<i64>,
+    },
+    String {
+        shape: Vec<usize>,
+        data: Vec<String>,
+    },
+    Double {
+        shape: Vec<usize>,
+        data: Vec<f64>,
+    },
+    Uint32 {
+        shape: Vec<usize>,
+        data: Vec<u32>,
+    },
+    Uint64 {
+        shape: Vec<usize>,
+        data: Vec<u64>,
+    },
+    Bool {
+        shape: Vec<usize>,
+        data: Vec<bool>,
+    },
+}
+
+/// Trait implemented by the element types a [`SerializableTensor`](enum.SerializableTensor.html)
+/// can hold
+pub trait SerializableElement: TensorDataToType + Clone + Debug {
+    /// Wrap elements, in standard order, into the matching variant
+    #[doc(hidden)]
+    fn wrap(shape: Vec<usize>, data: Vec<Self>) -> SerializableTensor;
+
+    /// Take the shape and elements out of the matching variant, or give the tensor back
+    #[doc(hidden)]
+    fn unwrap(
+        tensor: SerializableTensor,
+    ) -> std::result::Result<(Vec<usize>, Vec<Self>), SerializableTensor>;
+}
+
+macro_rules! impl_serializable_element {
+    ($type_:ty, $variant:ident) => {
+        impl SerializableElement for $type_ {
+            fn wrap(shape: Vec<usize>, data: Vec<Self>) -> SerializableTensor {
+                SerializableTensor::$variant { shape, data }
+            }
+
+            fn unwrap(
+                tensor: SerializableTensor,
+            ) -> std::result::Result<(Vec<usize>, Vec<Self>), SerializableTensor> {
+                match tensor {
+                    SerializableTensor::$variant { shape, data } => Ok((shape, data)),
+                    other => Err(other),
+                }
+            }
+        }
+    };
+}
+
+impl_serializable_element!(f32, Float);
+impl_serializable_element!(u8, Uint8);
+impl_serializable_element!(i8, Int8);
+impl_serializable_element!(u16, Uint16);
+impl_serializable_element!(i16, Int16);
+impl_serializable_element!(i32, Int32);
+impl_serializable_element!(i64, Int64);
+impl_serializable_element!(String, String);
+impl_serializable_element!(f64, Double);
+impl_serializable_element!(u32, Uint32);
+impl_serializable_element!(u64, Uint64);
+impl_serializable_element!(bool, Bool);
+
+impl SerializableTensor {
+    /// Copy the elements of an array, in standard order
+    pub fn from_array<T, S, D>(array: &ArrayBase<S, D>) -> SerializableTensor
+    where
+        T: SerializableElement,
+        S: Data<Elem = T>,
+        D: Dimension,
+    {
+        T::wrap(array.shape().to_vec(), array.iter().cloned().collect())
+    }
+
+    /// Copy the elements of a tensor output by
+    /// [`Session::run()`](../../session/struct.Session.html#method.run), whatever its element type
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the output is not a tensor (but a sequence or a map), or if it is a
+    /// tensor of complex numbers.
+    pub fn from_output<D>(output: &DynOrtTensor<D>) -> Result<SerializableTensor>
+    where
+        D: Dimension,
+    {
+        match output.data_type() {
+            TensorElementDataType::Float => Self::extract::<f32, D>(output),
+            TensorElementDataType::Uint8 => Self::extract::<u8, D>(output),
+            TensorElementDataType::Int8 => Self::extract::<i8, D>(output),
+            TensorElementDataType::Uint16 => Self::extract::<u16, D>(output),
+            TensorElementDataType::Int16 => Self::extract::<i16, D>(output),
+            TensorElementDataType::Int32 => Self::extract::<i32, D>(output),
+            TensorElementDataType::Int64 => Self::extract::<i64, D>(output),
+            TensorElementDataType::String => Self::extract::<String, D>(output),
+            TensorElementDataType::Double => Self::extract::<f64, D>(output),
+            TensorElementDataType::Uint32 => Self::extract::<u32, D>(output),
+            TensorElementDataType::Uint64 => Self::extract::<u64, D>(output),
+            TensorElementDataType::Bool => Self::extract::<bool, D>(output),
+            #[cfg(feature = "complex")]
+            data_type @ TensorElementDataType::Complex64
+            | data_type @ TensorElementDataType::Complex128 => {
+                Err(OrtError::NotSerializable(data_type))
+            }
+        }
+    }
+
+    fn extract<T, D>(output: &DynOrtTensor<D>) -> Result<SerializableTensor>
+    where
+        T: SerializableElement,
+        D: Dimension,
+    {
+        let tensor = output.try_extract::<T>()?;
+        let view = tensor.view();
+        Ok(SerializableTensor::from_array(&*view))
+    }
+
+    /// The element type of the tensor
+    pub fn data_type(&self) -> TensorElementDataType {
+        match self {
+            SerializableTensor::Float { .. } => TensorElementDataType::Float,
+            SerializableTensor::Uint8 { .. } => TensorElementDataType::Uint8,
+            SerializableTensor::Int8 { .. } => TensorElementDataType::Int8,
+            SerializableTensor::Uint16 { .. } => TensorElementDataType::Uint16,
+            SerializableTensor::Int16 { .. } => TensorElementDataType::Int16,
+            SerializableTensor::Int32 { .. } => TensorElementDataType::Int32,
+            SerializableTensor::Int64 { .. } => TensorElementDataType::Int64,
+            SerializableTensor::String { .. } => TensorElementDataType::String,
+            SerializableTensor::Double { .. } => TensorElementDataType::Double,
+            SerializableTensor::Uint32 { .. } => TensorElementDataType::Uint32,
+            SerializableTensor::Uint64 { .. } => TensorElementDataType::Uint64,
+            SerializableTensor::Bool { .. } => TensorElementDataType::Bool,
+        }
+    }
+
+    /// The shape of the tensor
+    pub fn shape(&self) -> &[usize] {
+        match self {
+            SerializableTensor::Float { shape, .. }
+            | SerializableTensor::Uint8 { shape, .. }
+            | SerializableTensor::Int8 { shape, .. }
+            | SerializableTensor::Uint16 { shape, .. }
+            | SerializableTensor::Int16 { shape, .. }
+            | SerializableTensor::Int32 { shape, .. }
+            | SerializableTensor::Int64 { shape, .. }
+            | SerializableTensor::String { shape, .. }
+            | SerializableTensor::Double { shape, .. }
+            | SerializableTensor::Uint32 { shape, .. }
+            | SerializableTensor::Uint64 { shape, .. }
+            | SerializableTensor::Bool { shape, .. } => shape,
+        }
+    }
+
+    /// Move the elements into an array, ready to be passed to
+    /// [`Session::run()`](../../session/struct.Session.html#method.run)
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if `T` does not match the element type of the tensor, or if the
+    /// number of elements doesn't match its shape (which deserialization does not check).
+    pub fn into_array<T>(self) -> Result<Array<T, IxDyn>>
+    where
+        T: SerializableElement,
+    {
+        let (shape, data) = T::unwrap(self).map_err(|tensor| OrtError::DataTypeMismatch {
+            expected: T::tensor_element_data_type(),
+            actual: tensor.data_type(),
+        })?;
+        let element_count = data.len();
+        Array::from_shape_vec(IxDyn(&shape), data).map_err(|_| OrtError::ShapeMismatch {
+            shape,
+            element_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{arr1, arr2};
+    use test_env_log::test;
+
+    #[test]
+    fn numeric_round_trip() {
+        let array = arr2(&[[1.5_f32, -2.0, 3.25], [4.0, 0.0, 6.5]]);
+        let tensor = SerializableTensor::from_array(&array);
+        assert_eq!(tensor.data_type(), TensorElementDataType::Float);
+        assert_eq!(tensor.shape(), &[2, 3]);
+
+        let json = serde_json::to_value(&tensor).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "dtype": "float",
+                "shape": [2, 3],
+                "data": [1.5, -2.0, 3.25, 4.0, 0.0, 6.5],
+            })
+        );
+
+        let deserialized: SerializableTensor = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, tensor);
+        assert_eq!(deserialized.into_array::<f32>().unwrap(), array.into_dyn());
+    }
+
+    #[test]
+    fn transposed_array_is_serialized_in_standard_order() {
+        let array = arr2(&[[1_i64, 2], [3, 4]]);
+        let tensor = SerializableTensor::from_array(&array.t());
+        assert_eq!(
+            tensor,
+            SerializableTensor::Int64 {
+                shape: vec![2, 2],
+                data: vec![1, 3, 2, 4],
+            }
+        );
+    }
+
+    #[test]
+    fn string_round_trip() {
+        let array = arr1(&[String::from("hello"), String::from("world")]);
+        let json = serde_json::to_string(&SerializableTensor::from_array(&array)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"dtype":"string","shape":[2],"data":["hello","world"]}"#
+        );
+
+        let deserialized: SerializableTensor = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            deserialized.into_array::<String>().unwrap(),
+            array.into_dyn()
+        );
+    }
+
+    #[test]
+    fn into_array_errors() {
+        let tensor: SerializableTensor =
+            serde_json::from_str(r#"{"shape":[2],"dtype":"uint8","data":[1,2]}"#).unwrap();
+        assert!(matches!(
+            tensor.clone().into_array::<i8>(),
+            Err(OrtError::DataTypeMismatch {
+                expected: TensorElementDataType::Int8,
+                actual: TensorElementDataType::Uint8,
+            })
+        ));
+        assert_eq!(tensor.into_array::<u8>().unwrap(), arr1(&[1, 2]).into_dyn());
+
+        let tensor: SerializableTensor =
+            serde_json::from_str(r#"{"shape":[2,2],"dtype":"double","data":[1.0]}"#).unwrap();
+        assert!(matches!(
+            tensor.into_array::<f64>(),
+            Err(OrtError::ShapeMismatch {
+                element_count: 1,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! Module computing statistics over output tensors with [`ndarray-stats`](https://docs.rs/ndarray-stats)
+//!
+//! Enabled with the `stats` feature.
+
+use ndarray::{Array1, ArrayView, Axis, Dimension};
+use ndarray_stats::{errors::QuantileError, interpolate::Linear, QuantileExt};
+use noisy_float::types::n64;
+
+use crate::{OrtError, Result};
+
+/// Copy the elements of `view` into a flat array of `f64`
+fn flatten<T, D>(view: ArrayView<T, D>) -> Array1<f64>
+where
+    T: Copy + Into<f64>,
+    D: Dimension,
+{
+    view.iter().map(|value| (*value).into()).collect()
+}
+
+/// Mean of all the elements, or `None` if there are none
+pub(crate) fn mean<T, D>(view: ArrayView<T, D>) -> Option<f64>
+where
+    T: Copy + Into<f64>,
+    D: Dimension,
+{
+    flatten(view).mean()
+}
+
+/// Population standard deviation of all the elements, or `None` if there are none
+pub(crate) fn std<T, D>(view: ArrayView<T, D>) -> Option<f64>
+where
+    T: Copy + Into<f64>,
+    D: Dimension,
+{
+    if view.is_empty() {
+        return None;
+    }
+    Some(flatten(view).std(0.0))
+}
+
+/// The `q`-th percentile of all the elements, linearly interpolated and ignoring NaNs, or `None` if
+/// there are no elements
+pub(crate) fn percentile<T, D>(view: ArrayView<T, D>, q: f64) -> Result<Option<f64>>
+where
+    T: Copy + Into<f64>,
+    D: Dimension,
+{
+    // Also rejects NaN, which `n64()` panics on
+    if !(0.0..=100.0).contains(&q) {
+        return Err(OrtError::InvalidPercentile(q));
+    }
+
+    match flatten(view).quantile_axis_skipnan_mut(Axis(0), n64(q / 100.0), &Linear) {
+        Ok(percentile) => Ok(Some(percentile.into_scalar())),
+        Err(QuantileError::EmptyInput) => Ok(None),
+        Err(_) => Err(OrtError::InvalidPercentile(q)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{arr2, Array2};
+    use test_env_log::test;
+
+    #[test]
+    fn mean_and_std() {
+        let array = arr2(&[[2.0_f32, 4.0, 4.0, 4.0], [5.0, 5.0, 7.0, 9.0]]);
+        assert_eq!(mean(array.view()), Some(5.0));
+        assert_eq!(std(array.view()), Some(2.0));
+    }
+
+    #[test]
+    fn percentiles() {
+        let array = arr2(&[[1.0_f64, 2.0], [3.0, 4.0], [5.0, f64::NAN]]);
+        assert_eq!(percentile(array.view(), 0.0).unwrap(), Some(1.0));
+        assert_eq!(percentile(array.view(), 50.0).unwrap(), Some(3.0));
+        assert_eq!(percentile(array.view(), 75.0).unwrap(), Some(4.0));
+        assert_eq!(percentile(array.view(), 100.0).unwrap(), Some(5.0));
+        assert!(matches!(
+            percentile(array.view(), 101.0),
+            Err(OrtError::InvalidPercentile(_))
+        ));
+        assert!(matches!(
+            percentile(array.view(), f64::NAN),
+            Err(OrtError::InvalidPercentile(_))
+        ));
+    }
+
+    #[test]
+    fn empty() {
+        let array = Array2::<f32>::zeros((0, 3));
+        assert_eq!(mean(array.view()), None);
+        assert_eq!(std(array.view()), None);
+        assert_eq!(percentile(array.view(), 50.0).unwrap(), None);
+    }
+}
@@ -0,0 +1,231 @@
+//! Module containing a builder to fill large input tensors incrementally
+
+use std::fmt::Debug;
+
+use ndarray::ShapeBuilder;
+
+use crate::{
+    error::{OrtError, Result, TensorBuilderError},
+    tensor::{OrtTensor, TensorElementDataType, TypeToTensorElementDataType},
+};
+
+/// Builder filling an input tensor of a known shape, row by row
+///
+/// Producing a large input in a separate buffer before converting it into an
+/// [`ndarray::Array`](https://docs.rs/ndarray/latest/ndarray/type.Array.html) holds the data in memory
+/// twice. A `TensorBuilder` instead has ONNX Runtime allocate the tensor once, with its final
+/// shape, and lets rows (sub-arrays along the first axis) be written directly into its memory as
+/// they are produced.
+///
+/// The tensor is in standard (C) layout. Strings are not supported, ONNX Runtime managing the
+/// memory of string tensors itself.
+///
+/// # Example
+///
+/// ```
+/// # use onnxruntime::tensor::TensorBuilder;
+/// # fn main() -> onnxruntime::Result<()> {
+/// let mut builder = TensorBuilder::<f32, _>::new((2, 3))?;
+/// builder.write_row(1, &[4.0, 5.0, 6.0])?;
+/// builder.write_row(0, &[1.0, 2.0, 3.0])?;
+/// let tensor = builder.build()?;
+/// assert_eq!(tensor.view(), ndarray::arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TensorBuilder<T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone + 'static,
+    D: ndarray::Dimension,
+{
+    tensor: OrtTensor<'static, T, D>,
+    written: Vec<bool>,
+}
+
+impl<T, D> TensorBuilder<T, D>
+where
+    T: TypeToTensorElementDataType + Debug + Clone + 'static,
+    D: ndarray::Dimension,
+{
+    /// Allocate a tensor of the given shape, to be filled with rows
+    ///
+    /// Rows are indexed along the first axis. A tensor without dimensions (a scalar) has a single
+    /// row, of one element.
+    pub fn new<Sh>(shape: Sh) -> Result<TensorBuilder<T, D>>
+    where
+        Sh: ShapeBuilder<Dim = D>,
+    {
+        if T::tensor_element_data_type() == TensorElementDataType::String {
+            return Err(OrtError::TensorBuilder(TensorBuilderError::StringElements));
+        }
+
+        let shape = shape.into_shape().raw_dim().clone();
+        let rows = shape.slice().first().copied().unwrap_or(1);
+        let tensor = OrtTensor::allocate(shape)?;
+        Ok(TensorBuilder {
+            tensor,
+            written: vec![false; rows],
+        })
+    }
+
+    /// Number of rows of the tensor (length of its first axis)
+    pub fn rows(&self) -> usize {
+        self.written.len()
+    }
+
+    /// Number of elements in a single row
+    pub fn row_len(&self) -> usize {
+        self.tensor.shape().iter().skip(1).product()
+    }
+
+    /// Copy `data`, in standard (C) order, into the row at `index`
+    pub fn write_row(&mut self, index: usize, data: &[T]) -> Result<()> {
+        self.write_rows(index, data)
+    }
+
+    /// Copy `data`, in standard (C) order, into consecutive rows starting at `first_row`
+    ///
+    /// The length of `data` must be a multiple of [`row_len()`](#method.row_len).
+    pub fn write_rows(&mut self, first_row: usize, data: &[T]) -> Result<()> {
+        let row_len = self.row_len();
+        if row_len == 0 || !data.len().is_multiple_of(row_len) {
+            return Err(OrtError::TensorBuilder(TensorBuilderError::RowLength {
+                expected: row_len,
+                actual: data.len(),
+            }));
+        }
+
+        let row_count = data.len() / row_len;
+        if first_row + row_count > self.rows() {
+            return Err(OrtError::TensorBuilder(
+                TensorBuilderError::RowOutOfBounds {
+                    index: first_row + row_count - 1,
+                    rows: self.rows(),
+                },
+            ));
+        }
+
+        let start = first_row * row_len;
+        let memory = self
+            .tensor
+            .allocated_data_mut()
+            .expect("TensorBuilder tensor is allocated by ONNX Runtime");
+        memory[start..start + data.len()].clone_from_slice(data);
+        for written in &mut self.written[first_row..first_row + row_count] {
+            *written = true;
+        }
+
+        Ok(())
+    }
+
+    /// Finalize the tensor, once every row was written
+    pub fn build(self) -> Result<OrtTensor<'static, T, D>> {
+        let missing = self.written.iter().filter(|written| !**written).count();
+        if missing > 0 {
+            return Err(OrtError::TensorBuilder(TensorBuilderError::Incomplete {
+                missing,
+                rows: self.rows(),
+            }));
+        }
+
+        Ok(self.tensor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{error::call_ort, memory::MemoryInfo, tensor::OrtTensor, AllocatorType, MemType};
+    use ndarray::{arr0, arr2};
+    use std::{ffi, ptr};
+    use test_env_log::test;
+
+    #[test]
+    fn build_row_by_row_matches_from_array() {
+        let expected = arr2(&[[1_i32, 2, 3], [4, 5, 6], [7, 8, 9], [10, 11, 12]]);
+
+        let mut builder = TensorBuilder::<i32, _>::new((4, 3)).unwrap();
+        assert_eq!(builder.rows(), 4);
+        assert_eq!(builder.row_len(), 3);
+        for (index, row) in expected.outer_iter().enumerate() {
+            builder.write_row(index, row.as_slice().unwrap()).unwrap();
+        }
+        let from_builder = builder.build().unwrap();
+        assert!(!from_builder.is_borrowed());
+        assert_eq!(from_builder.view(), expected);
+
+        // Both paths provide the same data to the runtime
+        let memory_info = MemoryInfo::new(AllocatorType::Arena, MemType::Default).unwrap();
+        let from_array =
+            OrtTensor::from_array(&memory_info, ptr::null_mut(), expected.into()).unwrap();
+        assert_eq!(tensor_data(&from_builder), tensor_data(&from_array));
+    }
+
+    #[test]
+    fn build_from_chunks() {
+        let mut builder = TensorBuilder::<f32, _>::new((3, 2)).unwrap();
+        builder.write_rows(1, &[3.0, 4.0, 5.0, 6.0]).unwrap();
+        builder.write_rows(0, &[1.0, 2.0]).unwrap();
+        assert_eq!(
+            builder.build().unwrap().view(),
+            arr2(&[[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]])
+        );
+    }
+
+    #[test]
+    fn build_scalar() {
+        let mut builder = TensorBuilder::<i64, _>::new(()).unwrap();
+        assert_eq!(builder.rows(), 1);
+        assert_eq!(builder.row_len(), 1);
+        builder.write_row(0, &[42]).unwrap();
+        let tensor = builder.build().unwrap();
+        assert_eq!(tensor.shape(), &[] as &[usize]);
+        assert_eq!(tensor.view(), arr0(42));
+    }
+
+    #[test]
+    fn build_errors() {
+        assert!(matches!(
+            TensorBuilder::<String, _>::new((2, 2)),
+            Err(OrtError::TensorBuilder(TensorBuilderError::StringElements))
+        ));
+
+        let mut builder = TensorBuilder::<f32, _>::new((2, 2)).unwrap();
+        assert!(matches!(
+            builder.write_row(0, &[1.0, 2.0, 3.0]),
+            Err(OrtError::TensorBuilder(TensorBuilderError::RowLength {
+                expected: 2,
+                actual: 3
+            }))
+        ));
+        assert!(matches!(
+            builder.write_rows(1, &[1.0, 2.0, 3.0, 4.0]),
+            Err(OrtError::TensorBuilder(
+                TensorBuilderError::RowOutOfBounds { index: 2, rows: 2 }
+            ))
+        ));
+        builder.write_row(1, &[1.0, 2.0]).unwrap();
+        assert!(matches!(
+            builder.build(),
+            Err(OrtError::TensorBuilder(TensorBuilderError::Incomplete {
+                missing: 1,
+                rows: 2
+            }))
+        ));
+    }
+
+    fn tensor_data(tensor: &OrtTensor<i32, ndarray::Ix2>) -> Vec<i32> {
+        let mut data_ptr: *mut i32 = ptr::null_mut();
+        unsafe {
+            call_ort(|ort| {
+                ort.GetTensorMutableData.unwrap()(
+                    tensor.c_ptr,
+                    &mut data_ptr as *mut *mut i32 as *mut *mut ffi::c_void,
+                )
+            })
+        }
+        .unwrap();
+        unsafe { std::slice::from_raw_parts(data_ptr, tensor.element_count()) }.to_vec()
+    }
+}
@@ -0,0 +1,179 @@
+//! Module containing the options of the global thread pools
+//!
+//! By default, each session has its own intra-op and inter-op thread pools. An
+//! [`Environment`](../environment/struct.Environment.html) built with a
+//! [global thread pool](../environment/struct.EnvBuilder.html#method.with_global_thread_pool)
+//! instead shares the same thread pools between all its sessions, configured with
+//! [`ThreadingOptions`](struct.ThreadingOptions.html).
+
+use tracing::debug;
+
+use onnxruntime_sys as sys;
+
+use crate::{
+    error::{call_ort, OrtError, Result},
+    g_ort,
+};
+
+/// Options of the thread pools shared by all the sessions of an environment
+///
+/// Options which are not set keep ONNX Runtime's defaults.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::error::Error;
+/// # use onnxruntime::{environment::Environment, threading::ThreadingOptions};
+/// # fn main() -> Result<(), Box<dyn Error>> {
+/// // A batch job: don't burn CPU spinning while waiting for work
+/// let environment = Environment::builder()
+///     .with_global_thread_pool(
+///         ThreadingOptions::new()
+///             .with_intra_op_num_threads(4)
+///             .with_spinning(false),
+///     )
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ThreadingOptions {
+    intra_op_num_threads: Option<usize>,
+    inter_op_num_threads: Option<usize>,
+    spinning: Option<bool>,
+    denormal_as_zero: bool,
+}
+
+impl ThreadingOptions {
+    /// Create options keeping ONNX Runtime's defaults
+    pub fn new() -> ThreadingOptions {
+        ThreadingOptions::default()
+    }
+
+    /// Set the number of threads of the global intra-op thread pool (used to parallelize the
+    /// execution of a single node)
+    ///
+    /// 0 lets ONNX Runtime pick the number of threads from the number of cores.
+    pub fn with_intra_op_num_threads(mut self, num_threads: usize) -> ThreadingOptions {
+        self.intra_op_num_threads = Some(num_threads);
+        self
+    }
+
+    /// Set the number of threads of the global inter-op thread pool (used to execute
+    /// independent nodes in parallel, with the parallel execution mode)
+    ///
+    /// 0 lets ONNX Runtime pick the number of threads from the number of cores.
+    pub fn with_inter_op_num_threads(mut self, num_threads: usize) -> ThreadingOptions {
+        self.inter_op_num_threads = Some(num_threads);
+        self
+    }
+
+    /// Set whether the threads of the global thread pools spin while waiting for work
+    ///
+    /// Spinning threads pick new work up faster, lowering the latency (especially the tail
+    /// latency) of servers handling many requests, at the cost of burning CPU while idle. Batch
+    /// jobs, or programs sharing the machine with other work, are better off without spinning.
+    /// ONNX Runtime spins by default.
+    pub fn with_spinning(mut self, spinning: bool) -> ThreadingOptions {
+        self.spinning = Some(spinning);
+        self
+    }
+
+    /// Flush denormal (subnormal) floats to zero in the threads of the global thread pools
+    ///
+    /// Computations on denormals are much slower on most CPUs: models producing very small values
+    /// (for example in the tails of activations) can run noticeably faster with this flag. This
+    /// slightly changes numerical results, since the smallest values become zero. Disabled by
+    /// default.
+    pub fn with_denormal_as_zero(mut self, denormal_as_zero: bool) -> ThreadingOptions {
+        self.denormal_as_zero = denormal_as_zero;
+        self
+    }
+
+    /// Number of threads of the global intra-op thread pool, if set
+    pub fn intra_op_num_threads(&self) -> Option<usize> {
+        self.intra_op_num_threads
+    }
+
+    /// Number of threads of the global inter-op thread pool, if set
+    pub fn inter_op_num_threads(&self) -> Option<usize> {
+        self.inter_op_num_threads
+    }
+
+    /// Whether the threads of the global thread pools spin while waiting for work, if set
+    pub fn spinning(&self) -> Option<bool> {
+        self.spinning
+    }
+
+    /// Whether denormal floats are flushed to zero in the threads of the global thread pools
+    pub fn denormal_as_zero(&self) -> bool {
+        self.denormal_as_zero
+    }
+
+    /// Create the ONNX Runtime threading options, to be released with
+    /// `ReleaseThreadingOptions()` once the environment is created
+    pub(crate) fn to_ort(&self) -> Result<*mut sys::OrtThreadingOptions> {
+        debug!(options = ?self, "Creating threading options.");
+        let mut ptr: *mut sys::OrtThreadingOptions = std::ptr::null_mut();
+        unsafe { call_ort(|ort| ort.CreateThreadingOptions.unwrap()(&mut ptr)) }
+            .map_err(OrtError::ThreadingOptions)?;
+        assert_ne!(ptr, std::ptr::null_mut());
+
+        let result = self.configure(ptr);
+        if result.is_err() {
+            unsafe { g_ort().ReleaseThreadingOptions.unwrap()(ptr) };
+        }
+        result.map(|_| ptr)
+    }
+
+    fn configure(&self, ptr: *mut sys::OrtThreadingOptions) -> Result<()> {
+        if let Some(num_threads) = self.intra_op_num_threads {
+            unsafe {
+                call_ort(|ort| ort.SetGlobalIntraOpNumThreads.unwrap()(ptr, num_threads as i32))
+            }
+            .map_err(OrtError::ThreadingOptions)?;
+        }
+        if let Some(num_threads) = self.inter_op_num_threads {
+            unsafe {
+                call_ort(|ort| ort.SetGlobalInterOpNumThreads.unwrap()(ptr, num_threads as i32))
+            }
+            .map_err(OrtError::ThreadingOptions)?;
+        }
+        if let Some(spinning) = self.spinning {
+            unsafe { call_ort(|ort| ort.SetGlobalSpinControl.unwrap()(ptr, spinning as i32)) }
+                .map_err(OrtError::ThreadingOptions)?;
+        }
+        if self.denormal_as_zero {
+            unsafe { call_ort(|ort| ort.SetGlobalDenormalAsZero.unwrap()(ptr)) }
+                .map_err(OrtError::ThreadingOptions)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_env_log::test;
+
+    #[test]
+    fn threading_options() {
+        let options = ThreadingOptions::new();
+        assert_eq!(options.spinning(), None);
+        assert_eq!(options.intra_op_num_threads(), None);
+        assert!(!options.denormal_as_zero());
+
+        let options = options
+            .with_intra_op_num_threads(2)
+            .with_inter_op_num_threads(1)
+            .with_spinning(false)
+            .with_denormal_as_zero(true);
+        assert_eq!(options.intra_op_num_threads(), Some(2));
+        assert_eq!(options.inter_op_num_threads(), Some(1));
+        assert_eq!(options.spinning(), Some(false));
+        assert!(options.denormal_as_zero());
+
+        let ptr = options.to_ort().unwrap();
+        unsafe { g_ort().ReleaseThreadingOptions.unwrap()(ptr) };
+    }
+}
@@ -0,0 +1,11 @@
+//! Helpers shared by the integration tests
+
+use onnxruntime::{environment::Environment, LoggingLevel, Result};
+
+/// Environment of the tests, only logging warnings and errors
+pub fn environment() -> Result<Environment> {
+    Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .build()
+}
@@ -0,0 +1,69 @@
+use std::{
+    alloc::{self, Layout},
+    error::Error,
+    ffi,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use onnxruntime::{allocator::Allocator, environment::Environment, LoggingLevel};
+
+/// Size of the header storing the allocation size in front of each allocation
+const HEADER: usize = 16;
+
+#[derive(Debug)]
+struct CountingAllocator {
+    allocations: Arc<AtomicUsize>,
+}
+
+impl Allocator for CountingAllocator {
+    fn alloc(&self, size: usize) -> *mut ffi::c_void {
+        self.allocations.fetch_add(1, Ordering::SeqCst);
+        let layout = Layout::from_size_align(size + HEADER, HEADER).unwrap();
+        unsafe {
+            let ptr = alloc::alloc(layout);
+            if ptr.is_null() {
+                return ptr as *mut ffi::c_void;
+            }
+            *(ptr as *mut usize) = size;
+            ptr.add(HEADER) as *mut ffi::c_void
+        }
+    }
+
+    fn free(&self, ptr: *mut ffi::c_void) {
+        unsafe {
+            let ptr = (ptr as *mut u8).sub(HEADER);
+            let size = *(ptr as *mut usize);
+            alloc::dealloc(ptr, Layout::from_size_align(size + HEADER, HEADER).unwrap());
+        }
+    }
+}
+
+#[test]
+fn registered_allocator_is_used_during_run() -> Result<(), Box<dyn Error>> {
+    let environment = Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .build()?;
+
+    let allocations = Arc::new(AtomicUsize::new(0));
+    environment.register_allocator(CountingAllocator {
+        allocations: Arc::clone(&allocations),
+    })?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file("../test-models/tensorflow/unique_model.onnx")?;
+    let allocations_after_load = allocations.load(Ordering::SeqCst);
+
+    // String inputs are allocated through the session's allocator
+    let array = ndarray::Array::from(vec!["foo", "bar", "foo"]);
+    let outputs = session.run(vec![array])?;
+    assert_eq!(outputs.len(), 2);
+
+    assert!(allocations.load(Ordering::SeqCst) > allocations_after_load);
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+//! The global environment lives for the whole process: it is tested in its own test binary
+
+use std::error::Error;
+
+use onnxruntime::{environment::Environment, ndarray::array};
+
+#[test]
+fn global_environment_is_shared() -> Result<(), Box<dyn Error>> {
+    let first = Environment::global()?;
+    let second = std::thread::spawn(|| Environment::global().map(|env| env as *const _ as usize))
+        .join()
+        .unwrap()?;
+    assert_eq!(first as *const _ as usize, second);
+    assert!(std::ptr::eq(first, Environment::global()?));
+    assert_eq!(first.name(), "global");
+
+    // Other environments share the same underlying environment
+    let other = Environment::builder().with_name("other").build()?;
+    assert_eq!(other.name(), "global");
+
+    let session = first
+        .new_session_builder()?
+        .with_model_from_file("../test-models/onnx/optional_input.onnx")?;
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+//! The environment's thread pools are set when it is created: they are tested in their own test
+//! binary
+
+use std::{error::Error, sync::Mutex};
+
+use lazy_static::lazy_static;
+use onnxruntime::{
+    environment::Environment, ndarray::array, threading::ThreadingOptions, LoggingLevel,
+};
+
+lazy_static! {
+    // Each test needs its own environment: they must not be alive at the same time
+    static ref ENVIRONMENT_LOCK: Mutex<()> = Mutex::new(());
+}
+
+#[test]
+fn global_thread_pool_without_spinning() -> Result<(), Box<dyn Error>> {
+    let _lock = ENVIRONMENT_LOCK.lock().unwrap();
+    let environment = Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .with_global_thread_pool(
+            ThreadingOptions::new()
+                .with_intra_op_num_threads(2)
+                .with_inter_op_num_threads(1)
+                .with_spinning(false),
+        )
+        .build()?;
+    assert!(environment.has_global_thread_pool());
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file("../test-models/onnx/optional_input.onnx")?;
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn global_thread_pool_with_denormal_as_zero() -> Result<(), Box<dyn Error>> {
+    let _lock = ENVIRONMENT_LOCK.lock().unwrap();
+    let environment = Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .with_global_thread_pool(ThreadingOptions::new().with_denormal_as_zero(true))
+        .build()?;
+    assert!(environment.has_global_thread_pool());
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file("../test-models/onnx/optional_input.onnx")?;
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
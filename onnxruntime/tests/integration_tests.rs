@@ -14,6 +14,7 @@ mod download {
 
     use onnxruntime::tensor::ndarray_tensor::NdArrayTensor;
     use onnxruntime::{
+        allocator::OrtAllocator,
         download::vision::{DomainBasedImageClassification, ImageClassification},
         environment::Environment,
         tensor::{DynOrtTensor, OrtOwnedTensor},
@@ -30,7 +31,7 @@ mod download {
             .build()
             .unwrap();
 
-        let mut session = environment
+        let session = environment
             .new_session_builder()
             .unwrap()
             .with_optimization_level(GraphOptimizationLevel::Basic)
@@ -130,6 +131,41 @@ mod download {
         // }
     }
 
+    #[test]
+    fn squeezenet_run_with_allocator() {
+        let environment = Environment::builder()
+            .with_name("integration_test")
+            .with_log_level(LoggingLevel::Warning)
+            .build()
+            .unwrap();
+
+        let session = environment
+            .new_session_builder()
+            .unwrap()
+            .with_model_downloaded(ImageClassification::SqueezeNet)
+            .expect("Could not download model from file");
+
+        let array = ndarray::Array::linspace(0.0_f32, 1.0, 3 * 224 * 224)
+            .into_shape((1, 3, 224, 224))
+            .unwrap();
+
+        let expected: Vec<f32> = {
+            let outputs = session.run(vec![array.clone()]).unwrap();
+            let output: OrtOwnedTensor<f32, _> = outputs[0].try_extract().unwrap();
+            output.view().iter().cloned().collect()
+        };
+
+        let allocator = OrtAllocator::default_cpu().unwrap();
+        let outputs = session.run_with_allocator(vec![array], &allocator).unwrap();
+        let output: OrtOwnedTensor<f32, _> = outputs[0].try_extract().unwrap();
+
+        assert_eq!(output.view().shape(), [1, 1000]);
+        assert_eq!(
+            output.view().iter().cloned().collect::<Vec<f32>>(),
+            expected
+        );
+    }
+
     #[test]
     fn mnist_5() {
         const IMAGE_TO_LOAD: &str = "mnist_5.jpg";
@@ -140,7 +176,7 @@ mod download {
             .build()
             .unwrap();
 
-        let mut session = environment
+        let session = environment
             .new_session_builder()
             .unwrap()
             .with_optimization_level(GraphOptimizationLevel::Basic)
@@ -238,7 +274,7 @@ mod download {
             .build()
             .unwrap();
 
-        let mut session = environment
+        let session = environment
             .new_session_builder()
             .unwrap()
             .with_optimization_level(GraphOptimizationLevel::Basic)
@@ -0,0 +1,103 @@
+use std::error::Error;
+
+use onnxruntime::{
+    environment::Environment,
+    ndarray::array,
+    tensor::{ort_owned_tensor::TensorExtractError, TensorElementDataType, ValueType},
+    LoggingLevel,
+};
+
+const LOGISTIC_REGRESSION_MODEL: &str = "../test-models/sklearn/logistic_regression.onnx";
+const RAGGED_SEQUENCE_MODEL: &str = "../test-models/onnx/ragged_sequence.onnx";
+
+#[test]
+fn sequence_of_maps_output() -> Result<(), Box<dyn Error>> {
+    let environment = Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .build()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(LOGISTIC_REGRESSION_MODEL)?;
+
+    assert_eq!(session.outputs[0].value_type, ValueType::Tensor);
+    assert_eq!(session.outputs[1].value_type, ValueType::Sequence);
+    assert_eq!(session.outputs[1].output_type, TensorElementDataType::Float);
+    assert!(session.outputs[1].dimensions.is_empty());
+
+    let input = array![[0.25_f32, 0.75], [0.5, 0.125]];
+    let outputs = session.run(vec![input])?;
+
+    assert_eq!(outputs[0].value_type(), ValueType::Tensor);
+    assert_eq!(outputs[1].value_type(), ValueType::Sequence);
+
+    let labels = outputs[0].try_extract::<i64>()?;
+    assert_eq!(labels.view().to_owned(), array![1_i64, 0].into_dyn());
+
+    // A sequence can't be extracted as a tensor
+    assert!(matches!(
+        outputs[1].try_extract::<f32>(),
+        Err(TensorExtractError::ValueTypeMismatch {
+            actual: ValueType::Sequence,
+            requested: ValueType::Tensor,
+        })
+    ));
+
+    let probabilities = outputs[1].try_extract_sequence()?;
+    assert_eq!(probabilities.len(), 2);
+
+    assert_eq!(probabilities.get(0).unwrap().value_type(), ValueType::Map);
+
+    let maps = probabilities.try_extract_maps::<i64, f32>()?;
+    assert_eq!(maps[0].len(), 2);
+    assert_eq!(maps[0][&0], 0.25);
+    assert_eq!(maps[0][&1], 0.75);
+    assert_eq!(maps[1][&0], 0.5);
+    assert_eq!(maps[1][&1], 0.125);
+
+    // Keys are int64
+    assert!(matches!(
+        probabilities
+            .get(0)
+            .unwrap()
+            .try_extract_map::<String, f32>(),
+        Err(TensorExtractError::DataTypeMismatch { .. })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn sequence_of_tensors_output() -> Result<(), Box<dyn Error>> {
+    let environment = Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .build()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(RAGGED_SEQUENCE_MODEL)?;
+
+    assert_eq!(session.outputs[0].value_type, ValueType::Sequence);
+
+    let input = array![1.0_f32, 2.0, 3.0, 4.0];
+    let outputs = session.run(vec![input])?;
+
+    let parts = outputs[0].try_extract_sequence()?.into_tensors();
+    assert_eq!(parts.len(), 2);
+
+    // The elements have different shapes
+    let first = parts[0].try_extract::<f32>()?;
+    assert_eq!(first.view().shape(), &[1]);
+    assert_eq!(first.view().to_owned(), array![1.0_f32].into_dyn());
+
+    let second = parts[1].try_extract::<f32>()?;
+    assert_eq!(second.view().shape(), &[3]);
+    assert_eq!(
+        second.view().to_owned(),
+        array![2.0_f32, 3.0, 4.0].into_dyn()
+    );
+
+    Ok(())
+}
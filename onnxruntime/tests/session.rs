@@ -0,0 +1,1845 @@
+mod common;
+
+use std::{
+    collections::HashMap,
+    error::Error,
+    io::{self, Cursor, Read},
+    time::Duration,
+};
+
+use onnxruntime::{
+    environment::Environment,
+    initializer::Initializers,
+    ndarray::{array, s, Array, Axis},
+    run_options::{CancellationToken, RunOptions},
+    session::{LossyCastPolicy, MinimalCompatibility, Session},
+    tensor::{OrtTensor, TensorElementDataType},
+    LoggingLevel, OrtError, OrtErrorCode,
+};
+
+use common::environment;
+
+const UNIQUE_MODEL: &str = "../test-models/tensorflow/unique_model.onnx";
+const OPTIONAL_INPUT_MODEL: &str = "../test-models/onnx/optional_input.onnx";
+const FIXED_BATCH_MODEL: &str = "../test-models/onnx/fixed_batch.onnx";
+const COUNTER_MODEL: &str = "../test-models/onnx/counter.onnx";
+const UNUSED_INITIALIZER_MODEL: &str = "../test-models/onnx/unused_initializer.onnx";
+const DENOTATIONS_MODEL: &str = "../test-models/onnx/denotations.onnx";
+const TOKEN_IDS_MODEL: &str = "../test-models/onnx/token_ids.onnx";
+const STATIC_AND_DYNAMIC_OUTPUTS_MODEL: &str =
+    "../test-models/onnx/static_and_dynamic_outputs.onnx";
+const MASK_MODEL: &str = "../test-models/onnx/mask.onnx";
+const MASKED_MODEL: &str = "../test-models/onnx/masked.onnx";
+const CONV_RELU_MODEL: &str = "../test-models/onnx/conv_relu.onnx";
+const CUSTOM_METADATA_MODEL: &str = "../test-models/onnx/custom_metadata.onnx";
+#[cfg(feature = "gzip")]
+const GZIP_MODEL: &str = "../test-models/onnx/optional_input.onnx.gz";
+#[cfg(feature = "zstd")]
+const ZSTD_MODEL: &str = "../test-models/onnx/optional_input.onnx.zst";
+#[cfg(feature = "complex")]
+const COMPLEX_IDENTITY_MODEL: &str = "../test-models/onnx/complex_identity.onnx";
+#[cfg(feature = "bundle")]
+const BUNDLE_DIR: &str = "../test-models/onnx/bundle";
+
+#[test]
+fn model_versions() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(UNIQUE_MODEL)?;
+
+    assert_eq!(session.ir_version(), Some(6));
+    assert_eq!(session.opset_version(""), Some(11));
+    assert_eq!(session.opset_version("ai.onnx"), Some(11));
+    assert_eq!(session.opset_version("ai.onnx.ml"), None);
+
+    let metadata = session.metadata()?;
+    assert_eq!(metadata.producer_name()?, "tf2onnx");
+    assert_eq!(metadata.graph_name()?, "tf2onnx");
+
+    Ok(())
+}
+
+#[test]
+fn custom_metadata() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(CUSTOM_METADATA_MODEL)?;
+    let metadata = session.metadata()?;
+    assert_eq!(
+        metadata.custom("mean")?.as_deref(),
+        Some("[0.485, 0.456, 0.406]")
+    );
+    assert_eq!(metadata.custom("missing")?, None);
+
+    let map = metadata.custom_map()?;
+    assert_eq!(map.len(), 3);
+    assert_eq!(map["mean"], "[0.485, 0.456, 0.406]");
+    assert_eq!(map["std"], "[0.229, 0.224, 0.225]");
+    assert_eq!(map["labels"], "cat,dog");
+
+    // Models without custom metadata have an empty map
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    assert!(session.metadata()?.custom_map()?.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn model_versions_from_memory() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_memory(std::fs::read(UNIQUE_MODEL)?)?;
+
+    assert_eq!(session.ir_version(), Some(6));
+    assert_eq!(session.opset_version(""), Some(11));
+
+    Ok(())
+}
+
+#[test]
+fn cloned_session_runs_concurrently() -> Result<(), Box<dyn Error>> {
+    // Threads need a `'static` environment to borrow from
+    let environment: &'static Environment = Box::leak(Box::new(environment()?));
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(UNIQUE_MODEL)?;
+
+    let handles: Vec<_> = (0..2)
+        .map(|_| {
+            let session = session.clone();
+            std::thread::spawn(move || {
+                for _ in 0..10 {
+                    let array = ndarray::Array::from(vec!["foo", "bar", "foo", "baz"]);
+                    let outputs = session.run(vec![array]).unwrap();
+                    let strings = outputs[1].try_extract::<String>().unwrap();
+                    assert_eq!(
+                        strings.view().iter().collect::<Vec<_>>(),
+                        vec!["foo", "bar", "baz"]
+                    );
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("inference thread panicked");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn model_from_reader() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let reader = Cursor::new(std::fs::read(UNIQUE_MODEL)?);
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_reader(reader)?;
+
+    assert_eq!(session.inputs.len(), 1);
+    assert_eq!(session.ir_version(), Some(6));
+
+    Ok(())
+}
+
+struct FailingReader;
+
+impl Read for FailingReader {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::new(
+            io::ErrorKind::ConnectionReset,
+            "connection reset",
+        ))
+    }
+}
+
+#[test]
+fn model_from_failing_reader() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let result = environment
+        .new_session_builder()?
+        .with_model_from_reader(FailingReader);
+
+    assert!(matches!(result, Err(OrtError::ModelRead(_))));
+
+    Ok(())
+}
+
+#[test]
+fn optional_input() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    assert_eq!(session.inputs.len(), 1);
+    assert_eq!(session.inputs[0].name, "x");
+    assert_eq!(session.optional_inputs.len(), 1);
+    assert_eq!(session.optional_inputs[0].name, "y");
+
+    // Without the optional input, its default value is used
+    let mut inputs = HashMap::new();
+    inputs.insert("x", array![1.0_f32, 2.0, 3.0]);
+    let outputs = session.run_with_inputs(inputs)?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    // With the optional input
+    let mut inputs = HashMap::new();
+    inputs.insert("x", array![1.0_f32, 2.0, 3.0]);
+    inputs.insert("y", array![1.0_f32, 1.0, 1.0]);
+    let outputs = session.run_with_inputs(inputs)?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![2.0_f32, 3.0, 4.0].into_dyn());
+
+    // Unknown inputs are rejected
+    let mut inputs = HashMap::new();
+    inputs.insert("w", array![1.0_f32, 2.0, 3.0]);
+    assert!(matches!(
+        session.run_with_inputs(inputs),
+        Err(OrtError::UnknownInput { .. })
+    ));
+
+    // Required inputs can't be omitted
+    let mut inputs = HashMap::new();
+    inputs.insert("y", array![1.0_f32, 1.0, 1.0]);
+    assert!(matches!(
+        session.run_with_inputs(inputs),
+        Err(OrtError::MissingInput { name }) if name == "x"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn optional_input_positional() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    // `None` for the optional input uses its default value
+    let outputs = session.run_with_optional_inputs(vec![Some(array![1.0_f32, 2.0, 3.0]), None])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    let outputs = session.run_with_optional_inputs(vec![
+        Some(array![1.0_f32, 2.0, 3.0]),
+        Some(array![1.0_f32, 1.0, 1.0]),
+    ])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![2.0_f32, 3.0, 4.0].into_dyn());
+
+    // Required inputs can't be omitted
+    assert!(matches!(
+        session.run_with_optional_inputs(vec![None, Some(array![1.0_f32, 1.0, 1.0])]),
+        Err(OrtError::MissingInput { .. })
+    ));
+    assert!(matches!(
+        session.run_with_optional_inputs(vec![
+            None::<Array<f32, onnxruntime::ndarray::Ix1>>,
+            None,
+            None
+        ]),
+        Err(OrtError::TooManyInputs {
+            given: 3,
+            model_input_count: 2
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_stats() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(UNIQUE_MODEL)?;
+
+    let array = ndarray::Array::from(vec!["foo", "bar", "foo", "baz"]);
+    let (outputs, stats) = session.run_with_stats(vec![array])?;
+    assert_eq!(outputs.len(), 2);
+
+    assert!(stats.duration > Duration::from_nanos(0));
+    assert!(stats.duration < Duration::from_secs(60));
+
+    Ok(())
+}
+
+#[test]
+fn deterministic_compute() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let result = environment
+        .new_session_builder()?
+        .with_deterministic_compute(true);
+    assert!(matches!(result, Err(OrtError::UnsupportedByOrtVersion(_))));
+
+    let session = environment
+        .new_session_builder()?
+        .with_deterministic_compute(false)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let run = || -> Result<Vec<f32>, Box<dyn Error>> {
+        let outputs = session.run(vec![array![0.1_f32, 0.2, 0.3]])?;
+        let z = outputs[0].try_extract::<f32>()?;
+        Ok(z.view().iter().cloned().collect())
+    };
+    assert_eq!(run()?, run()?);
+
+    Ok(())
+}
+
+#[test]
+fn run_batched() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+    assert_eq!(session.inputs[0].dimensions, [Some(4), Some(3)]);
+
+    let input = Array::from_shape_fn((10, 3), |(i, j)| (i * 3 + j) as f32);
+    let outputs = session.run_batched(input.view(), 4)?;
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(outputs[0].shape(), &[10, 3]);
+
+    // Each sample gives the same output as when run on its own (padded to a full batch)
+    for (i, sample) in input.outer_iter().enumerate() {
+        let mut batch = Array::zeros((4, 3));
+        batch.index_axis_mut(Axis(0), 0).assign(&sample);
+        let sample_outputs = session.run(vec![batch])?;
+        let sample_output = sample_outputs[0].try_extract::<f32>()?;
+        assert_eq!(
+            outputs[0].slice(s![i, ..]),
+            sample_output.view().slice(s![0, ..])
+        );
+    }
+    assert_eq!(
+        outputs[0].slice(s![9, ..]),
+        array![27.0_f32, 28.0 * 2.0, 29.0 * 3.0]
+    );
+
+    // Without any sample, the (padded) input is still run for the outputs to have their shape
+    let outputs = session.run_batched(Array::<f32, _>::zeros((0, 3)).view(), 4)?;
+    assert_eq!(outputs[0].shape(), &[0, 3]);
+
+    assert!(matches!(
+        session.run_batched(ndarray::arr0(1.0_f32).view(), 4),
+        Err(OrtError::BatchScalarInput)
+    ));
+    assert!(matches!(
+        session.run_batched(input.view(), 5),
+        Err(OrtError::BatchSizeTooLarge {
+            batch_size: 5,
+            fixed_batch_size: 4,
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn warm_up() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    session.warm_up(&[array![1.0_f32, 2.0, 3.0]], 3)?;
+    session.warm_up_with_zeros(3)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn ort_format_model() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    // Have ONNX Runtime convert the model to the ORT format
+    let ort_model = std::env::temp_dir().join("onnxruntime-rs-optional_input.ort");
+    let onnx_session = environment
+        .new_session_builder()?
+        .with_optimized_model_path(&ort_model)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    assert!(ort_model.exists());
+
+    let ort_session = environment
+        .new_session_builder()?
+        .with_model_from_file(ort_model.clone())?;
+    assert_eq!(ort_session.ir_version(), None);
+    let ort_memory_session = environment
+        .new_session_builder()?
+        .with_model_from_memory(std::fs::read(&ort_model)?)?;
+
+    let run = |session: &onnxruntime::session::Session| -> Result<Vec<f32>, Box<dyn Error>> {
+        let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+        let z = outputs[0].try_extract::<f32>()?;
+        Ok(z.view().iter().cloned().collect())
+    };
+    assert_eq!(run(&ort_session)?, run(&onnx_session)?);
+    assert_eq!(run(&ort_memory_session)?, run(&onnx_session)?);
+
+    std::fs::remove_file(ort_model)?;
+
+    Ok(())
+}
+
+#[test]
+fn minimal_compatibility() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let model_bytes = std::fs::read(OPTIONAL_INPUT_MODEL)?;
+    let compatibility = Session::check_minimal_compatibility(&environment, &model_bytes)?;
+    assert_eq!(compatibility, MinimalCompatibility::Compatible);
+    assert!(compatibility.is_compatible());
+
+    Ok(())
+}
+
+#[test]
+fn disabled_optimizers() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_disabled_optimizers(&["ConstantFolding", "CommonSubexpressionElimination"])?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn run_iter() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(COUNTER_MODEL)?;
+
+    let mut updates = 0;
+    let counts = session
+        .run_iter(vec![array![0_i64].into_dyn()], |outputs| {
+            updates += 1;
+            let next = outputs[0].try_extract::<i64>()?.view().to_owned();
+            Ok(if next[0] < 5 { Some(vec![next]) } else { None })
+        })
+        .map(|outputs| -> Result<i64, Box<dyn Error>> {
+            Ok(outputs?[0].try_extract::<i64>()?.view()[0])
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(counts, vec![1, 2, 3, 4, 5]);
+    assert_eq!(updates, 5);
+
+    // The loop can also be bounded by the caller
+    let steps = session
+        .run_iter(vec![array![0_i64].into_dyn()], |outputs| {
+            Ok(Some(vec![outputs[0]
+                .try_extract::<i64>()?
+                .view()
+                .to_owned()]))
+        })
+        .take(3)
+        .count();
+    assert_eq!(steps, 3);
+
+    Ok(())
+}
+
+#[test]
+fn log_levels() -> Result<(), Box<dyn Error>> {
+    let levels = [
+        LoggingLevel::Verbose,
+        LoggingLevel::Info,
+        LoggingLevel::Warning,
+        LoggingLevel::Error,
+        LoggingLevel::Fatal,
+    ];
+    let environment = environment()?;
+
+    for &session_level in &levels {
+        let session = environment
+            .new_session_builder()?
+            .with_log_level(session_level)?
+            .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+        for &run_level in &levels {
+            let mut run_options = RunOptions::new()?;
+            run_options.set_log_level(run_level)?;
+
+            let outputs =
+                session.run_with_options(vec![array![1.0_f32, 2.0, 3.0]], &run_options)?;
+            let z = outputs[0].try_extract::<f32>()?;
+            assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn copy_output_into_slice() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let mut buffer = vec![0.0_f32; 3];
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    outputs[0]
+        .try_extract::<f32>()?
+        .copy_into_slice(&mut buffer)?;
+    assert_eq!(buffer, vec![11.0_f32, 22.0, 33.0]);
+
+    Ok(())
+}
+
+#[test]
+fn run_cow() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    let expected = array![11.0_f32, 22.0, 33.0];
+
+    // Owned
+    let outputs = session.run_cow(vec![array![1.0_f32, 2.0, 3.0].into()])?;
+    assert_eq!(
+        *outputs[0].try_extract::<f32>()?.view(),
+        expected.view().into_dyn()
+    );
+
+    // Contiguous view, read in place
+    let buffer = array![[0.0_f32, 0.0, 0.0], [1.0, 2.0, 3.0]];
+    let outputs = session.run_cow(vec![buffer.row(1).into()])?;
+    assert_eq!(
+        *outputs[0].try_extract::<f32>()?.view(),
+        expected.view().into_dyn()
+    );
+
+    // Strided view, copied
+    let buffer = array![1.0_f32, 0.0, 2.0, 0.0, 3.0];
+    let outputs = session.run_cow(vec![buffer.slice(s![..;2]).into()])?;
+    assert_eq!(
+        *outputs[0].try_extract::<f32>()?.view(),
+        expected.view().into_dyn()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn strict_load() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    // Without strict mode, the warning is only logged
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(UNUSED_INITIALIZER_MODEL)?;
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let y = outputs[0].try_extract::<f32>()?;
+    assert_eq!(y.view().as_slice(), Some(&[2.0_f32, 3.0, 4.0][..]));
+
+    let result = environment
+        .new_session_builder()?
+        .with_strict_load()?
+        .with_model_from_file(UNUSED_INITIALIZER_MODEL);
+    match result {
+        Err(OrtError::StrictLoadWarning(warnings)) => {
+            assert!(warnings.iter().any(|warning| warning.contains("unused")));
+        }
+        other => panic!("Expected a strict load error, got {:?}", other.map(|_| ())),
+    }
+
+    // Models loading cleanly are unaffected
+    environment
+        .new_session_builder()?
+        .with_strict_load()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    Ok(())
+}
+
+#[test]
+fn model_summary() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+
+    let summary = session.model_summary();
+    assert_eq!(summary.input_count, 1);
+    assert_eq!(summary.optional_input_count, 0);
+    assert_eq!(summary.output_count, 1);
+    assert_eq!(summary.node_count, Some(1));
+    assert_eq!(summary.initializer_count, Some(1));
+
+    Ok(())
+}
+
+#[test]
+fn output_shapes() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(STATIC_AND_DYNAMIC_OUTPUTS_MODEL)?;
+
+    assert_eq!(
+        session.output_shapes(),
+        vec![vec![None, Some(3)], vec![Some(1), Some(1)]]
+    );
+
+    let outputs = session.run(vec![array![[1.0_f32, -2.0, 3.0], [4.0, 5.0, -6.0]]])?;
+    assert_eq!(outputs[0].try_extract::<f32>()?.view().shape(), &[2, 3]);
+    let total = outputs[1].try_extract::<f32>()?;
+    assert_eq!(total.view().shape(), &[1, 1]);
+    assert_eq!(total.view()[[0, 0]], 5.0);
+
+    Ok(())
+}
+
+#[test]
+fn input_denotations() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(DENOTATIONS_MODEL)?;
+
+    let denotations = session.input_denotations("x").expect("denotations");
+    assert_eq!(
+        denotations,
+        &[
+            Some(String::from("DATA_BATCH")),
+            Some(String::from("DATA_CHANNEL")),
+            Some(String::from("DATA_FEATURE")),
+            None
+        ]
+    );
+    assert_eq!(session.input_denotations("y"), None);
+
+    // Models without denotations
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+    assert_eq!(session.input_denotations("x"), Some(&[None, None][..]));
+
+    Ok(())
+}
+
+#[test]
+fn run_ids() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(TOKEN_IDS_MODEL)?;
+
+    let outputs = session.run_ids(&[101, 7592, 102, 0], &[1, 1, 1, 0], Some(&[0, 0, 1, 1]))?;
+    let output = outputs[0].try_extract::<i64>()?;
+    assert_eq!(output.view().shape(), &[1, 4]);
+    assert_eq!(
+        output.view().iter().cloned().collect::<Vec<_>>(),
+        vec![101, 7592, 103, 1]
+    );
+
+    // Without token type ids, the model's default is used
+    let outputs = session.run_ids(&[101, 102], &[1, 1], None)?;
+    let output = outputs[0].try_extract::<i64>()?;
+    assert_eq!(output.view().shape(), &[1, 2]);
+    assert_eq!(
+        output.view().iter().cloned().collect::<Vec<_>>(),
+        vec![101, 102]
+    );
+
+    assert!(matches!(
+        session.run_ids(&[101, 102], &[1], None),
+        Err(OrtError::IdsLengthMismatch {
+            name: "attention_mask",
+            expected: 2,
+            actual: 1
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn session_outlives_environment_handle() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let handle = environment.clone();
+
+    let session = handle
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    // The session keeps the environment alive on its own
+    drop(environment);
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+    drop(outputs);
+
+    drop(session);
+    drop(handle);
+
+    Ok(())
+}
+
+#[test]
+fn initializers() -> Result<(), Box<dyn Error>> {
+    let initializers = Initializers::from_file(FIXED_BATCH_MODEL)?;
+    assert_eq!(initializers.names(), vec!["w"]);
+    assert_eq!(
+        initializers.data_type("w"),
+        Some(TensorElementDataType::Float)
+    );
+    assert_eq!(initializers.shape("w"), Some(&[3_i64][..]));
+
+    let w = initializers.extract::<f32>("w")?;
+    assert_eq!(w.shape(), &[3]);
+    assert_eq!(w.iter().cloned().collect::<Vec<_>>(), vec![1.0, 2.0, 3.0]);
+
+    assert!(matches!(
+        initializers.extract::<i64>("w"),
+        Err(OrtError::InitializerDataType { .. })
+    ));
+    assert!(matches!(
+        initializers.extract::<f32>("x"),
+        Err(OrtError::InitializerNotFound(_))
+    ));
+
+    let initializers = Initializers::from_memory(&std::fs::read(TOKEN_IDS_MODEL)?)?;
+    let token_type_ids = initializers.extract::<i64>("token_type_ids")?;
+    assert_eq!(token_type_ids, array![[0_i64]].into_dyn());
+
+    Ok(())
+}
+
+#[test]
+fn fixed_batch() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_fixed_batch(2)?
+        .with_model_from_file(STATIC_AND_DYNAMIC_OUTPUTS_MODEL)?;
+    assert_eq!(session.inputs[0].dimensions, vec![Some(2), Some(3)]);
+
+    let outputs = session.run(vec![array![[1.0_f32, -2.0, 3.0], [4.0, 5.0, -6.0]]])?;
+    let y = outputs[0].try_extract::<f32>()?;
+    assert_eq!(y.view().shape(), &[2, 3]);
+    assert_eq!(
+        y.view().iter().cloned().collect::<Vec<_>>(),
+        vec![1.0, 0.0, 3.0, 4.0, 5.0, 0.0]
+    );
+
+    // Other batch sizes are rejected
+    assert!(session.run(vec![array![[1.0_f32, 2.0, 3.0]]]).is_err());
+
+    // Models without a dynamic batch are unaffected
+    let session = environment
+        .new_session_builder()?
+        .with_fixed_batch(2)?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+    assert_eq!(session.inputs[0].dimensions, vec![Some(4), Some(3)]);
+
+    Ok(())
+}
+
+#[test]
+fn output_layout() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(STATIC_AND_DYNAMIC_OUTPUTS_MODEL)?;
+
+    let outputs = session.run(vec![array![[1.0_f32, -2.0, 3.0], [4.0, 5.0, -6.0]]])?;
+    let y = outputs[0].try_extract::<f32>()?;
+    assert!(y.is_standard_layout());
+    assert_eq!(y.strides(), vec![3, 1]);
+    assert_eq!(y.as_slice(), Some(&[1.0_f32, 0.0, 3.0, 4.0, 5.0, 0.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn cpu_without_arena() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_cpu(false)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn slice_output() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+
+    let input = Array::from_shape_fn((4, 3), |(i, j)| (i * 3 + j) as f32);
+    let outputs = session.run(vec![input])?;
+    let output = outputs[0].try_extract::<f32>()?;
+
+    // Each row is multiplied by the weights [1, 2, 3]
+    let crop = output.slice(s![1..3, 1..]);
+    assert_eq!(crop, array![[8.0_f32, 15.0], [14.0, 24.0]]);
+    assert_eq!(output.slice(s![3, ..]), array![9.0_f32, 20.0, 33.0]);
+
+    Ok(())
+}
+
+#[test]
+fn optimization_cache() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let temp_dir = std::env::temp_dir();
+    let model = temp_dir.join("onnxruntime-rs-cached_model.onnx");
+    let cache = temp_dir.join("onnxruntime-rs-cached_model.optimized.onnx");
+    let cache_hash = temp_dir.join("onnxruntime-rs-cached_model.optimized.onnx.hash");
+    for path in &[&cache, &cache_hash] {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    std::fs::copy(OPTIONAL_INPUT_MODEL, &model)?;
+
+    let run = |environment: &Environment| -> Result<Vec<f32>, Box<dyn Error>> {
+        let session = environment
+            .new_session_builder()?
+            .with_optimization_cache(&cache)?
+            .with_model_from_file(model.clone())?;
+        let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+        let y = outputs[0].try_extract::<f32>()?;
+        Ok(y.view().iter().cloned().collect())
+    };
+
+    // The first load creates the cache
+    assert_eq!(run(&environment)?, vec![11.0, 22.0, 33.0]);
+    assert!(cache.exists());
+    assert!(cache_hash.exists());
+
+    // The next loads use the cache: replacing its content shows in the results (y = x + 1)
+    std::fs::copy(UNUSED_INITIALIZER_MODEL, &cache)?;
+    assert_eq!(run(&environment)?, vec![2.0, 3.0, 4.0]);
+
+    // Changing the model invalidates the cache, which is rebuilt
+    let hash = std::fs::read_to_string(&cache_hash)?;
+    std::fs::copy(UNUSED_INITIALIZER_MODEL, &model)?;
+    assert_eq!(run(&environment)?, vec![2.0, 3.0, 4.0]);
+    assert_ne!(std::fs::read_to_string(&cache_hash)?, hash);
+
+    for path in &[&model, &cache, &cache_hash] {
+        std::fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bind_output_to_ptr() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::memory::MemoryInfo;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let memory_info = MemoryInfo::for_device("Cpu", 0)?;
+    let mut z = vec![0.0_f32; 3];
+    let mut binding = session.io_binding()?;
+    binding.bind_input("x", array![1.0_f32, 2.0, 3.0])?;
+    unsafe {
+        binding.bind_output_to_ptr(
+            "z",
+            &memory_info,
+            z.as_mut_ptr() as *mut std::ffi::c_void,
+            &[3],
+            TensorElementDataType::Float,
+        )?;
+    }
+    session.run_with_binding(&binding)?;
+
+    // The size of the buffer would overflow: rejected before ONNX Runtime sees the pointer
+    let result = unsafe {
+        binding.bind_output_to_ptr(
+            "z",
+            &memory_info,
+            z.as_mut_ptr() as *mut std::ffi::c_void,
+            &[usize::MAX / 2, 3],
+            TensorElementDataType::Float,
+        )
+    };
+    assert!(matches!(result, Err(OrtError::InvalidTensorShape { .. })));
+
+    drop(binding);
+    assert_eq!(z, vec![11.0, 22.0, 33.0]);
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn run_serializable() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::tensor::serde_tensor::SerializableTensor;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run_serializable(vec![array![1.0_f32, 2.0, 3.0]])?;
+    assert_eq!(
+        serde_json::to_string(&outputs)?,
+        r#"[{"dtype":"float","shape":[3],"data":[11.0,22.0,33.0]}]"#
+    );
+
+    // A deserialized tensor can be fed back as an input
+    let inputs: Vec<SerializableTensor> =
+        serde_json::from_str(r#"[{"dtype":"float","shape":[3],"data":[1.0,0.5,0.0]}]"#)?;
+    let inputs = inputs
+        .into_iter()
+        .map(|input| input.into_array::<f32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    let outputs = session.run_serializable(inputs)?;
+    assert_eq!(
+        outputs,
+        vec![SerializableTensor::Float {
+            shape: vec![3],
+            data: vec![11.0, 5.5, 0.0],
+        }]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn custom_ops_library_not_found() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let result = environment
+        .new_session_builder()?
+        .with_custom_ops_library("does_not_exist.so");
+    assert!(matches!(result, Err(OrtError::FileDoesNotExists { .. })));
+
+    Ok(())
+}
+
+#[test]
+fn error_code() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    // The input is a float tensor
+    let result = session.run(vec![array![1_i64, 2, 3]]);
+    match result {
+        Err(OrtError::Run(error)) => {
+            assert_eq!(error.code(), Some(OrtErrorCode::InvalidArgument))
+        }
+        other => panic!(
+            "Unexpected result: {:?}",
+            other.map(|outputs| outputs.len())
+        ),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn one_hot_input() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::tensor::ndarray_tensor::one_hot;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+
+    // Each row of the output selects one of the weights [1, 2, 3]
+    let outputs = session.run(vec![one_hot(&[2, 0, 1, 2], 3)?])?;
+    let output = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        output.view().to_owned(),
+        array![
+            [0.0_f32, 0.0, 3.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 2.0, 0.0],
+            [0.0, 0.0, 3.0]
+        ]
+        .into_dyn()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn bool_input_and_output() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(MASK_MODEL)?;
+
+    assert_eq!(session.inputs[0].input_type, TensorElementDataType::Bool);
+    assert_eq!(session.outputs[0].output_type, TensorElementDataType::Bool);
+
+    let outputs = session.run(vec![array![true, false, false, true]])?;
+    let inverted = outputs[0].try_extract::<bool>()?;
+    assert_eq!(
+        inverted.view().as_slice(),
+        Some(&[false, true, true, false][..])
+    );
+
+    Ok(())
+}
+
+#[test]
+fn memory_limit() -> Result<(), Box<dyn Error>> {
+    const LIMIT: usize = 64 * 1024 * 1024;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_memory_limit(LIMIT)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    // A small model runs well within the limit
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    // Other sessions share the environment's limit
+    environment
+        .new_session_builder()?
+        .with_memory_limit(LIMIT)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    let result = environment
+        .new_session_builder()?
+        .with_memory_limit(LIMIT / 2);
+    assert!(matches!(
+        result,
+        Err(OrtError::MemoryLimitConflict {
+            limit: LIMIT,
+            requested
+        }) if requested == LIMIT / 2
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn preprocessor_scales_inputs() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::{ndarray::IxDyn, preprocessor::Preprocessor};
+
+    #[derive(Debug)]
+    struct Scale;
+
+    impl Preprocessor for Scale {
+        type Input = u8;
+        type Output = f32;
+
+        fn preprocess(
+            &self,
+            inputs: Vec<Array<u8, IxDyn>>,
+        ) -> onnxruntime::Result<Vec<Array<f32, IxDyn>>> {
+            Ok(inputs
+                .into_iter()
+                .map(|input| input.mapv(|pixel| f32::from(pixel) / 255.0))
+                .collect())
+        }
+    }
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?
+        .with_preprocessor(Scale);
+    assert_eq!(session.inputs.len(), 1);
+
+    // The model adds its default y = [10, 20, 30] to the scaled x
+    let outputs = session.run(vec![array![255_u8, 0, 51]])?;
+    let output = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        output.view().to_owned(),
+        array![11.0_f32, 20.0, 51.0 / 255.0 + 30.0].into_dyn()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn run_map() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::tensor::InputTensor;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(MASKED_MODEL)?;
+    assert_eq!(session.inputs[0].name, "x");
+
+    // Given in the reverse order of the model's inputs, and of different element types
+    let mut inputs = HashMap::new();
+    inputs.insert("mask", InputTensor::from(array![true, false, true, false]));
+    inputs.insert("x", InputTensor::from(array![1.0_f32, 2.0, 3.0, 4.0]));
+    let outputs = session.run_map(inputs)?;
+    let output = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        output.view().to_owned(),
+        array![1.0_f32, 0.0, 3.0, 0.0].into_dyn()
+    );
+
+    let mut inputs = HashMap::new();
+    inputs.insert("x", InputTensor::from(array![1.0_f32, 2.0, 3.0, 4.0]));
+    assert!(matches!(
+        session.run_map(inputs.clone()),
+        Err(OrtError::MissingInput { name }) if name == "mask"
+    ));
+
+    inputs.insert("y", InputTensor::from(array![1.0_f32]));
+    assert!(matches!(
+        session.run_map(inputs.clone()),
+        Err(OrtError::UnknownInput { name }) if name == "y"
+    ));
+
+    inputs.remove("y");
+    inputs.insert("mask", InputTensor::from(array![1.0_f32, 0.0, 1.0, 0.0]));
+    assert!(matches!(
+        session.run_map(inputs),
+        Err(OrtError::DataTypeMismatch {
+            expected: TensorElementDataType::Bool,
+            actual: TensorElementDataType::Float,
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn output_memory_info() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let memory_info = outputs[0].memory_info();
+    assert!(memory_info.is_cpu()?);
+    assert_eq!(memory_info.name()?, "Cpu");
+    assert_eq!(memory_info.device_id()?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn run_with_retry() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::retry::RetryPolicy;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let policy = RetryPolicy::default();
+    let inputs = vec![array![1.0_f32, 2.0, 3.0]];
+    let outputs = session.run_with_retry(&inputs, &policy)?;
+    let output = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        output.view().to_owned(),
+        array![11.0_f32, 22.0, 33.0].into_dyn()
+    );
+
+    // Invalid inputs are not retried
+    let inputs = vec![array![1.0_f32, 2.0]];
+    assert!(session.run_with_retry(&inputs, &policy).is_err());
+
+    // A failing run is retried when its code is listed: the first attempt gets an input of the
+    // wrong shape, the second one the expected input
+    let policy = RetryPolicy {
+        backoff: Duration::from_millis(1),
+        retry_on: vec![OrtErrorCode::InvalidArgument],
+        ..RetryPolicy::default()
+    };
+    let mut attempts = 0;
+    let outputs = policy.run(|| {
+        attempts += 1;
+        if attempts == 1 {
+            session.run(vec![array![1.0_f32, 2.0]])
+        } else {
+            session.run(vec![array![1.0_f32, 2.0, 3.0]])
+        }
+    })?;
+    assert_eq!(attempts, 2);
+    let output = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        output.view().to_owned(),
+        array![11.0_f32, 22.0, 33.0].into_dyn()
+    );
+
+    // Attempts stop at the maximum, returning the last error
+    let mut attempts = 0;
+    let result = policy.run(|| {
+        attempts += 1;
+        session.run(vec![array![1.0_f32, 2.0]])
+    });
+    assert!(matches!(result, Err(OrtError::Run(_))));
+    assert_eq!(attempts, policy.max_attempts);
+
+    Ok(())
+}
+
+#[test]
+fn into_scalar() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(COUNTER_MODEL)?;
+    let outputs = session.run(vec![array![41_i64]])?;
+    assert_eq!(outputs[0].try_extract::<i64>()?.into_scalar()?, 42);
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    assert!(matches!(
+        outputs[0].try_extract::<f32>()?.into_scalar(),
+        Err(OrtError::NotScalar { shape }) if shape == vec![3]
+    ));
+
+    Ok(())
+}
+
+#[cfg(feature = "bundle")]
+#[test]
+fn model_bundle() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::bundle::ModelBundle;
+
+    let bundle = ModelBundle::from_dir(BUNDLE_DIR)?;
+    assert_eq!(bundle.config.layout, "nchw");
+
+    let environment = environment()?;
+    let session = bundle.load(environment.new_session_builder()?)?;
+
+    // Each channel c becomes (x * 0.5 - mean[c]) / std[c], then goes through a relu
+    let input = Array::from_elem((1, 3, 2, 2), 4.0_f32);
+    let outputs = session.run(vec![input])?;
+    let output = outputs[0].try_extract::<f32>()?;
+    let expected = Array::from_shape_fn((1, 3, 2, 2), |(_, c, _, _)| [1.0_f32, 1.0, 0.0][c]);
+    assert_eq!(output.view().to_owned(), expected.into_dyn());
+
+    assert!(matches!(
+        ModelBundle::from_dir("../test-models/onnx"),
+        Err(OrtError::FileDoesNotExists { .. })
+    ));
+
+    // The counter model's single input is an int64 tensor, which the preprocessing can't produce
+    let int64_dir = std::env::temp_dir().join("onnxruntime-int64-bundle");
+    std::fs::create_dir_all(&int64_dir)?;
+    std::fs::copy(COUNTER_MODEL, int64_dir.join("model.onnx"))?;
+    std::fs::copy(
+        std::path::Path::new(BUNDLE_DIR).join("preprocess.json"),
+        int64_dir.join("preprocess.json"),
+    )?;
+    let result = ModelBundle::from_dir(&int64_dir)?.load(environment.new_session_builder()?);
+    std::fs::remove_dir_all(&int64_dir)?;
+    assert!(matches!(
+        result,
+        Err(OrtError::DataTypeMismatch {
+            expected: TensorElementDataType::Float,
+            actual: TensorElementDataType::Int64,
+        })
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn profiling_start_time() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let temp_dir = std::env::temp_dir();
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    assert_eq!(session.profiling_start_time_ns()?, 0);
+
+    let session = environment
+        .new_session_builder()?
+        .with_profiling(temp_dir.join("onnxruntime-rs-profile"))?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    let start_time_ns = session.profiling_start_time_ns()?;
+    assert!(start_time_ns > 0);
+    session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    drop(session);
+
+    // The profile is written when the session is dropped
+    for entry in std::fs::read_dir(&temp_dir)? {
+        let path = entry?.path();
+        let is_profile = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("onnxruntime-rs-profile"));
+        if is_profile {
+            std::fs::remove_file(path)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn with_initializer() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+    let x = array![
+        [1.0_f32, 1.0, 1.0],
+        [1.0, 2.0, 3.0],
+        [0.0, 0.0, 0.0],
+        [2.0, 2.0, 2.0]
+    ];
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+    let outputs = session.run(vec![x.clone()])?;
+    let y = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        y.view().iter().cloned().collect::<Vec<_>>(),
+        vec![1.0, 2.0, 3.0, 1.0, 4.0, 9.0, 0.0, 0.0, 0.0, 2.0, 4.0, 6.0]
+    );
+
+    // The weights are swapped at load time
+    let w = OrtTensor::from_vec(vec![10.0_f32, 20.0, 30.0], &[3])?;
+    let session = environment
+        .new_session_builder()?
+        .with_initializer("w", w)?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+    let outputs = session.run(vec![x])?;
+    let y = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        y.view().iter().cloned().collect::<Vec<_>>(),
+        vec![10.0, 20.0, 30.0, 10.0, 40.0, 90.0, 0.0, 0.0, 0.0, 20.0, 40.0, 60.0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn with_copied_initializer() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let w = {
+        let source = array![[10.0_f32, 0.0], [20.0, 0.0], [30.0, 0.0]];
+        // A strided view of a source dropped before the session runs
+        OrtTensor::from_array_copied(&source.column(0))?
+    };
+    let session = environment
+        .new_session_builder()?
+        .with_initializer("w", w)?
+        .with_model_from_file(FIXED_BATCH_MODEL)?;
+    let x = array![
+        [1.0_f32, 1.0, 1.0],
+        [1.0, 2.0, 3.0],
+        [0.0, 0.0, 0.0],
+        [2.0, 2.0, 2.0]
+    ];
+    let outputs = session.run(vec![x])?;
+    let y = outputs[0].try_extract::<f32>()?;
+    assert_eq!(
+        y.view().iter().cloned().collect::<Vec<_>>(),
+        vec![10.0, 20.0, 30.0, 10.0, 40.0, 90.0, 0.0, 0.0, 0.0, 20.0, 40.0, 60.0]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn auto_cast() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_auto_cast(true)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    // The model expects `float` inputs: the `f64` array is cast
+    let outputs = session.run(vec![array![1.0_f64, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    // Rounding to `f32` only logs a warning by default...
+    let outputs = session.run(vec![array![1.0_f64 + 1e-12, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    // ...but can be an error
+    let session = environment
+        .new_session_builder()?
+        .with_auto_cast(true)?
+        .with_lossy_cast_policy(LossyCastPolicy::Error)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    assert!(matches!(
+        session.run(vec![array![1.0_f64 + 1e-12, 2.0, 3.0]]),
+        Err(OrtError::LossyCast {
+            from: TensorElementDataType::Double,
+            to: TensorElementDataType::Float,
+            changed: 1,
+            ..
+        })
+    ));
+
+    // Strings are never cast
+    assert!(matches!(
+        session.run(vec![array![
+            String::from("1"),
+            String::from("2"),
+            String::from("3")
+        ]]),
+        Err(OrtError::DataTypeMismatch { .. })
+    ));
+
+    // Without auto-cast, mismatched inputs are rejected by ONNX Runtime
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    assert!(session.run(vec![array![1.0_f64, 2.0, 3.0]]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn run_with_timeout() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs =
+        session.run_with_timeout(vec![array![1.0_f32, 2.0, 3.0]], Duration::from_secs(60))?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    // The model is too small to reliably time out: the run either completes or is terminated
+    match session.run_with_timeout(vec![array![1.0_f32, 2.0, 3.0]], Duration::from_nanos(0)) {
+        Ok(outputs) => assert_eq!(outputs.len(), 1),
+        Err(e) => assert!(matches!(e, OrtError::Timeout(_)), "{:?}", e),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn run_terminated() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    // What the watchdog of `run_with_timeout()` does once the deadline passes
+    let run_options = RunOptions::new()?;
+    run_options.set_terminate()?;
+    assert!(matches!(
+        session.run_with_options(vec![array![1.0_f32, 2.0, 3.0]], &run_options),
+        Err(OrtError::Run(_))
+    ));
+
+    run_options.unset_terminate()?;
+    let outputs = session.run_with_options(vec![array![1.0_f32, 2.0, 3.0]], &run_options)?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn uncompressed_model_from_compressed_file() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_compressed_file(OPTIONAL_INPUT_MODEL)?;
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_model_ops() -> Result<(), Box<dyn Error>> {
+    let model_bytes = std::fs::read(CONV_RELU_MODEL)?;
+    let ops = onnxruntime::inspect_model_ops(&model_bytes)?;
+    let mut ops: Vec<_> = ops.into_iter().collect();
+    ops.sort();
+    assert_eq!(
+        ops,
+        vec![
+            (String::new(), String::from("Conv")),
+            (String::new(), String::from("Relu"))
+        ]
+    );
+
+    assert!(matches!(
+        onnxruntime::inspect_model_ops(&[0x0a, 0xff]),
+        Err(OrtError::ModelRead(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn run_with_cancellation() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let token = CancellationToken::new();
+    let outputs = session.run_with_cancellation(vec![array![1.0_f32, 2.0, 3.0]], &token)?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    // Cancel from another thread while runs are in progress: the current run is either terminated
+    // or completes, and the next one fails right away
+    let canceller = {
+        let token = token.clone();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(10));
+            token.cancel();
+        })
+    };
+    let error = loop {
+        match session.run_with_cancellation(vec![array![1.0_f32, 2.0, 3.0]], &token) {
+            Ok(_) => continue,
+            Err(e) => break e,
+        }
+    };
+    canceller.join().unwrap();
+    assert!(matches!(error, OrtError::Cancelled), "{:?}", error);
+    assert!(token.is_cancelled());
+
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn run_async() -> Result<(), Box<dyn Error>> {
+    // The session is moved to tokio's blocking threads: it can't borrow a local environment
+    let environment: &'static Environment = Box::leak(Box::new(environment()?));
+    let session = std::sync::Arc::new(
+        environment
+            .new_session_builder()?
+            .with_model_from_file(OPTIONAL_INPUT_MODEL)?,
+    );
+
+    let token = CancellationToken::new();
+    let z = session
+        .clone()
+        .run_async(vec![array![1.0_f32, 2.0, 3.0]], &token, |outputs| {
+            Ok(outputs[0].try_extract::<f32>()?.view().to_owned())
+        })?
+        .await?;
+    assert_eq!(z, array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    // Cancel while runs are in flight: the current run is either terminated or completes, and the
+    // next one fails right away
+    let canceller = {
+        let token = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            token.cancel();
+        })
+    };
+    let error = loop {
+        let run = session
+            .clone()
+            .run_async(vec![array![1.0_f32, 2.0, 3.0]], &token, |_| Ok(()))?;
+        match run.await {
+            Ok(()) => continue,
+            Err(e) => break e,
+        }
+    };
+    canceller.await?;
+    assert!(matches!(error, OrtError::Cancelled), "{:?}", error);
+
+    Ok(())
+}
+
+#[cfg(feature = "openvino")]
+#[test]
+#[ignore]
+fn openvino() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::session::OpenVinoOptions;
+
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_openvino(OpenVinoOptions {
+            device_type: String::from("CPU"),
+            precision: Some(String::from("FP32")),
+            ..Default::default()
+        })?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+#[ignore]
+fn cuda() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::session::CudaProviderOptions;
+
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_cuda(CudaProviderOptions::default())?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+// Requires at least 2 GPUs
+#[cfg(feature = "cuda")]
+#[test]
+#[ignore]
+fn cuda_device_selection() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::session::CudaProviderOptions;
+
+    let environment = environment()?;
+    assert!(onnxruntime::cuda_device_count()? >= 2);
+
+    for device_id in 0..2 {
+        let session = environment
+            .new_session_builder()?
+            .with_cuda(CudaProviderOptions {
+                device_id,
+                ..Default::default()
+            })?
+            .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+        let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+        let z = outputs[0].try_extract::<f32>()?;
+        assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "cuda")]
+#[test]
+#[ignore]
+fn cuda_bind_output_to_ptr() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::{memory::MemoryInfo, session::CudaProviderOptions};
+    use std::os::raw::{c_int, c_void};
+
+    #[link(name = "cudart")]
+    extern "C" {
+        fn cudaMalloc(ptr: *mut *mut c_void, size: usize) -> c_int;
+        fn cudaMemcpy(dst: *mut c_void, src: *const c_void, count: usize, kind: c_int) -> c_int;
+        fn cudaFree(ptr: *mut c_void) -> c_int;
+    }
+    const CUDA_MEMCPY_DEVICE_TO_HOST: c_int = 2;
+
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_cuda(CudaProviderOptions::default())?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let size = 3 * std::mem::size_of::<f32>();
+    let mut device_ptr: *mut c_void = std::ptr::null_mut();
+    assert_eq!(unsafe { cudaMalloc(&mut device_ptr, size) }, 0);
+
+    let memory_info = MemoryInfo::for_device("Cuda", 0)?;
+    let mut binding = session.io_binding()?;
+    binding.bind_input("x", array![1.0_f32, 2.0, 3.0])?;
+    unsafe {
+        binding.bind_output_to_ptr(
+            "z",
+            &memory_info,
+            device_ptr,
+            &[3],
+            TensorElementDataType::Float,
+        )?;
+    }
+    session.run_with_binding(&binding)?;
+    drop(binding);
+
+    let mut z = vec![0.0_f32; 3];
+    let copied = unsafe {
+        cudaMemcpy(
+            z.as_mut_ptr() as *mut c_void,
+            device_ptr,
+            size,
+            CUDA_MEMCPY_DEVICE_TO_HOST,
+        )
+    };
+    unsafe { cudaFree(device_ptr) };
+    assert_eq!(copied, 0);
+    assert_eq!(z, vec![11.0, 22.0, 33.0]);
+
+    Ok(())
+}
+
+// Uses the custom ops library of ONNX Runtime's tests, built along ONNX Runtime
+// (`libcustom_op_library.so`, `custom_op_library.dll` on Windows) and its model
+// (`onnxruntime/test/testdata/custom_op_library/custom_op_test.onnx`), which adds its two inputs
+// with a custom operator and casts the sum to `int32` with another one. Their paths are given with
+// the `CUSTOM_OP_LIBRARY` and `CUSTOM_OP_MODEL` environment variables.
+#[test]
+#[ignore]
+fn custom_ops_library() -> Result<(), Box<dyn Error>> {
+    let library_path = std::env::var("CUSTOM_OP_LIBRARY")?;
+    let model_path = std::env::var("CUSTOM_OP_MODEL")?;
+
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_custom_ops_library(library_path)?
+        .with_model_from_file(model_path)?;
+
+    let outputs = session.run(vec![
+        Array::from_elem((3, 5), 1.0_f32),
+        Array::from_elem((3, 5), 2.0_f32),
+    ])?;
+    let output = outputs[0].try_extract::<i32>()?;
+    assert_eq!(
+        output.view().to_owned(),
+        Array::from_elem((3, 5), 3).into_dyn()
+    );
+
+    Ok(())
+}
+
+#[cfg(all(feature = "acl", target_arch = "aarch64"))]
+#[test]
+#[ignore]
+fn acl() -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_acl(true)?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().as_slice(), Some(&[11.0_f32, 22.0, 33.0][..]));
+
+    Ok(())
+}
+
+// ONNX Runtime 1.6 doesn't implement complex tensors
+#[cfg(feature = "complex")]
+#[test]
+#[ignore]
+fn complex_round_trip() -> Result<(), Box<dyn Error>> {
+    use onnxruntime::num_complex::Complex;
+
+    let environment = environment()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(COMPLEX_IDENTITY_MODEL)?;
+    assert_eq!(
+        session.inputs[0].input_type,
+        TensorElementDataType::Complex64
+    );
+
+    let x = array![Complex::new(1.0_f32, -1.0), Complex::new(0.5, 2.0)];
+    let outputs = session.run(vec![x.clone()])?;
+    let y = outputs[0].try_extract::<Complex<f32>>()?;
+    assert_eq!(y.view().to_owned(), x.into_dyn());
+
+    Ok(())
+}
+
+/// Load `compressed_model` and check it behaves as the uncompressed model
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+fn assert_same_as_uncompressed(compressed_model: &str) -> Result<(), Box<dyn Error>> {
+    let environment = environment()?;
+
+    let uncompressed = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+    let from_file = environment
+        .new_session_builder()?
+        .with_model_from_compressed_file(compressed_model)?;
+    let from_reader = environment
+        .new_session_builder()?
+        .with_model_from_compressed_reader(std::fs::File::open(compressed_model)?)?;
+
+    let expected = uncompressed.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    let expected = expected[0].try_extract::<f32>()?;
+    for session in &[from_file, from_reader] {
+        assert_eq!(session.inputs[0].name, uncompressed.inputs[0].name);
+        assert_eq!(session.outputs[0].name, uncompressed.outputs[0].name);
+        let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+        let z = outputs[0].try_extract::<f32>()?;
+        assert_eq!(z.view().as_slice(), expected.view().as_slice());
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn gzip_compressed_model() -> Result<(), Box<dyn Error>> {
+    assert_same_as_uncompressed(GZIP_MODEL)
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_compressed_model() -> Result<(), Box<dyn Error>> {
+    assert_same_as_uncompressed(ZSTD_MODEL)
+}
@@ -1,7 +1,7 @@
 use std::error::Error;
 
 use ndarray;
-use onnxruntime::tensor::{OrtOwnedTensor, TensorElementDataType};
+use onnxruntime::tensor::{OrtOwnedTensor, TensorElementDataType, ValueType};
 use onnxruntime::{environment::Environment, tensor::DynOrtTensor, LoggingLevel};
 
 #[test]
@@ -11,7 +11,7 @@ fn run_model_with_string_1d_input_output() -> Result<(), Box<dyn Error>> {
         .with_log_level(LoggingLevel::Verbose)
         .build()?;
 
-    let mut session = environment
+    let session = environment
         .new_session_builder()?
         .with_model_from_file("../test-models/tensorflow/unique_model.onnx")?;
 
@@ -37,6 +37,8 @@ fn run_model_with_string_1d_input_output() -> Result<(), Box<dyn Error>> {
 
     assert_eq!(TensorElementDataType::Int32, outputs[0].data_type());
     assert_eq!(TensorElementDataType::String, outputs[1].data_type());
+    assert_eq!(ValueType::Tensor, outputs[0].value_type());
+    assert_eq!(ValueType::Tensor, outputs[1].value_type());
 
     let int_output: OrtOwnedTensor<i32, _> = outputs[0].try_extract()?;
     let string_output: OrtOwnedTensor<String, _> = outputs[1].try_extract()?;
@@ -53,5 +55,44 @@ fn run_model_with_string_1d_input_output() -> Result<(), Box<dyn Error>> {
         string_output.view().as_slice().unwrap()
     );
 
+    let string_views = outputs[1].try_extract_string_views()?;
+    let views = string_views.view();
+    assert_eq!(&[3], views.shape());
+    assert_eq!(string_output.view().map(|s| s.as_str()), views);
+    assert_eq!("foobarbaz", string_views.buffer());
+
+    Ok(())
+}
+
+#[test]
+fn array_cow_of_primitive_and_string_outputs() -> Result<(), Box<dyn Error>> {
+    let environment = Environment::builder()
+        .with_name("test")
+        .with_log_level(LoggingLevel::Warning)
+        .build()?;
+
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file("../test-models/tensorflow/unique_model.onnx")?;
+
+    let array = ndarray::Array::from(vec!["foo", "bar", "foo", "foo", "baz"]);
+    let outputs: Vec<DynOrtTensor<_>> = session.run(vec![array])?;
+
+    // Primitives are borrowed from ONNX Runtime's memory...
+    let int_output: OrtOwnedTensor<i32, _> = outputs[0].try_extract()?;
+    let int_cow = int_output.as_array_cow();
+    assert!(int_cow.is_view());
+    assert_eq!(int_cow.as_slice(), Some(&[0, 1, 0, 0, 2][..]));
+
+    // ...while strings are owned
+    let string_output: OrtOwnedTensor<String, _> = outputs[1].try_extract()?;
+    let string_cow = string_output.as_array_cow();
+    assert!(string_cow.is_owned());
+    assert_eq!(string_cow.shape(), &[3]);
+    assert_eq!(
+        string_cow.into_owned().into_raw_vec(),
+        vec!["foo", "bar", "baz"]
+    );
+
     Ok(())
 }
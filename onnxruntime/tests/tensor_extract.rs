@@ -0,0 +1,45 @@
+mod common;
+
+use onnxruntime::{ndarray::array, tensor::TensorElementDataType, OrtError, Result};
+
+use common::environment;
+
+const OPTIONAL_INPUT_MODEL: &str = "../test-models/onnx/optional_input.onnx";
+
+#[test]
+fn extract_matching_type() -> Result<()> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+    assert_eq!(outputs[0].data_type(), TensorElementDataType::Float);
+
+    // Extraction errors convert into `OrtError`
+    let z = outputs[0].try_extract::<f32>()?;
+    assert_eq!(z.view().to_owned(), array![11.0_f32, 22.0, 33.0].into_dyn());
+
+    Ok(())
+}
+
+#[test]
+fn extract_mismatching_type() -> Result<()> {
+    let environment = environment()?;
+    let session = environment
+        .new_session_builder()?
+        .with_model_from_file(OPTIONAL_INPUT_MODEL)?;
+
+    let outputs = session.run(vec![array![1.0_f32, 2.0, 3.0]])?;
+
+    let result: Result<_> = outputs[0].try_extract::<i64>().map_err(OrtError::from);
+    assert!(matches!(
+        result,
+        Err(OrtError::DataTypeMismatch {
+            expected: TensorElementDataType::Int64,
+            actual: TensorElementDataType::Float,
+        })
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+//! Compile-time checks of the thread safety of the public types
+
+use onnxruntime::{
+    allocator::OrtAllocator,
+    environment::Environment,
+    metadata::ModelMetadata,
+    ndarray::{Ix2, IxDyn},
+    session::{Session, SessionBuilder},
+    tensor::{DynOrtTensor, MapOutput, OrtOwnedTensor, OrtTensor, SequenceOutput, StringViews},
+};
+
+fn assert_send<T: Send>() {}
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn send_and_sync() {
+    assert_send::<Environment>();
+    assert_sync::<Environment>();
+
+    assert_send::<Session<'static>>();
+    assert_sync::<Session<'static>>();
+    assert_send::<SessionBuilder<'static>>();
+
+    assert_send::<ModelMetadata>();
+    assert_sync::<ModelMetadata>();
+    assert_send::<OrtAllocator>();
+    assert_sync::<OrtAllocator>();
+
+    assert_send::<OrtTensor<'static, f32, Ix2>>();
+    assert_sync::<OrtTensor<'static, f32, Ix2>>();
+
+    // Outputs can be moved to another thread, e.g. across `.await` points in async tasks
+    assert_send::<DynOrtTensor<'static, IxDyn>>();
+    assert_sync::<DynOrtTensor<'static, IxDyn>>();
+    assert_send::<OrtOwnedTensor<'static, f32, IxDyn>>();
+    assert_sync::<OrtOwnedTensor<'static, f32, IxDyn>>();
+    assert_send::<OrtOwnedTensor<'static, String, IxDyn>>();
+    assert_send::<SequenceOutput<'static>>();
+    assert_send::<MapOutput<i64, f32>>();
+    assert_send::<StringViews<IxDyn>>();
+}
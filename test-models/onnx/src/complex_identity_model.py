@@ -0,0 +1,20 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = x` on a `complex64` tensor
+graph = helper.make_graph(
+    [helper.make_node("Identity", inputs=["x"], outputs=["y"], name="identity")],
+    "complex_identity",
+    [helper.make_tensor_value_info("x", TensorProto.COMPLEX64, [2])],
+    [helper.make_tensor_value_info("y", TensorProto.COMPLEX64, [2])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 13)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "complex_identity.onnx")
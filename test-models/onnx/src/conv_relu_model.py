@@ -0,0 +1,28 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = relu(conv(x, w))`, where `w` is a 3x3 identity kernel
+graph = helper.make_graph(
+    [
+        helper.make_node("Conv", inputs=["x", "w"], outputs=["c"], name="conv", pads=[1, 1, 1, 1]),
+        helper.make_node("Relu", inputs=["c"], outputs=["y"], name="relu"),
+    ],
+    "conv_relu",
+    [helper.make_tensor_value_info("x", TensorProto.FLOAT, [1, 1, 4, 4])],
+    [helper.make_tensor_value_info("y", TensorProto.FLOAT, [1, 1, 4, 4])],
+    initializer=[
+        helper.make_tensor(
+            "w", TensorProto.FLOAT, [1, 1, 3, 3], [0, 0, 0, 0, 1, 0, 0, 0, 0]
+        )
+    ],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "conv_relu.onnx")
@@ -0,0 +1,21 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `next = count + 1`: a trivial stateful model, whose output is fed back as its next input
+graph = helper.make_graph(
+    [helper.make_node("Add", inputs=["count", "one"], outputs=["next"], name="add")],
+    "counter",
+    [helper.make_tensor_value_info("count", TensorProto.INT64, [1])],
+    [helper.make_tensor_value_info("next", TensorProto.INT64, [1])],
+    initializer=[helper.make_tensor("one", TensorProto.INT64, [1], [1])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "counter.onnx")
@@ -0,0 +1,28 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = x`, with preprocessing configuration stored in the model's custom metadata
+graph = helper.make_graph(
+    [helper.make_node("Identity", inputs=["x"], outputs=["y"], name="identity")],
+    "custom_metadata",
+    [helper.make_tensor_value_info("x", TensorProto.FLOAT, [3])],
+    [helper.make_tensor_value_info("y", TensorProto.FLOAT, [3])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+onnx.helper.set_model_props(
+    model,
+    {
+        "mean": "[0.485, 0.456, 0.406]",
+        "std": "[0.229, 0.224, 0.225]",
+        "labels": "cat,dog",
+    },
+)
+
+onnx.checker.check_model(model)
+onnx.save(model, "custom_metadata.onnx")
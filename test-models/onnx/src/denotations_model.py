@@ -0,0 +1,27 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = relu(x)` on an image batch whose input dimensions carry denotations
+x = helper.make_tensor_value_info("x", TensorProto.FLOAT, ["batch", 3, 2, 2])
+for dim, denotation in zip(
+    x.type.tensor_type.shape.dim, ["DATA_BATCH", "DATA_CHANNEL", "DATA_FEATURE", None]
+):
+    if denotation:
+        dim.denotation = denotation
+
+graph = helper.make_graph(
+    [helper.make_node("Relu", inputs=["x"], outputs=["y"], name="relu")],
+    "denotations",
+    [x],
+    [helper.make_tensor_value_info("y", TensorProto.FLOAT, ["batch", 3, 2, 2])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "denotations.onnx")
@@ -0,0 +1,21 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = x * w`, with a fixed batch dimension of 4 and `w = [1, 2, 3]` broadcast over the batch
+graph = helper.make_graph(
+    [helper.make_node("Mul", inputs=["x", "w"], outputs=["y"], name="mul")],
+    "fixed_batch",
+    [helper.make_tensor_value_info("x", TensorProto.FLOAT, [4, 3])],
+    [helper.make_tensor_value_info("y", TensorProto.FLOAT, [4, 3])],
+    initializer=[helper.make_tensor("w", TensorProto.FLOAT, [3], [1.0, 2.0, 3.0])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "fixed_batch.onnx")
@@ -0,0 +1,20 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `inverted = not mask`, on boolean tensors
+graph = helper.make_graph(
+    [helper.make_node("Not", inputs=["mask"], outputs=["inverted"], name="not")],
+    "mask",
+    [helper.make_tensor_value_info("mask", TensorProto.BOOL, [4])],
+    [helper.make_tensor_value_info("inverted", TensorProto.BOOL, [4])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "mask.onnx")
@@ -0,0 +1,28 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = x * mask`, the boolean mask being cast to float: the inputs have different element types
+graph = helper.make_graph(
+    [
+        helper.make_node(
+            "Cast", inputs=["mask"], outputs=["mask_float"], name="cast", to=TensorProto.FLOAT
+        ),
+        helper.make_node("Mul", inputs=["x", "mask_float"], outputs=["y"], name="mul"),
+    ],
+    "masked",
+    [
+        helper.make_tensor_value_info("x", TensorProto.FLOAT, [4]),
+        helper.make_tensor_value_info("mask", TensorProto.BOOL, [4]),
+    ],
+    [helper.make_tensor_value_info("y", TensorProto.FLOAT, [4])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "masked.onnx")
@@ -0,0 +1,25 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `z = x + y`, where `y` is an initializer also declared as a graph input: it defaults to
+# `[10, 20, 30]` but can be overridden at inference time.
+graph = helper.make_graph(
+    [helper.make_node("Add", inputs=["x", "y"], outputs=["z"], name="add")],
+    "optional_input",
+    [
+        helper.make_tensor_value_info("x", TensorProto.FLOAT, [3]),
+        helper.make_tensor_value_info("y", TensorProto.FLOAT, [3]),
+    ],
+    [helper.make_tensor_value_info("z", TensorProto.FLOAT, [3])],
+    initializer=[helper.make_tensor("y", TensorProto.FLOAT, [3], [10.0, 20.0, 30.0])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "optional_input.onnx")
@@ -0,0 +1,30 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `parts = split_to_sequence(x, [1, 3])`: a sequence of two tensors of different shapes
+graph = helper.make_graph(
+    [
+        helper.make_node(
+            "SplitToSequence", inputs=["x", "split"], outputs=["parts"], name="split_to_sequence"
+        )
+    ],
+    "ragged_sequence",
+    [helper.make_tensor_value_info("x", TensorProto.FLOAT, [4])],
+    [
+        helper.make_value_info(
+            "parts",
+            helper.make_sequence_type_proto(helper.make_tensor_type_proto(TensorProto.FLOAT, None)),
+        )
+    ],
+    initializer=[helper.make_tensor("split", TensorProto.INT64, [2], [1, 3])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "ragged_sequence.onnx")
@@ -0,0 +1,28 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = relu(x)` and `total = sum(x)`: `y` has a dynamic batch dimension, `total` a static shape
+graph = helper.make_graph(
+    [
+        helper.make_node("Relu", inputs=["x"], outputs=["y"], name="relu"),
+        helper.make_node(
+            "ReduceSum", inputs=["x"], outputs=["total"], name="sum", axes=[0, 1], keepdims=1
+        ),
+    ],
+    "static_and_dynamic_outputs",
+    [helper.make_tensor_value_info("x", TensorProto.FLOAT, ["batch", 3])],
+    [
+        helper.make_tensor_value_info("y", TensorProto.FLOAT, ["batch", 3]),
+        helper.make_tensor_value_info("total", TensorProto.FLOAT, [1, 1]),
+    ],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "static_and_dynamic_outputs.onnx")
@@ -0,0 +1,29 @@
+import onnx
+from onnx import TensorProto, helper
+
+# Stub of a transformer's inputs: `output = input_ids * attention_mask + token_type_ids`, where
+# `token_type_ids` is optional (it defaults to `[[0]]`, broadcast over the sequence)
+graph = helper.make_graph(
+    [
+        helper.make_node("Mul", inputs=["input_ids", "attention_mask"], outputs=["masked"], name="mul"),
+        helper.make_node("Add", inputs=["masked", "token_type_ids"], outputs=["output"], name="add"),
+    ],
+    "token_ids",
+    [
+        helper.make_tensor_value_info("input_ids", TensorProto.INT64, [1, "sequence"]),
+        helper.make_tensor_value_info("attention_mask", TensorProto.INT64, [1, "sequence"]),
+        helper.make_tensor_value_info("token_type_ids", TensorProto.INT64, [1, "token_types"]),
+    ],
+    [helper.make_tensor_value_info("output", TensorProto.INT64, [1, "sequence"])],
+    initializer=[helper.make_tensor("token_type_ids", TensorProto.INT64, [1, 1], [0])],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "token_ids.onnx")
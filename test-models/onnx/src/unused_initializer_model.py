@@ -0,0 +1,24 @@
+import onnx
+from onnx import TensorProto, helper
+
+# `y = x + 1`, with an initializer no node uses: ONNX Runtime warns about it while loading the model
+graph = helper.make_graph(
+    [helper.make_node("Add", inputs=["x", "one"], outputs=["y"], name="add")],
+    "unused_initializer",
+    [helper.make_tensor_value_info("x", TensorProto.FLOAT, [3])],
+    [helper.make_tensor_value_info("y", TensorProto.FLOAT, [3])],
+    initializer=[
+        helper.make_tensor("one", TensorProto.FLOAT, [1], [1.0]),
+        helper.make_tensor("unused", TensorProto.FLOAT, [1], [2.0]),
+    ],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "unused_initializer.onnx")
@@ -0,0 +1,53 @@
+import onnx
+from onnx import TensorProto, helper
+
+# A binary classifier equivalent to what skl2onnx produces for a scikit-learn LogisticRegression,
+# with hand-picked weights so that the outputs are easy to predict: the probability of each class
+# is the corresponding input feature.
+classifier = helper.make_node(
+    "LinearClassifier",
+    inputs=["float_input"],
+    outputs=["output_label", "probability_tensor"],
+    name="LinearClassifier",
+    domain="ai.onnx.ml",
+    classlabels_ints=[0, 1],
+    coefficients=[1.0, 0.0, 0.0, 1.0],
+    intercepts=[0.0, 0.0],
+    multi_class=0,
+    post_transform="NONE",
+)
+zipmap = helper.make_node(
+    "ZipMap",
+    inputs=["probability_tensor"],
+    outputs=["output_probability"],
+    name="ZipMap",
+    domain="ai.onnx.ml",
+    classlabels_int64s=[0, 1],
+)
+
+graph = helper.make_graph(
+    [classifier, zipmap],
+    "logistic_regression",
+    [helper.make_tensor_value_info("float_input", TensorProto.FLOAT, [None, 2])],
+    [
+        helper.make_tensor_value_info("output_label", TensorProto.INT64, [None]),
+        helper.make_value_info(
+            "output_probability",
+            helper.make_sequence_type_proto(
+                helper.make_map_type_proto(
+                    TensorProto.INT64, helper.make_tensor_type_proto(TensorProto.FLOAT, None)
+                )
+            ),
+        ),
+    ],
+)
+
+model = helper.make_model(
+    graph,
+    producer_name="onnxruntime-rs",
+    opset_imports=[helper.make_opsetid("", 12), helper.make_opsetid("ai.onnx.ml", 1)],
+)
+model.ir_version = 7
+
+onnx.checker.check_model(model)
+onnx.save(model, "logistic_regression.onnx")